@@ -0,0 +1,208 @@
+use crate::puzzle_backend;
+use crate::word_list;
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[derive(Clone, Debug)]
+pub enum FillOutcome {
+    // Letters to place, as (square index, letter) pairs for every square that was empty.
+    Filled(Vec<(usize, char)>),
+    NoSolution,
+    Cancelled,
+}
+
+struct Slot {
+    squares: Vec<usize>,
+}
+
+// A snapshot of the grid that can be solved away from the UI thread.
+pub struct FillProblem {
+    slots: Vec<Slot>,
+    // For each square, the (slot, position) pairs it belongs to.
+    crossings: Vec<Vec<(usize, usize)>>,
+    cells: Vec<Option<u8>>,
+    // Answers already complete in the grid, which may not be used again.
+    existing_answers: HashSet<Vec<u8>>,
+}
+
+impl FillProblem {
+    pub fn new(puzzle: &puzzle_backend::Puzzle) -> Self {
+        let mut cells: Vec<Option<u8>> = Vec::with_capacity(puzzle.squares.len());
+        let mut rebus: Vec<bool> = Vec::with_capacity(puzzle.squares.len());
+        for sq in puzzle.squares.iter() {
+            match &sq.content {
+                puzzle_backend::SquareContents::TextContent(s,_m) if s.chars().count() == 1 => {
+                    cells.push(s.to_uppercase().bytes().next());
+                    rebus.push(false);
+                },
+                puzzle_backend::SquareContents::TextContent(s,_m) => {
+                    cells.push(None);
+                    rebus.push(!s.is_empty());
+                },
+                puzzle_backend::SquareContents::Blocker => {
+                    cells.push(None);
+                    rebus.push(false);
+                },
+            }
+        }
+
+        let mut slots: Vec<Slot> = Vec::new();
+        let mut existing_answers: HashSet<Vec<u8>> = HashSet::new();
+        for entry in puzzle.across_entries.iter().chain(puzzle.down_entries.iter()) {
+            // Entries through rebus squares are left as the constructor wrote them.
+            if entry.member_indices.iter().any(|i| rebus[*i]) {
+                continue;
+            }
+            // A lone unchecked square is no word at all, so only its other entry constrains it.
+            if entry.member_indices.len() < 2 {
+                continue;
+            }
+            if entry.member_indices.iter().all(|i| cells[*i].is_some()) {
+                existing_answers.insert(entry.member_indices.iter().map(|i| cells[*i].unwrap()).collect());
+                continue;
+            }
+            slots.push(Slot { squares: entry.member_indices.clone() });
+        }
+
+        let mut crossings: Vec<Vec<(usize, usize)>> = vec![Vec::new(); puzzle.squares.len()];
+        for (slot_index, slot) in slots.iter().enumerate() {
+            for (pos, sq) in slot.squares.iter().enumerate() {
+                crossings[*sq].push((slot_index, pos));
+            }
+        }
+
+        FillProblem {
+            slots,
+            crossings,
+            cells,
+            existing_answers,
+        }
+    }
+
+    pub fn solve(&self, words: &word_list::WordList, cancel: &AtomicBool) -> FillOutcome {
+        let mut domains: Vec<Vec<u32>> = Vec::with_capacity(self.slots.len());
+        for slot in self.slots.iter() {
+            let pattern: Vec<Option<u8>> = slot.squares.iter().map(|sq| self.cells[*sq]).collect();
+            let d: Vec<u32> = words.matching(&pattern).into_iter()
+                .filter(|w| !self.existing_answers.contains(words.letters(*w)))
+                .collect();
+            domains.push(d);
+        }
+
+        let mut search = Search {
+            problem: self,
+            words,
+            cells: self.cells.clone(),
+            assigned: vec![false; self.slots.len()],
+            used: HashSet::new(),
+            cancel,
+        };
+        match search.run(&mut domains) {
+            Some(true) => {
+                let mut fill = Vec::new();
+                for (index, c) in search.cells.iter().enumerate() {
+                    if let (None, Some(letter)) = (self.cells[index], c) {
+                        fill.push((index, *letter as char));
+                    }
+                }
+                FillOutcome::Filled(fill)
+            },
+            Some(false) => FillOutcome::NoSolution,
+            None => FillOutcome::Cancelled,
+        }
+    }
+}
+
+struct Search<'a> {
+    problem: &'a FillProblem,
+    words: &'a word_list::WordList,
+    cells: Vec<Option<u8>>,
+    assigned: Vec<bool>,
+    used: HashSet<u32>,
+    cancel: &'a AtomicBool,
+}
+
+impl<'a> Search<'a> {
+    // Depth-first search that always branches on the open slot with the fewest
+    // candidates and prunes crossing slots after every placement. Returns None
+    // if cancelled.
+    fn run(&mut self, domains: &mut Vec<Vec<u32>>) -> Option<bool> {
+        if self.cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        let next = (0..self.problem.slots.len())
+            .filter(|s| !self.assigned[*s])
+            .min_by_key(|s| domains[*s].len());
+        let slot_index = match next {
+            Some(s) => s,
+            None => { return Some(true); },
+        };
+
+        let problem = self.problem;
+        let words = self.words;
+        let options = domains[slot_index].clone();
+        for w in options {
+            let word = words.letters(w);
+            if self.used.contains(&w) {
+                continue;
+            }
+
+            // Place the word, remembering which cells and domains changed.
+            let squares = &problem.slots[slot_index].squares;
+            let mut placed: Vec<usize> = Vec::new();
+            for (sq, c) in squares.iter().zip(word.iter()) {
+                if self.cells[*sq].is_none() {
+                    self.cells[*sq] = Some(*c);
+                    placed.push(*sq);
+                }
+            }
+            let mut saved: Vec<(usize, Vec<u32>)> = Vec::new();
+            let mut dead_end = false;
+            for sq in placed.iter() {
+                for (other, pos) in problem.crossings[*sq].iter() {
+                    if *other == slot_index || self.assigned[*other] {
+                        continue;
+                    }
+                    let letter = self.cells[*sq].unwrap();
+                    let pruned: Vec<u32> = domains[*other].iter().cloned()
+                        .filter(|o| words.letters(*o)[*pos] == letter)
+                        .collect();
+                    if pruned.len() != domains[*other].len() {
+                        saved.push((*other, std::mem::replace(&mut domains[*other], pruned)));
+                    }
+                    if domains[*other].is_empty() {
+                        dead_end = true;
+                        break;
+                    }
+                }
+                if dead_end {
+                    break;
+                }
+            }
+
+            if !dead_end {
+                self.assigned[slot_index] = true;
+                self.used.insert(w);
+                let result = self.run(domains);
+                self.assigned[slot_index] = false;
+                self.used.remove(&w);
+                match result {
+                    Some(true) => { return Some(true); },
+                    None => { return None; },
+                    Some(false) => {},
+                }
+            }
+
+            for (other, d) in saved.into_iter().rev() {
+                domains[other] = d;
+            }
+            for sq in placed {
+                self.cells[sq] = None;
+            }
+        }
+
+        Some(false)
+    }
+}
@@ -0,0 +1,179 @@
+use crate::puzzle_backend;
+use crate::puzzle_file_io;
+
+use serde_json::json;
+use std::collections::BTreeMap;
+
+// Every clue written for each answer, gathered from saved and imported puzzles
+// so old clues can be found and reused. The whole database is one JSON file:
+// {"answers": {"HEART": [{"clue": ..., "source": ..., "date": ...}]}}.
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ClueRecord {
+    pub clue: String,
+    // The file the clue was first seen in, and that puzzle's date if it had one.
+    pub source: String,
+    pub date: String,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ClueDatabase {
+    answers: BTreeMap<String, Vec<ClueRecord>>,
+}
+
+// What a directory import found.
+#[derive(Clone, Debug, Default)]
+pub struct ImportSummary {
+    pub files: usize,
+    pub new_clues: usize,
+    // Files that couldn't be read, with why.
+    pub failed: Vec<(String, String)>,
+}
+
+impl ClueDatabase {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    // A missing file is an empty database.
+    pub fn load(path: &std::path::Path) -> std::result::Result<Self, String> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => { return Ok(ClueDatabase::new()); },
+            Err(e) => { return Err(e.to_string()); },
+        };
+        let value: serde_json::Value = serde_json::from_str(&contents).map_err(|e| e.to_string())?;
+        let answers = match &value["answers"] {
+            serde_json::Value::Object(m) => m,
+            _ => { return Err("Not a clue database.".to_string()); },
+        };
+        let mut db = ClueDatabase::new();
+        for (answer, records) in answers.iter() {
+            for r in records.as_array().map(|a| a.as_slice()).unwrap_or(&[]) {
+                let field = |key: &str| r[key].as_str().unwrap_or("").to_string();
+                db.add(answer, &field("clue"), &field("source"), &field("date"));
+            }
+        }
+        Ok(db)
+    }
+
+    pub fn save(&self, path: &std::path::Path) -> std::result::Result<(), String> {
+        let mut answers = serde_json::Map::new();
+        for (answer, records) in self.answers.iter() {
+            let records: Vec<serde_json::Value> = records.iter()
+                .map(|r| json!({ "clue": r.clue, "source": r.source, "date": r.date }))
+                .collect();
+            answers.insert(answer.clone(), serde_json::Value::Array(records));
+        }
+        // Written beside the real file and renamed over it, so a failed write
+        // never leaves the database cut short.
+        let mut temp = path.as_os_str().to_owned();
+        temp.push(".tmp");
+        let temp = std::path::PathBuf::from(temp);
+        if let Err(e) = std::fs::write(&temp, json!({ "answers": answers }).to_string()) {
+            let _ = std::fs::remove_file(&temp);
+            return Err(e.to_string());
+        }
+        std::fs::rename(&temp, path).map_err(|e| e.to_string())
+    }
+
+    // How many clues there are, over all answers.
+    pub fn len(&self) -> usize {
+        self.answers.values().map(|r| r.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.answers.is_empty()
+    }
+
+    pub fn clues_for(&self, answer: &str) -> &[ClueRecord] {
+        self.answers.get(&answer.to_uppercase()).map(|r| r.as_slice()).unwrap_or(&[])
+    }
+
+    // Returns false if the answer already had this clue, or either is blank.
+    pub fn add(&mut self, answer: &str, clue: &str, source: &str, date: &str) -> bool {
+        let (answer, clue) = (answer.trim().to_uppercase(), clue.trim());
+        if answer.is_empty() || clue.is_empty() {
+            return false;
+        }
+        let records = self.answers.entry(answer).or_default();
+        if records.iter().any(|r| r.clue == clue) {
+            return false;
+        }
+        records.push(ClueRecord { clue: clue.to_string(), source: source.to_string(), date: date.to_string() });
+        true
+    }
+
+    // Adds every clue of a puzzle whose answer is filled in, returning how many
+    // were new.
+    pub fn add_puzzle(&mut self, puzzle: &puzzle_backend::Puzzle, source: &str) -> usize {
+        let mut added = 0;
+        for e in puzzle.across_entries.iter().chain(puzzle.down_entries.iter()) {
+            if let Some(answer) = entry_answer(puzzle, &e.member_indices) {
+                if self.add(&answer, &e.clue, source, &puzzle.date) {
+                    added += 1;
+                }
+            }
+        }
+        added
+    }
+
+    // Adds the clues from every .cro file in a directory and the directories
+    // under it.
+    pub fn import_directory(&mut self, dir: &std::path::Path) -> std::result::Result<ImportSummary, String> {
+        let mut summary = ImportSummary::default();
+        // Sources are recorded as full paths so they still mean something later.
+        let dir = std::fs::canonicalize(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        let mut pending = vec![dir];
+        while let Some(d) = pending.pop() {
+            let mut entries: Vec<std::path::PathBuf> = std::fs::read_dir(&d)
+                .map_err(|e| format!("{}: {}", d.display(), e))?
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .collect();
+            entries.sort();
+            for path in entries {
+                if path.is_dir() {
+                    pending.push(path);
+                } else if puzzle_file_io::file_extension(&path.to_string_lossy()) == "cro" {
+                    let name = path.to_string_lossy().to_string();
+                    match puzzle_file_io::get_puzzle_from_cro(name.clone()) {
+                        Ok(p) => {
+                            summary.files += 1;
+                            summary.new_clues += self.add_puzzle(&p, &name);
+                        }
+                        Err(e) => summary.failed.push((name, e.to_string())),
+                    }
+                }
+            }
+        }
+        Ok(summary)
+    }
+
+    // Adds everything from another database, returning how many clues were new.
+    pub fn merge(&mut self, other: &ClueDatabase) -> usize {
+        let mut added = 0;
+        for (answer, records) in other.answers.iter() {
+            for r in records.iter() {
+                if self.add(answer, &r.clue, &r.source, &r.date) {
+                    added += 1;
+                }
+            }
+        }
+        added
+    }
+}
+
+// The answer an entry spells, or None while any of its squares is empty.
+// Solvable grids use their stored solution rather than what the solver has typed.
+pub fn entry_answer(puzzle: &puzzle_backend::Puzzle, members: &[usize]) -> Option<String> {
+    let letters: Option<Vec<String>> = members.iter().map(|i| {
+        let letters = match (&puzzle.solution, &puzzle.squares[*i].content) {
+            (Some(solution), _) if puzzle.fill_only => solution[*i].clone(),
+            (_, puzzle_backend::SquareContents::TextContent(s,_m)) => s.clone(),
+            (_, puzzle_backend::SquareContents::Blocker) => String::new(),
+        };
+        if letters.is_empty() { None } else { Some(letters) }
+    }).collect();
+    letters.map(|l| l.concat())
+}
@@ -0,0 +1,57 @@
+// Where the parts of a grid are drawn, shared by the canvas and the exporters.
+// Positions are measured from the top left corner of the grid.
+pub struct GridLayout {
+    pub square_width: f32,
+    // The white part of a square; the rest is grid line.
+    pub content_width: f32,
+    pub label_size: f32,
+    width: u32,
+    height: u32,
+}
+
+const PADDING_FACTOR: f32 = 0.05;
+const LABEL_FACTOR: f32 = 0.30;
+const CIRCLE_FACTOR: f32 = 0.92;
+const CIRCLE_STROKE_FACTOR: f32 = 0.03;
+
+impl GridLayout {
+    pub fn new(square_width: f32, width: u32, height: u32) -> Self {
+        let padding_width = PADDING_FACTOR * square_width;
+        GridLayout {
+            square_width,
+            content_width: square_width - padding_width,
+            label_size: LABEL_FACTOR * square_width,
+            width,
+            height,
+        }
+    }
+
+    pub fn grid_size(&self) -> (f32, f32) {
+        (self.square_width * self.width as f32, self.square_width * self.height as f32)
+    }
+
+    pub fn center(&self, x: u32, y: u32) -> (f32, f32) {
+        (self.square_width * (x as f32 + 0.5), self.square_width * (y as f32 + 0.5))
+    }
+
+    pub fn content_top_left(&self, x: u32, y: u32) -> (f32, f32) {
+        let (cx, cy) = self.center(x, y);
+        (cx - self.content_width / 2.0, cy - self.content_width / 2.0)
+    }
+
+    // Rebus entries shrink so they still fit in the square.
+    pub fn content_text_size(&self, s: &str) -> f32 {
+        match s.len() {
+            0 | 1 => self.content_width,
+            n => 1.2 * self.content_width / n as f32,
+        }
+    }
+
+    pub fn circle_radius(&self) -> f32 {
+        CIRCLE_FACTOR * self.content_width / 2.0
+    }
+
+    pub fn circle_stroke_width(&self) -> f32 {
+        CIRCLE_STROKE_FACTOR * self.content_width
+    }
+}
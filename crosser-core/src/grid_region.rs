@@ -0,0 +1,105 @@
+use crate::puzzle_backend;
+
+// Shared with the text export so both text grids read the same way.
+pub const TEXT_BLOCKER: char = '#';
+pub const TEXT_EMPTY: char = '.';
+
+// A rectangle of squares lifted out of a grid, for pasting back into the same
+// grid or another one. Squares are row-major, like the grid's.
+#[derive(Clone, PartialEq)]
+pub struct GridRegion {
+    pub width: usize,
+    pub height: usize,
+    pub squares: Vec<puzzle_backend::SquareContents>,
+}
+
+impl GridRegion {
+    pub fn at(&self, x: usize, y: usize) -> &puzzle_backend::SquareContents {
+        &self.squares[y * self.width + x]
+    }
+
+    // One line per row, with '#' for blockers, '.' for empty squares and
+    // rebus squares in brackets. Modifiers don't survive the trip.
+    pub fn to_text(&self) -> String {
+        let mut lines = Vec::with_capacity(self.height);
+        for row in self.squares.chunks(self.width.max(1)) {
+            let mut line = String::new();
+            for sq in row.iter() {
+                match sq {
+                    puzzle_backend::SquareContents::Blocker => line.push(TEXT_BLOCKER),
+                    puzzle_backend::SquareContents::TextContent(s,_m) if s.is_empty() => line.push(TEXT_EMPTY),
+                    puzzle_backend::SquareContents::TextContent(s,_m) if s.chars().count() == 1 => line.push_str(s),
+                    puzzle_backend::SquareContents::TextContent(s,_m) => line.push_str(&format!("[{}]", s)),
+                }
+            }
+            lines.push(line);
+        }
+        lines.join("\n")
+    }
+
+    // Reads text written by to_text, or typed by hand. Short rows are padded
+    // with empty squares, and spaces count as empty squares too.
+    pub fn from_text(text: &str) -> std::result::Result<GridRegion, String> {
+        let mut rows: Vec<Vec<puzzle_backend::SquareContents>> = Vec::new();
+        for (n, line) in text.trim_end().lines().enumerate() {
+            let mut row = Vec::new();
+            let mut chars = line.trim_end().chars();
+            while let Some(c) = chars.next() {
+                let sq = match c {
+                    TEXT_BLOCKER => puzzle_backend::SquareContents::Blocker,
+                    TEXT_EMPTY | ' ' | '_' => puzzle_backend::SquareContents::TextContent(String::new(), None),
+                    '[' => {
+                        let rebus: String = chars.by_ref().take_while(|c| *c != ']').collect();
+                        if rebus.is_empty() || !rebus.chars().all(|c| c.is_alphanumeric()) {
+                            return Err(format!("Line {} has a bracketed square that isn't letters.", n + 1));
+                        }
+                        puzzle_backend::SquareContents::TextContent(rebus.to_uppercase(), None)
+                    }
+                    c if c.is_alphanumeric() => puzzle_backend::SquareContents::TextContent(c.to_uppercase().collect(), None),
+                    c => {
+                        return Err(format!("'{}' on line {} isn't a letter, '{}' or '{}'.", c, n + 1, TEXT_BLOCKER, TEXT_EMPTY));
+                    }
+                };
+                row.push(sq);
+            }
+            rows.push(row);
+        }
+        let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        if width == 0 {
+            return Err("The clipboard doesn't hold a grid.".to_string());
+        }
+        let height = rows.len();
+        let mut squares = Vec::with_capacity(width * height);
+        for mut row in rows {
+            row.resize(width, puzzle_backend::SquareContents::TextContent(String::new(), None));
+            squares.append(&mut row);
+        }
+        Ok(GridRegion { width, height, squares })
+    }
+
+    // The region moved dx squares right and dy down, with whatever falls off
+    // one side coming back in on the other.
+    pub fn shifted(&self, dx: i32, dy: i32) -> GridRegion {
+        let (w, h) = (self.width as i32, self.height as i32);
+        let mut squares = Vec::with_capacity(self.squares.len());
+        for y in 0..h {
+            for x in 0..w {
+                let from_x = (x - dx).rem_euclid(w);
+                let from_y = (y - dy).rem_euclid(h);
+                squares.push(self.at(from_x as usize, from_y as usize).clone());
+            }
+        }
+        GridRegion { width: self.width, height: self.height, squares }
+    }
+
+    // The region turned a quarter turn clockwise.
+    pub fn rotated(&self) -> GridRegion {
+        let mut squares = Vec::with_capacity(self.squares.len());
+        for y in 0..self.width {
+            for x in 0..self.height {
+                squares.push(self.at(y, self.height - 1 - x).clone());
+            }
+        }
+        GridRegion { width: self.height, height: self.width, squares }
+    }
+}
@@ -0,0 +1,16 @@
+//! The puzzle model, entry numbering, file formats, validation, autofill and
+//! exporters behind Crosser, without any of the GUI.
+
+pub mod autofill;
+pub mod clue_database;
+pub mod grid_layout;
+pub mod grid_region;
+pub mod pdf_export;
+pub mod puzzle_backend;
+pub mod puzzle_file_io;
+pub mod puzzle_history;
+pub mod puzzle_validation;
+pub mod solution_check;
+pub mod svg_export;
+pub mod text_export;
+pub mod word_list;
@@ -0,0 +1,516 @@
+use crate::puzzle_backend;
+
+// Pages are laid out in points, measured from the top left corner, and
+// flipped into PDF coordinates as they're written.
+const MARGIN: f32 = 36.0;
+const COLUMN_GAP: f32 = 18.0;
+const TITLE_SIZE: f32 = 18.0;
+const BYLINE_SIZE: f32 = 11.0;
+const HEADING_SIZE: f32 = 11.0;
+const CLUE_SIZE: f32 = 9.5;
+const FOOTER_SIZE: f32 = 8.0;
+const LINE_SPACING: f32 = 1.2;
+// Grids sit beside the clues unless that would make the squares smaller than this.
+const MIN_SQUARE_SIZE: f32 = 18.0;
+const MAX_SQUARE_SIZE: f32 = 36.0;
+const SHADING_GRAY: f32 = 0.8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PageSize {
+    Letter,
+    A4,
+}
+
+impl PageSize {
+    fn dimensions(&self) -> (f32, f32) {
+        match self {
+            PageSize::Letter => (612.0, 792.0),
+            PageSize::A4 => (595.0, 842.0),
+        }
+    }
+}
+
+// The standard PDF fonts, which every reader has and which don't need embedding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PdfFont {
+    Helvetica,
+    Times,
+    Courier,
+}
+
+impl PdfFont {
+    fn base_font(&self, bold: bool) -> &'static str {
+        match (self, bold) {
+            (PdfFont::Helvetica, false) => "Helvetica",
+            (PdfFont::Helvetica, true) => "Helvetica-Bold",
+            (PdfFont::Times, false) => "Times-Roman",
+            (PdfFont::Times, true) => "Times-Bold",
+            (PdfFont::Courier, false) => "Courier",
+            (PdfFont::Courier, true) => "Courier-Bold",
+        }
+    }
+
+    // Advance width of a WinAnsi character, in thousandths of the font size.
+    fn char_width(&self, bold: bool, c: u8) -> f32 {
+        let table = match (self, bold) {
+            (PdfFont::Helvetica, false) => &HELVETICA_WIDTHS,
+            (PdfFont::Helvetica, true) => &HELVETICA_BOLD_WIDTHS,
+            (PdfFont::Times, false) => &TIMES_WIDTHS,
+            (PdfFont::Times, true) => &TIMES_BOLD_WIDTHS,
+            (PdfFont::Courier, _) => { return 600.0; },
+        };
+        let ascii = match c {
+            32..=126 => c,
+            // Curly quotes and dashes are close to their plain counterparts.
+            0x91 | 0x92 => b',',
+            0x93 | 0x94 => b'"',
+            0x96 => b'n',
+            0x85 | 0x97 => { return 1000.0; },
+            0xC0..=0xDE => b'O',
+            _ => b'o',
+        };
+        table[(ascii - 32) as usize] as f32
+    }
+
+    fn text_width(&self, bold: bool, size: f32, s: &str) -> f32 {
+        s.chars().map(|c| self.char_width(bold, win_ansi(c))).sum::<f32>() * size / 1000.0
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PdfOptions {
+    pub page_size: PageSize,
+    pub font: PdfFont,
+    pub answer_key: bool,
+}
+
+impl Default for PdfOptions {
+    fn default() -> Self {
+        PdfOptions {
+            page_size: PageSize::Letter,
+            font: PdfFont::Helvetica,
+            answer_key: true,
+        }
+    }
+}
+
+pub fn write_puzzle_to_pdf(puzzle: &puzzle_backend::Puzzle, path_str: String, options: &PdfOptions) -> std::result::Result<(),std::io::Error> {
+    let (page_w, page_h) = options.page_size.dimensions();
+    let content_w = page_w - 2.0 * MARGIN;
+    let column_w = (content_w - COLUMN_GAP) / 2.0;
+    let column_bottom = page_h - MARGIN - 2.0 * FOOTER_SIZE;
+    let (grid_w, grid_h) = (puzzle.width() as f32, puzzle.height() as f32);
+
+    let mut pages: Vec<Page> = vec![Page::new(page_w, page_h, options.font)];
+    let title = if puzzle.title.is_empty() { "Untitled".to_string() } else { puzzle.title.clone() };
+    let content_top = draw_header(&mut pages[0], puzzle, &title);
+
+    // Grids fit in the right column when they can, with the clues starting to
+    // their left. Bigger grids span the page and the clues start below them.
+    let available_h = column_bottom - content_top;
+    let mut columns: Vec<Column> = Vec::new();
+    let beside = (column_w / grid_w).min(MAX_SQUARE_SIZE).min(0.6 * available_h / grid_h);
+    if beside >= MIN_SQUARE_SIZE {
+        let left = page_w - MARGIN - beside * grid_w;
+        draw_grid(&mut pages[0], puzzle, left, content_top, beside, None);
+        columns.push(Column { x: MARGIN, top: content_top, bottom: column_bottom });
+        columns.push(Column { x: MARGIN + column_w + COLUMN_GAP, top: content_top + beside * grid_h + COLUMN_GAP, bottom: column_bottom });
+    } else {
+        let square = (content_w / grid_w).min(0.65 * available_h / grid_h);
+        let left = MARGIN + (content_w - square * grid_w) / 2.0;
+        draw_grid(&mut pages[0], puzzle, left, content_top, square, None);
+        let top = content_top + square * grid_h + COLUMN_GAP;
+        columns.push(Column { x: MARGIN, top, bottom: column_bottom });
+        columns.push(Column { x: MARGIN + column_w + COLUMN_GAP, top, bottom: column_bottom });
+    }
+
+    let blocks = clue_blocks(puzzle, options.font, column_w);
+    let full_columns = [
+        Column { x: MARGIN, top: MARGIN, bottom: column_bottom },
+        Column { x: MARGIN + column_w + COLUMN_GAP, top: MARGIN, bottom: column_bottom },
+    ];
+    let mut column_index = 0;
+    let mut y = columns[0].top;
+    for block in blocks.iter() {
+        loop {
+            let at_top = y == columns[column_index].top;
+            let h = block.height(at_top);
+            // A block too tall for any column goes in anyway rather than looping forever.
+            if y + h <= columns[column_index].bottom || at_top {
+                let x = columns[column_index].x;
+                y = block.draw(pages.last_mut().unwrap(), x, y, at_top);
+                break;
+            }
+            column_index += 1;
+            if column_index == columns.len() {
+                pages.push(Page::new(page_w, page_h, options.font));
+                columns = full_columns.to_vec();
+                column_index = 0;
+            }
+            y = columns[column_index].top;
+        }
+    }
+
+    if options.answer_key {
+        let solution = if puzzle.fill_only {
+            puzzle.solution.clone()
+        } else {
+            Some(puzzle.contents_as_solution())
+        };
+        if let Some(sol) = solution.filter(|s| s.iter().any(|a| !a.is_empty() && a != "#")) {
+            let mut page = Page::new(page_w, page_h, options.font);
+            let top = draw_header(&mut page, puzzle, &format!("{} - Answers", title));
+            let square = (content_w / grid_w).min((column_bottom - top) / grid_h).min(MAX_SQUARE_SIZE);
+            let left = MARGIN + (content_w - square * grid_w) / 2.0;
+            draw_grid(&mut page, puzzle, left, top, square, Some(&sol));
+            pages.push(page);
+        }
+    }
+
+    if !puzzle.copyright.is_empty() {
+        for page in pages.iter_mut() {
+            let w = options.font.text_width(false, FOOTER_SIZE, &puzzle.copyright);
+            page.text((page_w - w) / 2.0, page_h - MARGIN, FOOTER_SIZE, false, &puzzle.copyright);
+        }
+    }
+
+    let info = [("Title", puzzle.title.as_str()), ("Author", puzzle.author.as_str()), ("Subject", puzzle.notes.as_str())];
+    std::fs::write(path_str, pdf_document(&pages, options.font, &info))
+}
+
+// Draws the title block and returns where the content below it starts.
+fn draw_header(page: &mut Page, puzzle: &puzzle_backend::Puzzle, title: &str) -> f32 {
+    let mut y = MARGIN + TITLE_SIZE;
+    page.text(MARGIN, y, TITLE_SIZE, true, title);
+
+    let details: Vec<&str> = [puzzle.date.as_str(), puzzle.difficulty.as_str()].iter().filter(|s| !s.is_empty()).cloned().collect();
+    if !details.is_empty() {
+        let s = details.join(" | ");
+        let w = page.font.text_width(false, BYLINE_SIZE, &s);
+        page.text(page.width - MARGIN - w, y, BYLINE_SIZE, false, &s);
+    }
+
+    let mut byline: Vec<String> = Vec::new();
+    if !puzzle.author.is_empty() {
+        byline.push(format!("By {}", puzzle.author));
+    }
+    if !puzzle.editor.is_empty() {
+        byline.push(format!("Edited by {}", puzzle.editor));
+    }
+    if !byline.is_empty() {
+        y += BYLINE_SIZE * LINE_SPACING + 2.0;
+        page.text(MARGIN, y, BYLINE_SIZE, false, &byline.join(" / "));
+    }
+
+    y += 8.0;
+    page.line(MARGIN, y, page.width - MARGIN, y, 0.75);
+    y + 12.0
+}
+
+// Blank squares unless answers are given, one string per square.
+fn draw_grid(page: &mut Page, puzzle: &puzzle_backend::Puzzle, left: f32, top: f32, square: f32, answers: Option<&[String]>) {
+    let w = puzzle.width();
+    for (i, sq) in puzzle.squares.iter().enumerate() {
+        let x = left + (i % w) as f32 * square;
+        let y = top + (i / w) as f32 * square;
+        match &sq.content {
+            puzzle_backend::SquareContents::Blocker => {
+                page.rect(x, y, square, square, Some(0.0), 0.5);
+            },
+            puzzle_backend::SquareContents::TextContent(_s,m) => {
+                let fill = match m {
+                    Some(puzzle_backend::SquareModifier::Shading) => Some(SHADING_GRAY),
+                    _ => None,
+                };
+                page.rect(x, y, square, square, fill, 0.5);
+                if let Some(puzzle_backend::SquareModifier::Circle) = m {
+                    page.circle(x + square / 2.0, y + square / 2.0, 0.45 * square);
+                }
+                if let Some(l) = sq.label {
+                    page.text(x + 0.06 * square, y + 0.3 * square, 0.28 * square, false, &l.to_string());
+                }
+                if let Some(a) = answers.map(|a| a[i].as_str()).filter(|a| !a.is_empty()) {
+                    // Rebus answers shrink to fit the square.
+                    let mut size = 0.55 * square;
+                    let text_w = page.font.text_width(false, size, a);
+                    if text_w > 0.85 * square {
+                        size *= 0.85 * square / text_w;
+                    }
+                    let text_w = page.font.text_width(false, size, a);
+                    page.text(x + (square - text_w) / 2.0, y + 0.88 * square, size, false, a);
+                }
+            },
+        }
+    }
+    page.rect(left, top, square * w as f32, square * puzzle.height() as f32, None, 1.5);
+}
+
+#[derive(Clone)]
+struct Column {
+    x: f32,
+    top: f32,
+    bottom: f32,
+}
+
+struct Line {
+    label: Option<String>,
+    text: String,
+    bold: bool,
+    size: f32,
+    // Whether the text starts after the label gutter.
+    indented: bool,
+}
+
+// Lines that stay together in one column.
+struct Block {
+    lines: Vec<Line>,
+    space_before: f32,
+    gutter: f32,
+}
+
+impl Block {
+    fn height(&self, at_top: bool) -> f32 {
+        let space = if at_top { 0.0 } else { self.space_before };
+        space + self.lines.iter().map(|l| l.size * LINE_SPACING).sum::<f32>()
+    }
+
+    // Returns where the next block starts.
+    fn draw(&self, page: &mut Page, x: f32, top: f32, at_top: bool) -> f32 {
+        let mut y = if at_top { top } else { top + self.space_before };
+        for line in self.lines.iter() {
+            let baseline = y + line.size;
+            if let Some(l) = &line.label {
+                let w = page.font.text_width(true, line.size, l);
+                page.text(x + self.gutter - w - 0.3 * line.size, baseline, line.size, true, l);
+            }
+            let text_x = if line.indented { x + self.gutter } else { x };
+            page.text(text_x, baseline, line.size, line.bold, &line.text);
+            y += line.size * LINE_SPACING;
+        }
+        y
+    }
+}
+
+fn clue_blocks(puzzle: &puzzle_backend::Puzzle, font: PdfFont, column_w: f32) -> Vec<Block> {
+    let gutter = font.text_width(true, CLUE_SIZE, "000 ");
+    let mut blocks: Vec<Block> = Vec::new();
+
+    if !puzzle.notes.is_empty() {
+        let lines = puzzle.notes.lines().flat_map(|p| wrap(font, false, CLUE_SIZE, p, column_w))
+            .map(|text| Line { label: None, text, bold: false, size: CLUE_SIZE, indented: false })
+            .collect();
+        blocks.push(Block { lines, space_before: 0.0, gutter });
+    }
+
+    for (heading, entries) in [("ACROSS", &puzzle.across_entries), ("DOWN", &puzzle.down_entries)] {
+        let mut heading_line = Some(Line { label: None, text: heading.to_string(), bold: true, size: HEADING_SIZE, indented: false });
+        for e in entries.iter() {
+            let mut lines: Vec<Line> = Vec::new();
+            // Headings stay with their first clue.
+            let space_before = match heading_line.take() {
+                Some(h) => {
+                    lines.push(h);
+                    HEADING_SIZE
+                },
+                None => 0.2 * CLUE_SIZE,
+            };
+            for (n, text) in wrap(font, false, CLUE_SIZE, &e.clue, column_w - gutter).into_iter().enumerate() {
+                let label = if n == 0 { Some(e.label.to_string()) } else { None };
+                lines.push(Line { label, text, bold: false, size: CLUE_SIZE, indented: true });
+            }
+            blocks.push(Block { lines, space_before, gutter });
+        }
+    }
+    blocks
+}
+
+// Breaks text into lines no wider than width, splitting words only when they
+// don't fit on a line of their own. Always returns at least one line.
+fn wrap(font: PdfFont, bold: bool, size: f32, text: &str, width: f32) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if current.is_empty() { word.to_string() } else { format!("{} {}", current, word) };
+        if font.text_width(bold, size, &candidate) <= width {
+            current = candidate;
+            continue;
+        }
+        if !current.is_empty() {
+            lines.push(std::mem::take(&mut current));
+        }
+        for c in word.chars() {
+            current.push(c);
+            if font.text_width(bold, size, &current) > width && current.chars().count() > 1 {
+                current.pop();
+                lines.push(std::mem::take(&mut current));
+                current.push(c);
+            }
+        }
+    }
+    lines.push(current);
+    lines
+}
+
+struct Page {
+    width: f32,
+    height: f32,
+    font: PdfFont,
+    content: String,
+}
+
+impl Page {
+    fn new(width: f32, height: f32, font: PdfFont) -> Self {
+        Page {
+            width,
+            height,
+            font,
+            content: String::new(),
+        }
+    }
+
+    fn text(&mut self, x: f32, baseline: f32, size: f32, bold: bool, s: &str) {
+        let font_name = if bold { "F2" } else { "F1" };
+        self.content.push_str(&format!("BT /{} {:.2} Tf {:.2} {:.2} Td ({}) Tj ET\n", font_name, size, x, self.height - baseline, pdf_string(s)));
+    }
+
+    fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, line_width: f32) {
+        self.content.push_str(&format!("{:.2} w {:.2} {:.2} m {:.2} {:.2} l S\n", line_width, x1, self.height - y1, x2, self.height - y2));
+    }
+
+    // Outlined, and filled first with a shade of gray if given.
+    fn rect(&mut self, x: f32, y: f32, w: f32, h: f32, fill_gray: Option<f32>, line_width: f32) {
+        let (x, y) = (x, self.height - y - h);
+        if let Some(g) = fill_gray {
+            self.content.push_str(&format!("q {:.2} g {:.2} {:.2} {:.2} {:.2} re f Q\n", g, x, y, w, h));
+        }
+        self.content.push_str(&format!("{:.2} w {:.2} {:.2} {:.2} {:.2} re S\n", line_width, x, y, w, h));
+    }
+
+    // Four Bezier quarter arcs.
+    fn circle(&mut self, cx: f32, cy: f32, r: f32) {
+        let cy = self.height - cy;
+        let k = 0.5523 * r;
+        self.content.push_str(&format!(
+            "0.50 w {:.2} {:.2} m {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c {:.2} {:.2} {:.2} {:.2} {:.2} {:.2} c S\n",
+            cx + r, cy,
+            cx + r, cy + k, cx + k, cy + r, cx, cy + r,
+            cx - k, cy + r, cx - r, cy + k, cx - r, cy,
+            cx - r, cy - k, cx - k, cy - r, cx, cy - r,
+            cx + k, cy - r, cx + r, cy - k, cx + r, cy,
+        ));
+    }
+}
+
+// Assembles the file: catalog, page tree, the two fonts, document info, then
+// a page object and content stream per page.
+fn pdf_document(pages: &[Page], font: PdfFont, info: &[(&str, &str)]) -> Vec<u8> {
+    let mut objects: Vec<String> = Vec::new();
+    let first_page_obj = 6;
+    let kids: Vec<String> = (0..pages.len()).map(|i| format!("{} 0 R", first_page_obj + 2 * i)).collect();
+    objects.push("<< /Type /Catalog /Pages 2 0 R >>".to_string());
+    objects.push(format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), pages.len()));
+    for bold in [false, true] {
+        objects.push(format!("<< /Type /Font /Subtype /Type1 /BaseFont /{} /Encoding /WinAnsiEncoding >>", font.base_font(bold)));
+    }
+    let info_entries: Vec<String> = info.iter().filter(|(_k,v)| !v.is_empty()).map(|(k,v)| format!("/{} {}", k, pdf_text_string(v))).collect();
+    objects.push(format!("<< /Producer (Crosser) {} >>", info_entries.join(" ")));
+    for (i, page) in pages.iter().enumerate() {
+        objects.push(format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {:.0} {:.0}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+            page.width, page.height, first_page_obj + 2 * i + 1
+        ));
+        objects.push(format!("<< /Length {} >>\nstream\n{}endstream", page.content.len(), page.content));
+    }
+
+    let mut out = String::from("%PDF-1.4\n");
+    let mut offsets: Vec<usize> = Vec::with_capacity(objects.len());
+    for (i, obj) in objects.iter().enumerate() {
+        offsets.push(out.len());
+        out.push_str(&format!("{} 0 obj\n{}\nendobj\n", i + 1, obj));
+    }
+    let xref_offset = out.len();
+    out.push_str(&format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1));
+    for o in offsets.iter() {
+        out.push_str(&format!("{:010} 00000 n \n", o));
+    }
+    out.push_str(&format!("trailer\n<< /Size {} /Root 1 0 R /Info 5 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref_offset));
+    out.into_bytes()
+}
+
+// The WinAnsi code for a character, or '?' if it has none.
+fn win_ansi(c: char) -> u8 {
+    match c {
+        ' '..='~' => c as u8,
+        '\u{A0}'..='\u{FF}' => c as u32 as u8,
+        '\u{2026}' => 0x85,
+        '\u{2018}' => 0x91,
+        '\u{2019}' => 0x92,
+        '\u{201C}' => 0x93,
+        '\u{201D}' => 0x94,
+        '\u{2013}' => 0x96,
+        '\u{2014}' => 0x97,
+        _ => b'?',
+    }
+}
+
+// A literal string for a content stream, kept to ASCII with octal escapes.
+fn pdf_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match win_ansi(c) {
+            b'(' => out.push_str("\\("),
+            b')' => out.push_str("\\)"),
+            b'\\' => out.push_str("\\\\"),
+            b @ 32..=126 => out.push(b as char),
+            b => out.push_str(&format!("\\{:03o}", b)),
+        }
+    }
+    out
+}
+
+// Document info strings are UTF-16 so any title survives.
+fn pdf_text_string(s: &str) -> String {
+    let mut out = String::from("<FEFF");
+    for u in s.encode_utf16() {
+        out.push_str(&format!("{:04X}", u));
+    }
+    out.push('>');
+    out
+}
+
+// Widths of the characters from ' ' to '~', from the Adobe font metrics.
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+const HELVETICA_BOLD_WIDTHS: [u16; 95] = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611,
+    975, 722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 333, 278, 333, 584, 556,
+    333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611, 611,
+    611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+const TIMES_WIDTHS: [u16; 95] = [
+    250, 333, 408, 500, 500, 833, 778, 180, 333, 333, 500, 564, 250, 333, 250, 278,
+    500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 278, 278, 564, 564, 564, 444,
+    921, 722, 667, 667, 722, 611, 556, 722, 722, 333, 389, 722, 611, 889, 722, 722,
+    556, 722, 667, 556, 611, 722, 722, 944, 722, 722, 611, 333, 278, 333, 469, 500,
+    333, 444, 500, 444, 500, 444, 333, 500, 500, 278, 278, 500, 278, 778, 500, 500,
+    500, 500, 333, 389, 278, 500, 500, 722, 500, 500, 444, 480, 200, 480, 541,
+];
+
+const TIMES_BOLD_WIDTHS: [u16; 95] = [
+    250, 333, 555, 500, 500, 1000, 833, 278, 333, 333, 500, 570, 250, 333, 250, 278,
+    500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 333, 333, 570, 570, 570, 500,
+    930, 722, 667, 722, 722, 667, 611, 778, 778, 389, 500, 778, 667, 944, 722, 778,
+    611, 778, 722, 556, 667, 722, 722, 1000, 722, 722, 667, 333, 278, 333, 581, 500,
+    333, 500, 556, 444, 556, 444, 333, 500, 556, 278, 333, 556, 278, 833, 556, 500,
+    556, 556, 444, 389, 333, 556, 500, 722, 500, 500, 444, 394, 220, 394, 520,
+];
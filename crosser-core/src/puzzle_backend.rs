@@ -0,0 +1,1240 @@
+use crate::grid_region;
+use crate::puzzle_history;
+use crate::puzzle_validation;
+use crate::solution_check;
+
+#[derive(Clone, PartialEq)]
+pub enum SquareContents {
+    Blocker,
+    TextContent(String, Option<SquareModifier>),
+}
+
+#[derive(Clone, PartialEq)]
+pub enum SquareModifier {
+    Shading,
+    Circle,
+}
+
+#[derive(Clone)]
+pub struct Square {
+    pub content: SquareContents,
+    pub label: Option<u32>,
+    pub x: u32,
+    pub y: u32,
+    pub across_entry: Option<u32>,
+    pub next_across: Option<usize>,
+    pub prev_across: Option<usize>,
+    pub across_clue_text: Option<String>,
+    pub down_entry: Option<u32>,
+    pub next_down: Option<usize>,
+    pub prev_down: Option<usize>,
+    pub down_clue_text: Option<String>,
+}
+
+impl Square {
+    fn new(x: u32, y: u32) -> Self {
+        Square {
+            content: SquareContents::TextContent("".to_string(),None),
+            label: None,
+            x,
+            y,
+            across_entry: None,
+            next_across: None,
+            prev_across: None,
+            across_clue_text: None,
+            down_entry: None,
+            next_down: None,
+            prev_down: None,
+            down_clue_text: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum EntryVariant {
+    Across,
+    Down,
+}
+
+#[derive(Clone)]
+pub struct PuzzleEntry {
+    pub label: u32,
+    pub variant: EntryVariant,
+    pub member_indices: Vec<usize>,
+    pub clue: String,
+}
+
+#[derive(Clone, Debug)]
+pub enum PuzzleType {
+    Mini,
+    Weekday,
+    WeekdayAsymmetric,
+    Sunday,
+    Custom(usize, usize),
+}
+
+// How blockers are mirrored when one is toggled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Symmetry {
+    None,
+    // 180 degree rotation about the center.
+    Rotational,
+    MirrorLeftRight,
+    MirrorTopBottom,
+    // Reflection across the top-left to bottom-right diagonal. Only square grids have one.
+    Diagonal,
+    // Both mirrors at once.
+    FourWay,
+}
+
+// Ways of rearranging a whole grid. Letters, modifiers and clues all move
+// with their squares.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GridTransform {
+    // A quarter turn clockwise.
+    Rotate,
+    // Mirrored left to right.
+    FlipHorizontal,
+    // Mirrored top to bottom.
+    FlipVertical,
+    // Reflected across the top-left to bottom-right diagonal, so across entries become down entries.
+    Transpose,
+    // Moved this many squares right and down, wrapping around the edges.
+    Shift(i32, i32),
+}
+
+// Marks left on squares while solving.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SolveMark {
+    Wrong,
+    Revealed,
+}
+
+// A clue whose entry was broken up by a change to the grid, kept until it's
+// given to another entry or thrown away.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BinnedClue {
+    // The letters the entry had, with '.' for empty squares.
+    pub answer: String,
+    pub clue: String,
+}
+
+// A clue on the grid as it was before a structural edit, with the squares and
+// letters of its entry.
+struct PlacedClue {
+    variant: EntryVariant,
+    // Sorted, so entries can be compared however they run.
+    members: Vec<usize>,
+    answer: String,
+    clue: String,
+}
+
+// The descriptive fields of a puzzle, for editing them one at a time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MetadataField {
+    Title,
+    Author,
+    Editor,
+    Copyright,
+    Date,
+    Difficulty,
+    Notes,
+    Tags,
+}
+
+// The parts of a puzzle that saving keeps. Two snapshots differ exactly when
+// there are edits in between.
+#[derive(Clone, PartialEq)]
+pub struct Snapshot {
+    width: usize,
+    height: usize,
+    squares: Vec<(SquareContents, Option<String>, Option<String>)>,
+    metadata: [String; 7],
+    tags: Vec<String>,
+    symmetry: Symmetry,
+    clue_bin: Vec<BinnedClue>,
+}
+
+pub struct Puzzle {
+    pub title: String,
+    pub author: String,
+    pub editor: String,
+    pub copyright: String,
+    pub date: String,
+    pub difficulty: String,
+    // Notepad text or special instructions shown to the solver.
+    pub notes: String,
+    pub tags: Vec<String>,
+    width: usize,
+    height: usize,
+    pub variant: PuzzleType,
+    pub symmetry: Symmetry,
+    pub squares: Vec<Square>,
+    pub across_entries: Vec<PuzzleEntry>,
+    pub down_entries: Vec<PuzzleEntry>,
+    pub fill_only: bool,
+    // How to tell whether the grid is solved. Only solvable grids have one.
+    pub solution_check: Option<solution_check::SolutionCheck>,
+    // The answer for each square, "#" for blockers. Only solvable grids have one.
+    pub solution: Option<Vec<String>>,
+    pub marks: Vec<Option<SolveMark>>,
+    pub clue_bin: Vec<BinnedClue>,
+    // Top-level .cro keys this build doesn't know, from newer versions of
+    // Crosser. They are written back unchanged on save.
+    pub unknown_fields: serde_json::Map<String, serde_json::Value>,
+    history: puzzle_history::History,
+}
+
+impl Puzzle {
+    pub fn new(variant: PuzzleType) -> Self {
+        let (w, h) = match_puzzle_dim(&variant);
+        let mut v: Vec<Square> = Vec::with_capacity(w * h);
+        
+        // Squares are stored in row-major order
+        for y_index in 0..h as u32 {
+            for x_index in 0..w as u32 {
+                v.push(Square::new(x_index,y_index));
+            }
+        }
+
+        let mut p = Puzzle {
+            title: "New Puzzle".to_string(),
+            author: String::new(),
+            editor: String::new(),
+            copyright: String::new(),
+            date: String::new(),
+            difficulty: String::new(),
+            notes: String::new(),
+            tags: Vec::new(),
+            width: w,
+            height: h,
+            symmetry: default_symmetry(&variant),
+            variant,
+            squares: v,
+            across_entries: Vec::new(),
+            down_entries: Vec::new(),
+            fill_only: false,
+            solution_check: None,
+            solution: None,
+            marks: vec![None; w * h],
+            clue_bin: Vec::new(),
+            unknown_fields: serde_json::Map::new(),
+            history: puzzle_history::History::new(),
+        };
+        p.calculate_clues();
+        p
+    }
+
+    // Tags are edited as one comma separated string.
+    pub fn metadata(&self, field: MetadataField) -> String {
+        match field {
+            MetadataField::Title => self.title.clone(),
+            MetadataField::Author => self.author.clone(),
+            MetadataField::Editor => self.editor.clone(),
+            MetadataField::Copyright => self.copyright.clone(),
+            MetadataField::Date => self.date.clone(),
+            MetadataField::Difficulty => self.difficulty.clone(),
+            MetadataField::Notes => self.notes.clone(),
+            MetadataField::Tags => self.tags.join(", "),
+        }
+    }
+
+    pub fn set_metadata(&mut self, field: MetadataField, value: String) {
+        match field {
+            MetadataField::Title => { self.title = value; },
+            MetadataField::Author => { self.author = value; },
+            MetadataField::Editor => { self.editor = value; },
+            MetadataField::Copyright => { self.copyright = value; },
+            MetadataField::Date => { self.date = value; },
+            MetadataField::Difficulty => { self.difficulty = value; },
+            MetadataField::Notes => { self.notes = value; },
+            MetadataField::Tags => { self.tags = parse_tags(&value); },
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn at(&self, x: u32, y: u32) -> &Square {
+        let index = self.xy_to_index(x, y);
+        &self.squares[index]
+    }
+
+    pub fn cycle_blocker(&mut self, x: u32, y: u32)  {
+        if !self.fill_only {
+            let before = self.grid_state();
+            let clues = self.placed_clues();
+            let index = self.xy_to_index(x, y);
+            let make_blocker = self.squares[index].content != SquareContents::Blocker;
+            // Symmetric partners take on the same state as the toggled square.
+            self.set_blocker(index, make_blocker);
+            for (px, py) in self.symmetric_partners(x, y) {
+                let partner = self.xy_to_index(px, py);
+                self.set_blocker(partner, make_blocker);
+            }
+            self.calculate_clues();
+            self.rehome_clues(clues);
+            self.history.record(puzzle_history::EditKind::Structure, &before, &self.squares, &self.marks);
+        }
+    }
+
+    fn set_blocker(&mut self, index: usize, blocker: bool) {
+        match (&self.squares[index].content, blocker) {
+            (SquareContents::Blocker, false) => {
+                self.squares[index].content = SquareContents::TextContent("".to_string(),None);
+            },
+            (SquareContents::TextContent(_,_), true) => {
+                self.squares[index].across_clue_text = None;
+                self.squares[index].down_clue_text = None;
+                self.squares[index].content = SquareContents::Blocker;
+            },
+            _ => (),
+        }
+    }
+
+    // The other squares that mirror (x, y) under the current symmetry.
+    pub fn symmetric_partners(&self, x: u32, y: u32) -> Vec<(u32, u32)> {
+        let (w, h) = (self.width as u32, self.height as u32);
+        let (mx, my) = (w - x - 1, h - y - 1);
+        let mut partners = match self.symmetry {
+            Symmetry::None => Vec::new(),
+            Symmetry::Rotational => vec![(mx, my)],
+            Symmetry::MirrorLeftRight => vec![(mx, y)],
+            Symmetry::MirrorTopBottom => vec![(x, my)],
+            Symmetry::Diagonal => {
+                if w == h { vec![(y, x)] } else { Vec::new() }
+            },
+            Symmetry::FourWay => vec![(mx, y), (x, my), (mx, my)],
+        };
+        partners.retain(|p| *p != (x, y));
+        partners.sort_unstable();
+        partners.dedup();
+        partners
+    }
+
+    // Squares that would become blockers if the grid were made symmetric.
+    pub fn symmetry_violations(&self) -> Vec<usize> {
+        let mut violations = Vec::new();
+        for (index, sq) in self.squares.iter().enumerate() {
+            if sq.content != SquareContents::Blocker {
+                let mirrors_blocker = self.symmetric_partners(sq.x, sq.y).into_iter()
+                    .any(|(px, py)| self.at(px, py).content == SquareContents::Blocker);
+                if mirrors_blocker {
+                    violations.push(index);
+                }
+            }
+        }
+        violations
+    }
+
+    // Adds blockers until the grid matches the current symmetry, as one undo
+    // step. Squares holding letters are never overwritten; if any would have
+    // to be, nothing changes and their indices are returned instead.
+    pub fn symmetrize(&mut self) -> Result<usize, Vec<usize>> {
+        if self.fill_only {
+            return Ok(0);
+        }
+        let violations = self.symmetry_violations();
+        let conflicts: Vec<usize> = violations.iter().cloned()
+            .filter(|i| matches!(&self.squares[*i].content, SquareContents::TextContent(s,_m) if !s.is_empty()))
+            .collect();
+        if !conflicts.is_empty() {
+            return Err(conflicts);
+        }
+        if !violations.is_empty() {
+            let before = self.grid_state();
+            let clues = self.placed_clues();
+            for i in violations.iter() {
+                self.set_blocker(*i, true);
+            }
+            self.calculate_clues();
+            self.rehome_clues(clues);
+            self.history.record(puzzle_history::EditKind::Structure, &before, &self.squares, &self.marks);
+        }
+        Ok(violations.len())
+    }
+
+    pub fn cycle_modifier(&mut self, x: u32, y: u32) -> bool {
+        if !self.fill_only {
+            let index = self.xy_to_index(x, y);
+            let before = self.grid_state();
+            match &self.squares[index].content {
+                SquareContents::Blocker => false,
+                SquareContents::TextContent(s,modifier_option) => {
+                    match modifier_option {
+                        None => {
+                            self.squares[index].content = SquareContents::TextContent(s.clone(),Some(SquareModifier::Shading));
+                        }
+                        Some(SquareModifier::Shading) => {
+                            self.squares[index].content = SquareContents::TextContent(s.clone(),Some(SquareModifier::Circle));
+                        }
+                        Some(SquareModifier::Circle) => {
+                            self.squares[index].content = SquareContents::TextContent(s.clone(),None);
+                        }
+                    };
+                    self.history.record(puzzle_history::EditKind::Modifier, &before, &self.squares, &self.marks);
+                    true
+                },
+            }
+        } else {
+            false
+        }
+    }
+
+    pub fn modify_sq_contents(&mut self, x: u32, y: u32, c: char, append: bool) {
+        let index = self.xy_to_index(x, y);
+        let before = self.grid_state();
+        if let SquareContents::TextContent(s,modifier_option) = &self.squares[index].content {
+            if append {
+                let mut newstr = s.clone();
+                newstr.push(c);
+                self.squares[index].content = SquareContents::TextContent(newstr,modifier_option.clone());
+            } else {
+                self.squares[index].content = SquareContents::TextContent(c.to_string(),modifier_option.clone());
+            }
+        }
+        self.clear_wrong_mark(index);
+        self.history.record(puzzle_history::EditKind::Typing, &before, &self.squares, &self.marks);
+    }
+
+    pub fn clear_sq_contents(&mut self, x: u32, y: u32) -> bool {
+        let index = self.xy_to_index(x, y);
+        let before = self.grid_state();
+        let mut is_empty = false;
+        if let SquareContents::TextContent(s,modifier_option) = &self.squares[index].content {
+            if s.is_empty() {
+                is_empty = true;
+            };
+            self.squares[index].content = SquareContents::TextContent(String::new(),modifier_option.clone());
+        }
+        self.clear_wrong_mark(index);
+        self.history.record(puzzle_history::EditKind::Typing, &before, &self.squares, &self.marks);
+        is_empty
+    }
+
+    fn clear_wrong_mark(&mut self, index: usize) {
+        if self.marks[index] == Some(SolveMark::Wrong) {
+            self.marks[index] = None;
+        }
+    }
+
+    // Squares covered by a check or reveal at the selected square. Squares and
+    // words need a selection, so they give None without one.
+    pub fn solve_scope(&self, scope: SolveScope, selected: Option<(u32, u32, EntryVariant)>) -> Option<Vec<usize>> {
+        match (scope, selected) {
+            (SolveScope::Puzzle, _) => {
+                Some((0..self.squares.len()).filter(|i| self.squares[*i].content != SquareContents::Blocker).collect())
+            },
+            (SolveScope::Square, Some((x, y, _v))) => Some(vec![self.xy_to_index(x, y)]),
+            (SolveScope::Word, Some((x, y, v))) => {
+                let (a,d) = self.get_clue_entries(x,y);
+                let entry = match v {
+                    EntryVariant::Across => a,
+                    EntryVariant::Down => d,
+                };
+                Some(entry.map(|e| e.member_indices.clone()).unwrap_or_default())
+            },
+            (_, None) => None,
+        }
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            width: self.width,
+            height: self.height,
+            squares: self.squares.iter().map(|sq| (sq.content.clone(), sq.across_clue_text.clone(), sq.down_clue_text.clone())).collect(),
+            metadata: [
+                self.title.clone(),
+                self.author.clone(),
+                self.editor.clone(),
+                self.copyright.clone(),
+                self.date.clone(),
+                self.difficulty.clone(),
+                self.notes.clone(),
+            ],
+            tags: self.tags.clone(),
+            clue_bin: self.clue_bin.clone(),
+            symmetry: self.symmetry,
+        }
+    }
+
+    pub fn can_check(&self) -> bool {
+        self.solution.is_some() || self.solution_check.as_ref().is_some_and(|c| c.has_entry_checksums())
+    }
+
+    // Marks filled squares that don't match the solution. Returns how many were wrong.
+    pub fn check(&mut self, indices: &[usize]) -> usize {
+        let mut wrong: Vec<usize> = Vec::new();
+        if let Some(solution) = &self.solution {
+            for i in indices.iter() {
+                if let SquareContents::TextContent(s,_m) = &self.squares[*i].content {
+                    if !s.is_empty() && s.to_uppercase() != solution[*i].to_uppercase() {
+                        wrong.push(*i);
+                    }
+                }
+            }
+        } else if let Some(c) = &self.solution_check {
+            // Without the answers, only whole words can be checked. A wrong
+            // word marks its squares that were asked about.
+            for e in self.across_entries.iter().chain(self.down_entries.iter()) {
+                if !e.member_indices.iter().any(|i| indices.contains(i)) {
+                    continue;
+                }
+                let mut answer = String::new();
+                let mut complete = true;
+                for i in e.member_indices.iter() {
+                    match &self.squares[*i].content {
+                        SquareContents::TextContent(s,_m) if !s.is_empty() => answer.push_str(s),
+                        _ => { complete = false; },
+                    }
+                }
+                if !complete {
+                    continue;
+                }
+                if c.entry_matches(e.label, e.variant, &answer) == Some(false) {
+                    wrong.extend(e.member_indices.iter().filter(|i| indices.contains(i)));
+                }
+            }
+            wrong.sort_unstable();
+            wrong.dedup();
+        }
+        for i in wrong.iter() {
+            self.marks[*i] = Some(SolveMark::Wrong);
+        }
+        wrong.len()
+    }
+
+    // Writes the answer into squares that don't already hold it, as one undo step.
+    pub fn reveal(&mut self, indices: &[usize]) {
+        let solution = match &self.solution {
+            Some(s) => s.clone(),
+            None => { return; },
+        };
+        let before = self.grid_state();
+        for i in indices.iter() {
+            if let SquareContents::TextContent(s,m) = &self.squares[*i].content {
+                if s.to_uppercase() != solution[*i].to_uppercase() {
+                    self.squares[*i].content = SquareContents::TextContent(solution[*i].clone(),m.clone());
+                    self.marks[*i] = Some(SolveMark::Revealed);
+                }
+            }
+        }
+        self.history.record(puzzle_history::EditKind::Fill, &before, &self.squares, &self.marks);
+    }
+
+    pub fn calculate_clues(&mut self) {
+        if !self.fill_only {
+            let mut start_of_across_clue: Vec<bool> = vec![false; self.width * self.height];
+            for y in 0..self.height as u32 {
+                let start_index = self.xy_to_index(0, y);
+                let mut was_blocker = match self.squares[start_index].content {
+                    SquareContents::Blocker => {
+                        true
+                    },
+                    _ => {
+                        start_of_across_clue[start_index] = true;
+                        false
+                    },
+                };
+                for x in 1..self.width as u32 {
+                    let index = self.xy_to_index(x, y);
+                    match self.squares[index].content {
+                        SquareContents::Blocker => {
+                            was_blocker = true;
+                        },
+                        _ => {
+                            if was_blocker {
+                                start_of_across_clue[index] = true;
+                                was_blocker = false;
+                            }
+                        },
+                    }
+                }
+            }
+
+            let mut start_of_down_clue: Vec<bool> = vec![false; self.width * self.height];
+            for x in 0..self.width as u32 {
+                let start_index = self.xy_to_index(x, 0);
+                let mut was_blocker = match self.squares[start_index].content {
+                    SquareContents::Blocker => {
+                        true
+                    },
+                    _ => {
+                        start_of_down_clue[start_index] = true;
+                        false
+                    },
+                };
+                for y in 1..self.height as u32 {
+                    let index = self.xy_to_index(x, y);
+                    match self.squares[index].content {
+                        SquareContents::Blocker => {
+                            was_blocker = true;
+                        },
+                        _ => {
+                            if was_blocker {
+                                start_of_down_clue[index] = true;
+                                was_blocker = false;
+                            }
+                        },
+                    }
+                }
+            }
+
+            let mut current_label = 0;
+            for y in 0..self.height as u32 {
+                for x in 0..self.width as u32 {
+                    let index = self.xy_to_index(x, y);
+                    let l = if start_of_across_clue[index] || start_of_down_clue[index] {
+                        current_label += 1;
+                        Some(current_label)
+                    } else {
+                        None
+                    };
+                    self.squares[index].label = l;
+                }
+            }
+
+            // Construct across entries
+            self.across_entries.clear();
+            for y in 0..self.height as u32 {
+                let mut current_across = 0;
+                let mut entries: Vec<usize> = Vec::new();
+                for x in 0..self.width as u32 {
+                    let index = self.xy_to_index(x, y);
+                    if let Some(l) = self.squares[index].label {
+                        if entries.is_empty() {
+                            current_across = l;
+                        }
+                    };
+                    match &self.squares[index].content {
+                        SquareContents::Blocker => {
+                            self.squares[index].across_entry = None;
+                            if !entries.is_empty() {
+                                self.push_entry(current_across, EntryVariant::Across, entries);
+                                entries = Vec::new();
+                            }
+                        }
+                        SquareContents::TextContent(_,_) => {
+                            self.squares[index].across_entry = Some(current_across);
+                            entries.push(index);
+                        }
+                    }
+                }
+
+                if !entries.is_empty() {
+                    self.push_entry(current_across, EntryVariant::Across, entries);
+                }
+            }
+
+            // Construct down entries
+            self.down_entries.clear();
+            for x in 0..self.width as u32 {
+                let mut current_down = 0;
+                let mut entries: Vec<usize> = Vec::new();
+                for y in 0..self.height as u32 {
+                    let index = self.xy_to_index(x, y);
+                    if let Some(l) = self.squares[index].label {
+                        if entries.is_empty() {
+                            current_down = l;
+                        }
+                    };
+                    match &self.squares[index].content {
+                        SquareContents::Blocker => {
+                            self.squares[index].down_entry = None;
+                            if !entries.is_empty() {
+                                self.push_entry(current_down, EntryVariant::Down, entries);
+                                entries = Vec::new();
+                            }
+                        }
+                        SquareContents::TextContent(_,_) => {
+                            self.squares[index].down_entry = Some(current_down);
+                            entries.push(index);
+                        }
+                    }
+                };
+
+                if !entries.is_empty() {
+                    self.push_entry(current_down, EntryVariant::Down, entries);
+                }
+            }
+
+            // Down entries won't be in increasing order, so sort them.
+            self.down_entries.sort_by_key(|a| a.label);
+        }
+    }
+
+    // Links the squares of a finished entry together, moves its clue text onto
+    // the first square and pushes it onto the matching entries list.
+    fn push_entry(&mut self, label: u32, variant: EntryVariant, entries: Vec<usize>) {
+        for pair in entries.windows(2) {
+            match variant {
+                EntryVariant::Across => {
+                    self.squares[pair[0]].next_across = Some(pair[1]);
+                    self.squares[pair[1]].prev_across = Some(pair[0]);
+                },
+                EntryVariant::Down => {
+                    self.squares[pair[0]].next_down = Some(pair[1]);
+                    self.squares[pair[1]].prev_down = Some(pair[0]);
+                },
+            }
+        }
+
+        let first = entries[0];
+        let last = *entries.last().unwrap();
+        let text = match variant {
+            EntryVariant::Across => {
+                self.squares[first].prev_across = None;
+                self.squares[last].next_across = None;
+                let text = self.squares[first].across_clue_text.clone().unwrap_or_default();
+                self.squares[first].across_clue_text = Some(text.clone());
+                for e_index in entries.iter().skip(1) {
+                    self.squares[*e_index].across_clue_text = None;
+                }
+                text
+            },
+            EntryVariant::Down => {
+                self.squares[first].prev_down = None;
+                self.squares[last].next_down = None;
+                let text = self.squares[first].down_clue_text.clone().unwrap_or_default();
+                self.squares[first].down_clue_text = Some(text.clone());
+                for e_index in entries.iter().skip(1) {
+                    self.squares[*e_index].down_clue_text = None;
+                }
+                text
+            },
+        };
+
+        let e = PuzzleEntry {
+            label,
+            variant,
+            member_indices: entries,
+            clue: text,
+        };
+        match variant {
+            EntryVariant::Across => self.across_entries.push(e),
+            EntryVariant::Down => self.down_entries.push(e),
+        }
+    }
+
+    // Returns false, changing nothing, if there's no such entry, e.g. because
+    // an edit renumbered the grid since the label was looked up.
+    pub fn set_clue_text(&mut self, label: u32, variant: EntryVariant, text: String) -> bool {
+        let entries = match variant {
+            EntryVariant::Across => &self.across_entries,
+            EntryVariant::Down => &self.down_entries,
+        };
+        let index = match entries.iter().position(|x| x.label == label) {
+            Some(i) => i,
+            None => { return false; },
+        };
+        let before = self.grid_state();
+        self.set_entry_clue(variant, index, text);
+        self.history.record(puzzle_history::EditKind::Clue, &before, &self.squares, &self.marks);
+        true
+    }
+
+    // The letters of an entry, with '.' for empty squares.
+    fn entry_answer(&self, members: &[usize]) -> String {
+        members.iter().map(|i| {
+            match &self.squares[*i].content {
+                SquareContents::TextContent(s,_m) if !s.is_empty() => s.clone(),
+                _ => ".".to_string(),
+            }
+        }).collect()
+    }
+
+    fn placed_clues(&self) -> Vec<PlacedClue> {
+        self.across_entries.iter().chain(self.down_entries.iter())
+            .filter(|e| !e.clue.is_empty())
+            .map(|e| {
+                let mut members = e.member_indices.clone();
+                members.sort_unstable();
+                PlacedClue { variant: e.variant, members, answer: self.entry_answer(&e.member_indices), clue: e.clue.clone() }
+            })
+            .collect()
+    }
+
+    // Sets a clue without recording an undo step, for clues moved by another edit.
+    fn set_entry_clue(&mut self, variant: EntryVariant, index: usize, text: String) {
+        let entry = match variant {
+            EntryVariant::Across => &mut self.across_entries[index],
+            EntryVariant::Down => &mut self.down_entries[index],
+        };
+        entry.clue = text.clone();
+        let first = entry.member_indices[0];
+        match variant {
+            EntryVariant::Across => { self.squares[first].across_clue_text = Some(text); },
+            EntryVariant::Down => { self.squares[first].down_clue_text = Some(text); },
+        }
+    }
+
+    // Puts clues back after a structural edit and calculate_clues. An entry keeps
+    // its clue only if it covers exactly the same squares as before, since
+    // calculate_clues would otherwise leave it whatever clue started on its first
+    // square. A clue whose entry is gone goes to an entry spelling the same
+    // answer, or failing that to the clue bin.
+    fn rehome_clues(&mut self, before: Vec<PlacedClue>) {
+        let mut orphans: Vec<Option<PlacedClue>> = before.into_iter().map(Some).collect();
+        for variant in [EntryVariant::Across, EntryVariant::Down] {
+            let count = match variant {
+                EntryVariant::Across => self.across_entries.len(),
+                EntryVariant::Down => self.down_entries.len(),
+            };
+            for i in 0..count {
+                let mut members = match variant {
+                    EntryVariant::Across => self.across_entries[i].member_indices.clone(),
+                    EntryVariant::Down => self.down_entries[i].member_indices.clone(),
+                };
+                members.sort_unstable();
+                let same = orphans.iter_mut().find(|c| matches!(c, Some(c) if c.variant == variant && c.members == members));
+                let clue = match same {
+                    Some(c) => c.take().map(|c| c.clue).unwrap_or_default(),
+                    None => String::new(),
+                };
+                self.set_entry_clue(variant, i, clue);
+            }
+        }
+
+        for c in orphans.into_iter().flatten() {
+            match self.unclued_entry_spelling(&c.answer, c.variant) {
+                Some((v, i)) => self.set_entry_clue(v, i, c.clue),
+                None => self.clue_bin.push(BinnedClue { answer: c.answer, clue: c.clue }),
+            }
+        }
+        // Clues already in the bin go back too once their answer turns up again.
+        let mut i = 0;
+        while i < self.clue_bin.len() {
+            match self.unclued_entry_spelling(&self.clue_bin[i].answer, EntryVariant::Across) {
+                Some((v, e)) => {
+                    let binned = self.clue_bin.remove(i);
+                    self.set_entry_clue(v, e, binned.clue);
+                },
+                None => { i += 1; },
+            }
+        }
+    }
+
+    // An entry without a clue whose letters spell out a complete answer,
+    // preferring one running the given way.
+    fn unclued_entry_spelling(&self, answer: &str, variant: EntryVariant) -> Option<(EntryVariant, usize)> {
+        if answer.is_empty() || answer.contains('.') {
+            return None;
+        }
+        let other = match variant {
+            EntryVariant::Across => EntryVariant::Down,
+            EntryVariant::Down => EntryVariant::Across,
+        };
+        [variant, other].iter().find_map(|v| {
+            let entries = match v {
+                EntryVariant::Across => &self.across_entries,
+                EntryVariant::Down => &self.down_entries,
+            };
+            entries.iter().position(|e| e.clue.is_empty() && self.entry_answer(&e.member_indices) == answer).map(|i| (*v, i))
+        })
+    }
+
+    // Keeps the clue bin in step with an undo or redo, which puts clue text back
+    // on squares directly. Clues brought back come out of the bin, and clues taken
+    // off the grid by undoing a structural or bin edit go into it. Undoing a clue
+    // typed by hand just drops it.
+    fn rebin_clues(&mut self, before: Vec<PlacedClue>, kind: puzzle_history::EditKind) {
+        // Pair each clue with where it is now: the same entry first, then an
+        // entry spelling the same answer, and only then the same text anywhere,
+        // so repeated clues like "See 12-Across" are told apart.
+        let mut before: Vec<Option<PlacedClue>> = before.into_iter().map(Some).collect();
+        let mut after: Vec<Option<PlacedClue>> = self.placed_clues().into_iter().map(Some).collect();
+        let pairs = |pass: usize, b: &PlacedClue, a: &PlacedClue| a.clue == b.clue && match pass {
+            0 => a.variant == b.variant && a.members == b.members,
+            1 => a.answer == b.answer,
+            _ => true,
+        };
+        for pass in 0..3 {
+            for b in before.iter_mut() {
+                let found = match b {
+                    Some(b) => after.iter().position(|a| matches!(a, Some(a) if pairs(pass, b, a))),
+                    None => None,
+                };
+                if let Some(i) = found {
+                    after[i] = None;
+                    *b = None;
+                }
+            }
+        }
+
+        // Whatever is placed now but wasn't before came back out of the bin.
+        for a in after.into_iter().flatten() {
+            let binned = self.clue_bin.iter().position(|b| b.clue == a.clue && b.answer == a.answer)
+                .or_else(|| self.clue_bin.iter().position(|b| b.clue == a.clue));
+            if let Some(i) = binned {
+                self.clue_bin.remove(i);
+            }
+        }
+        if !matches!(kind, puzzle_history::EditKind::Structure | puzzle_history::EditKind::ClueFromBin) {
+            return;
+        }
+        for c in before.into_iter().flatten() {
+            self.clue_bin.push(BinnedClue { answer: c.answer, clue: c.clue });
+        }
+    }
+
+    // Gives a clue from the bin to an entry, putting any clue the entry had in
+    // the bin instead.
+    pub fn use_binned_clue(&mut self, bin_index: usize, label: u32, variant: EntryVariant) {
+        let i = match self.entry_index(label, variant) {
+            Some(i) if bin_index < self.clue_bin.len() => i,
+            _ => { return; },
+        };
+        let entry = match variant {
+            EntryVariant::Across => &self.across_entries[i],
+            EntryVariant::Down => &self.down_entries[i],
+        };
+        let (old_clue, answer) = (entry.clue.clone(), self.entry_answer(&entry.member_indices));
+        let binned = self.clue_bin.remove(bin_index);
+        if !old_clue.is_empty() {
+            self.clue_bin.push(BinnedClue { answer, clue: old_clue });
+        }
+        let before = self.grid_state();
+        self.set_entry_clue(variant, i, binned.clue);
+        self.history.record(puzzle_history::EditKind::ClueFromBin, &before, &self.squares, &self.marks);
+    }
+
+    fn entry_index(&self, label: u32, variant: EntryVariant) -> Option<usize> {
+        match variant {
+            EntryVariant::Across => self.across_entries.iter().position(|e| e.label == label),
+            EntryVariant::Down => self.down_entries.iter().position(|e| e.label == label),
+        }
+    }
+
+    pub fn discard_binned_clue(&mut self, bin_index: usize) {
+        if bin_index < self.clue_bin.len() {
+            self.clue_bin.remove(bin_index);
+        }
+    }
+
+    // Copies the width x height rectangle whose top-left square is (x, y). The
+    // rectangle is clipped to the grid.
+    pub fn copy_region(&self, x: usize, y: usize, width: usize, height: usize) -> grid_region::GridRegion {
+        let width = width.min(self.width.saturating_sub(x));
+        let height = height.min(self.height.saturating_sub(y));
+        let mut squares = Vec::with_capacity(width * height);
+        for ry in y..y + height {
+            for rx in x..x + width {
+                squares.push(self.squares[ry * self.width + rx].content.clone());
+            }
+        }
+        grid_region::GridRegion { width, height, squares }
+    }
+
+    // Overwrites the squares under a region placed with its top-left at (x, y),
+    // as one undo step. Whatever hangs off the grid is dropped, and symmetry is
+    // left for the user to restore.
+    pub fn paste_region(&mut self, x: usize, y: usize, region: &grid_region::GridRegion) {
+        if self.fill_only {
+            return;
+        }
+        let before = self.grid_state();
+        let clues = self.placed_clues();
+        for ry in 0..region.height.min(self.height.saturating_sub(y)) {
+            for rx in 0..region.width.min(self.width.saturating_sub(x)) {
+                let index = (y + ry) * self.width + x + rx;
+                let content = region.at(rx, ry).clone();
+                if content == SquareContents::Blocker {
+                    self.set_blocker(index, true);
+                } else {
+                    self.squares[index].content = content;
+                }
+            }
+        }
+        self.calculate_clues();
+        self.rehome_clues(clues);
+        self.history.record(puzzle_history::EditKind::Structure, &before, &self.squares, &self.marks);
+    }
+
+    // Turns the width x height rectangle at (x, y) into empty squares without
+    // modifiers, as one undo step.
+    pub fn clear_region(&mut self, x: usize, y: usize, width: usize, height: usize) {
+        if self.fill_only {
+            return;
+        }
+        let before = self.grid_state();
+        let clues = self.placed_clues();
+        for ry in y..(y + height).min(self.height) {
+            for rx in x..(x + width).min(self.width) {
+                let index = ry * self.width + rx;
+                self.set_blocker(index, false);
+                self.squares[index].content = SquareContents::TextContent(String::new(), None);
+            }
+        }
+        self.calculate_clues();
+        self.rehome_clues(clues);
+        self.history.record(puzzle_history::EditKind::Structure, &before, &self.squares, &self.marks);
+    }
+
+    // Rearranges the whole grid and renumbers it. Each clue stays with the
+    // squares of its entry, even when the entry changes direction. Returns false
+    // for solvable grids, which can't be changed.
+    pub fn transform(&mut self, t: GridTransform) -> bool {
+        if self.fill_only {
+            return false;
+        }
+        let (w, h) = (self.width as i32, self.height as i32);
+        let (new_w, new_h) = match t {
+            GridTransform::Rotate | GridTransform::Transpose => (h, w),
+            _ => (w, h),
+        };
+        let moved = |x: i32, y: i32| -> usize {
+            let (nx, ny) = match t {
+                GridTransform::Rotate => (h - 1 - y, x),
+                GridTransform::FlipHorizontal => (w - 1 - x, y),
+                GridTransform::FlipVertical => (x, h - 1 - y),
+                GridTransform::Transpose => (y, x),
+                GridTransform::Shift(dx, dy) => ((x + dx).rem_euclid(w), (y + dy).rem_euclid(h)),
+            };
+            (ny * new_w + nx) as usize
+        };
+
+        // Clues are found again afterwards by where their squares end up.
+        let swaps_direction = matches!(t, GridTransform::Rotate | GridTransform::Transpose);
+        let mut clues = self.placed_clues();
+        for c in clues.iter_mut() {
+            c.members = c.members.iter().map(|i| moved(*i as i32 % w, *i as i32 / w)).collect();
+            c.members.sort_unstable();
+            if swaps_direction {
+                c.variant = match c.variant {
+                    EntryVariant::Across => EntryVariant::Down,
+                    EntryVariant::Down => EntryVariant::Across,
+                };
+            }
+        }
+
+        let before = self.grid_state();
+        let mut squares: Vec<Square> = Vec::with_capacity(self.squares.len());
+        for y in 0..new_h as u32 {
+            for x in 0..new_w as u32 {
+                squares.push(Square::new(x, y));
+            }
+        }
+        for (i, sq) in self.squares.iter().enumerate() {
+            squares[moved(i as i32 % w, i as i32 / w)].content = sq.content.clone();
+        }
+        self.squares = squares;
+
+        let resized = new_w != w;
+        if resized {
+            self.width = new_w as usize;
+            self.height = new_h as usize;
+            self.variant = PuzzleType::Custom(self.width, self.height);
+        }
+        if swaps_direction {
+            // The mirror lines swap over along with the sides.
+            self.symmetry = match self.symmetry {
+                Symmetry::MirrorLeftRight => Symmetry::MirrorTopBottom,
+                Symmetry::MirrorTopBottom => Symmetry::MirrorLeftRight,
+                s => s,
+            };
+        }
+        self.calculate_clues();
+
+        self.rehome_clues(clues);
+
+        // Undo steps are recorded square by square, which only makes sense while
+        // the squares stay where they are.
+        if resized {
+            self.history.clear();
+        } else {
+            self.history.record(puzzle_history::EditKind::Structure, &before, &self.squares, &self.marks);
+        }
+        true
+    }
+
+    // Writes letters into squares that are still empty, as one undo step.
+    pub fn apply_fill(&mut self, fill: &[(usize, char)]) {
+        let before = self.grid_state();
+        for (index, c) in fill.iter() {
+            if let SquareContents::TextContent(s,modifier_option) = &self.squares[*index].content {
+                if s.is_empty() {
+                    self.squares[*index].content = SquareContents::TextContent(c.to_string(),modifier_option.clone());
+                }
+            }
+        }
+        self.history.record(puzzle_history::EditKind::Fill, &before, &self.squares, &self.marks);
+    }
+
+    // Writes a word into the empty squares of an entry, as one undo step.
+    pub fn fill_entry(&mut self, label: u32, variant: EntryVariant, word: &str) {
+        let entries = match variant {
+            EntryVariant::Across => &self.across_entries,
+            EntryVariant::Down => &self.down_entries,
+        };
+        let fill: Vec<(usize, char)> = match entries.iter().find(|e| e.label == label) {
+            Some(e) => e.member_indices.iter().cloned().zip(word.chars()).collect(),
+            None => Vec::new(),
+        };
+        self.apply_fill(&fill);
+    }
+
+    pub fn undo(&mut self) -> bool {
+        let clues = self.placed_clues();
+        let undone = self.history.undo(&mut self.squares, &mut self.marks);
+        if let Some(kind) = undone {
+            self.calculate_clues();
+            self.rebin_clues(clues, kind);
+        }
+        undone.is_some()
+    }
+
+    pub fn redo(&mut self) -> bool {
+        let clues = self.placed_clues();
+        let redone = self.history.redo(&mut self.squares, &mut self.marks);
+        if let Some(kind) = redone {
+            self.calculate_clues();
+            self.rebin_clues(clues, kind);
+        }
+        redone.is_some()
+    }
+
+    fn grid_state(&self) -> puzzle_history::GridState {
+        puzzle_history::GridState {
+            squares: self.squares.clone(),
+            marks: self.marks.clone(),
+        }
+    }
+
+    // Stops subsequent typing from being merged into the current undo step.
+    pub fn end_edit_group(&mut self) {
+        self.history.end_group();
+    }
+
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    fn xy_to_index(&self, x: u32, y: u32) -> usize {
+        (y * self.width as u32 + x) as usize
+    }
+
+    pub fn get_clue_entries(&self, x: u32, y: u32) -> (Option<&PuzzleEntry>,Option<&PuzzleEntry>) {
+        let sq = self.at(x,y);
+        match &sq.content {
+            SquareContents::Blocker => {
+                (None,None)
+            },
+            SquareContents::TextContent(_s,_m) => {
+                let across = if let Some(a_entry) = sq.across_entry {
+                    self.across_entries.iter().find(|x| x.label == a_entry)
+                } else {
+                    None
+                };
+                let down = if let Some(d_entry) = sq.down_entry {
+                    self.down_entries.iter().find(|x| x.label == d_entry)
+                } else {
+                    None
+                };
+                (across,down)
+            }
+        }
+    }
+
+    pub fn get_square_clue_texts(&self, x: u32, y: u32) -> (String,String) {
+        let (a,d) = self.get_clue_entries(x,y);
+
+        let across = match a {
+            Some(entry) => {
+                let clue_t = entry.clue.clone();
+                entry.label.to_string() + "A: " + &clue_t
+            },
+            None => {
+                "".to_string()
+            },
+        };
+        let down = match d {
+            Some(entry) => {
+                let clue_t = entry.clue.clone();
+                entry.label.to_string() + "D: " + &clue_t
+            },
+            None => {
+                "".to_string()
+            },
+        };
+
+        (across,down)
+    }
+
+    // Single unchecked squares get entries too, but they aren't words.
+    pub fn word_count(&self) -> usize {
+        self.across_entries.iter().chain(self.down_entries.iter()).filter(|e| e.member_indices.len() > 1).count()
+    }
+
+    pub fn blocker_count(&self) -> usize {
+        self.squares.iter().filter(|s| s.content == SquareContents::Blocker).count()
+    }
+
+    pub fn validate(&self) -> puzzle_validation::ValidationReport {
+        puzzle_validation::validate(self)
+    }
+
+    // The current grid in the form stored as a solution.
+    pub fn contents_as_solution(&self) -> Vec<String> {
+        self.squares.iter().map(|sq| {
+            match &sq.content {
+                SquareContents::TextContent(s,_m) => s.clone(),
+                SquareContents::Blocker => "#".to_string(),
+            }
+        }).collect()
+    }
+
+    pub fn is_solved(&self) -> bool {
+        if let Some(solution) = &self.solution {
+            return self.squares.iter().zip(solution.iter()).all(|(sq, answer)| {
+                match &sq.content {
+                    SquareContents::TextContent(s,_m) => s.to_uppercase() == answer.to_uppercase(),
+                    SquareContents::Blocker => true,
+                }
+            });
+        }
+        match &self.solution_check {
+            Some(c) => c.matches(&self.contents_as_solution()),
+            None => false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SolveScope {
+    Square,
+    Word,
+    Puzzle,
+}
+
+fn parse_tags(s: &str) -> Vec<String> {
+    s.split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect()
+}
+
+pub fn default_symmetry(p: &PuzzleType) -> Symmetry {
+    match p {
+        PuzzleType::Weekday | PuzzleType::Sunday => Symmetry::Rotational,
+        _ => Symmetry::None,
+    }
+}
+
+pub const MAX_CUSTOM_DIM: usize = 50;
+
+pub fn is_valid_custom_dim(width: usize, height: usize) -> bool {
+    (1..=MAX_CUSTOM_DIM).contains(&width) && (1..=MAX_CUSTOM_DIM).contains(&height)
+}
+
+// Returns the (width, height) of a puzzle type.
+pub fn match_puzzle_dim(p: &PuzzleType) -> (usize, usize) {
+    match p {
+        PuzzleType::Mini => (5, 5),
+        PuzzleType::Weekday => (15, 15),
+        PuzzleType::WeekdayAsymmetric => (15, 15),
+        PuzzleType::Sunday => (21, 21),
+        PuzzleType::Custom(w, h) => (*w, *h),
+    }
+}
\ No newline at end of file
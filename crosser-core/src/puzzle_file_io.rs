@@ -0,0 +1,1267 @@
+use serde_json::json;
+use crate::pdf_export;
+use crate::puzzle_backend;
+use crate::solution_check;
+use crate::svg_export;
+use crate::text_export;
+
+pub fn file_extension(path: &str) -> String {
+    std::path::Path::new(path).extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase()
+}
+
+// Paths saved without an extension get .cro, the format they'd be written in anyway.
+pub fn with_default_extension(path: &str) -> String {
+    if !file_extension(path).is_empty() || std::path::Path::new(path).is_dir() {
+        return path.to_string();
+    }
+    format!("{}.cro", path.trim_end_matches('.'))
+}
+
+// Formats are picked by file extension, with .cro for anything unrecognized.
+pub fn get_puzzle_from_file(path_str: String) -> std::result::Result<puzzle_backend::Puzzle, String> {
+    match file_extension(&path_str).as_str() {
+        "puz" => get_puzzle_from_puz(path_str),
+        "ipuz" => get_puzzle_from_ipuz(path_str),
+        _ => get_puzzle_from_cro(path_str).map_err(|e| e.to_string()),
+    }
+}
+
+pub fn write_puzzle_to_file(puzzle: &puzzle_backend::Puzzle, path_str: String, save_solvable_grid: bool, scramble_solution: bool) -> std::result::Result<(),std::io::Error> {
+    match file_extension(&path_str).as_str() {
+        "puz" => write_puzzle_to_puz(puzzle, path_str, None),
+        "ipuz" => write_puzzle_to_ipuz(puzzle, path_str),
+        _ => write_puzzle_to_cro(puzzle, path_str, save_solvable_grid, scramble_solution),
+    }
+}
+
+// What belongs in a square for the answer grids of .puz and .ipuz files. A
+// solvable grid's answers live in its solution, not in what the solver typed.
+fn square_answer(puzzle: &puzzle_backend::Puzzle, index: usize) -> String {
+    match (&puzzle.squares[index].content, &puzzle.solution) {
+        (puzzle_backend::SquareContents::Blocker, _) => String::new(),
+        (_, Some(solution)) if puzzle.fill_only => solution[index].clone(),
+        (_, None) if puzzle.fill_only => String::new(),
+        (puzzle_backend::SquareContents::TextContent(s,_m), _) => s.clone(),
+    }
+}
+
+// Exports can't be opened again. Anything that isn't .svg or .txt is a PDF.
+pub fn export_puzzle_to_file(puzzle: &puzzle_backend::Puzzle, path_str: String, pdf_options: &pdf_export::PdfOptions, show_fill: bool) -> std::result::Result<(),std::io::Error> {
+    match file_extension(&path_str).as_str() {
+        "svg" => svg_export::write_puzzle_to_svg(puzzle, path_str, show_fill),
+        "txt" => text_export::write_puzzle_to_text(puzzle, path_str, show_fill),
+        _ => pdf_export::write_puzzle_to_pdf(puzzle, path_str, pdf_options),
+    }
+}
+
+// The .cro layout this build writes, described by docs/cro.schema.json. Files
+// without a "format_version" key predate versioning and are version 1.
+pub const CRO_FORMAT_VERSION: u64 = 3;
+
+// Every top-level key this build reads or writes. Anything else is kept in
+// Puzzle::unknown_fields so saving doesn't drop data from newer builds.
+const CRO_KEYS: [&str; 20] = [
+    "format_version", "variant", "width", "height", "title", "author", "editor", "copyright", "date", "difficulty",
+    "notes", "tags", "symmetry", "squares", "across_clues", "down_clues", "clue_bin", "hash_string", "entry_checksums",
+    "solution",
+];
+
+pub fn write_puzzle_to_cro(puzzle: &puzzle_backend::Puzzle, path_str: String, save_solvable_grid: bool, scramble_solution: bool) -> std::result::Result<(),std::io::Error> {
+
+    let variant_str = match puzzle.variant {
+        puzzle_backend::PuzzleType::Mini => "mini".to_string(),
+        puzzle_backend::PuzzleType::Weekday => "weekday".to_string(),
+        puzzle_backend::PuzzleType::WeekdayAsymmetric => "weekday_asymmetric".to_string(),
+        puzzle_backend::PuzzleType::Sunday => "sunday".to_string(),
+        puzzle_backend::PuzzleType::Custom(_,_) => "custom".to_string(),
+    };
+
+    let symmetry_str = match puzzle.symmetry {
+        puzzle_backend::Symmetry::None => "none",
+        puzzle_backend::Symmetry::Rotational => "rotational",
+        puzzle_backend::Symmetry::MirrorLeftRight => "mirror_left_right",
+        puzzle_backend::Symmetry::MirrorTopBottom => "mirror_top_bottom",
+        puzzle_backend::Symmetry::Diagonal => "diagonal",
+        puzzle_backend::Symmetry::FourWay => "four_way",
+    };
+
+    let mut sq_strs: Vec<String> = Vec::new();
+    for sq in puzzle.squares.iter() {
+        let cont = match &sq.content {
+            puzzle_backend::SquareContents::Blocker => {
+                "#".to_string()
+            },
+            puzzle_backend::SquareContents::TextContent(s,m) => {
+                let mod_str = match m {
+                    None => "/n".to_string(),
+                    Some(puzzle_backend::SquareModifier::Shading) => "/s".to_string(),
+                    Some(puzzle_backend::SquareModifier::Circle) => "/c".to_string(),
+                };
+                let mut c = if save_solvable_grid {
+                    "".to_string()
+                } else {
+                    s.clone()
+                };
+                c.push_str(&mod_str);
+                c
+            }
+        };
+        sq_strs.push(cont);
+    }
+
+    let mut across_clues: serde_json::Map<String, serde_json::Value> = serde_json::Map::new();
+    for entry in puzzle.across_entries.iter() {
+        across_clues.insert(entry.label.to_string(), serde_json::Value::String(entry.clue.clone()));
+    }
+    let mut down_clues: serde_json::Map<String, serde_json::Value> = serde_json::Map::new();
+    for entry in puzzle.down_entries.iter() {
+        down_clues.insert(entry.label.to_string(), serde_json::Value::String(entry.clue.clone()));
+    }
+
+    // A solvable grid carries its answers so solvers can check and reveal.
+    // Grids being solved keep the answers they were loaded with, along with
+    // whatever has been typed unless the grid is saved empty.
+    let (check, solution_value) = if save_solvable_grid || puzzle.fill_only {
+        let solution = if puzzle.fill_only {
+            puzzle.solution.clone()
+        } else {
+            Some(puzzle.contents_as_solution())
+        };
+        match solution {
+            Some(sol) => (Some(solution_check::SolutionCheck::new(&sol, puzzle)), cro_solution_value(&sol, scramble_solution)),
+            None => (puzzle.solution_check.clone(), serde_json::Value::Null),
+        }
+    } else {
+        (None, serde_json::Value::Null)
+    };
+    let hash_string = match &check {
+        Some(c) => c.to_hash_string(),
+        None => "NULL".to_string(),
+    };
+    let entry_checksums = match &check {
+        Some(c) if c.has_entry_checksums() => {
+            let mut across = serde_json::Map::new();
+            let mut down = serde_json::Map::new();
+            for (label, variant, checksum) in c.entry_checksums() {
+                let map = match variant {
+                    puzzle_backend::EntryVariant::Across => &mut across,
+                    puzzle_backend::EntryVariant::Down => &mut down,
+                };
+                map.insert(label.to_string(), serde_json::Value::String(checksum.to_string()));
+            }
+            json!({ "across": across, "down": down })
+        },
+        _ => serde_json::Value::Null,
+    };
+
+    let mut json_rep = json!({
+        "format_version": CRO_FORMAT_VERSION,
+        "variant": variant_str, 
+        "title": puzzle.title,
+        "author": puzzle.author,
+        "editor": puzzle.editor,
+        "copyright": puzzle.copyright,
+        "date": puzzle.date,
+        "difficulty": puzzle.difficulty,
+        "notes": puzzle.notes,
+        "tags": puzzle.tags,
+        "width": puzzle.width(),
+        "height": puzzle.height(),
+        "symmetry": symmetry_str,
+        "squares": sq_strs,
+        "across_clues": across_clues,
+        "down_clues": down_clues,
+        "clue_bin": puzzle.clue_bin.iter().map(|b| json!({ "answer": b.answer, "clue": b.clue })).collect::<Vec<serde_json::Value>>(),
+        "hash_string": hash_string,
+        "entry_checksums": entry_checksums,
+        "solution": solution_value,
+    });
+    if let serde_json::Value::Object(m) = &mut json_rep {
+        for (key, value) in puzzle.unknown_fields.iter() {
+            m.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+    }
+
+    std::fs::write(path_str,json_rep.to_string())
+}
+
+// Everything that can go wrong reading a .cro file. Variants name the field,
+// square or clue that is wrong so a bad file can be fixed by hand.
+#[derive(Debug)]
+pub enum CroError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    NotAnObject,
+    MissingField(&'static str),
+    // The field is present but has the wrong type.
+    InvalidField(&'static str),
+    UnknownVariant(String),
+    InvalidDimensions,
+    UnknownSymmetry(String),
+    SquareCount { expected: usize, found: usize },
+    InvalidSquare(usize),
+    UnknownModifier { index: usize, modifier: String },
+    InvalidClueLabel { variant: puzzle_backend::EntryVariant, label: String },
+    InvalidClueText { variant: puzzle_backend::EntryVariant, label: u32 },
+    UnknownClue { variant: puzzle_backend::EntryVariant, label: u32 },
+    InvalidSolutionCheck(String),
+    InvalidEntryChecksum { variant: puzzle_backend::EntryVariant, label: String },
+    // The stored solution doesn't fit the grid or doesn't pass the solution check.
+    SolutionMismatch,
+}
+
+fn cro_variant_name(variant: puzzle_backend::EntryVariant) -> &'static str {
+    match variant {
+        puzzle_backend::EntryVariant::Across => "across",
+        puzzle_backend::EntryVariant::Down => "down",
+    }
+}
+
+impl std::fmt::Display for CroError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CroError::Io(e) => write!(f, "{}", e),
+            CroError::Json(e) => write!(f, "The file is not valid JSON: {}", e),
+            CroError::NotAnObject => write!(f, "The file is not a JSON object."),
+            CroError::MissingField(field) => write!(f, "The file has no \"{}\" field.", field),
+            CroError::InvalidField(field) => write!(f, "The \"{}\" field has the wrong type.", field),
+            CroError::UnknownVariant(s) => write!(f, "Unknown puzzle type \"{}\".", s),
+            CroError::InvalidDimensions => write!(f, "Custom grids need a width and height from 1 to {}.", puzzle_backend::MAX_CUSTOM_DIM),
+            CroError::UnknownSymmetry(s) => write!(f, "Unknown symmetry \"{}\".", s),
+            CroError::SquareCount { expected, found } => write!(f, "The grid needs {} squares but the file has {}.", expected, found),
+            CroError::InvalidSquare(index) => write!(f, "Square {} is not a string.", index),
+            CroError::UnknownModifier { index, modifier } => write!(f, "Square {} has an unknown modifier \"{}\".", index, modifier),
+            CroError::InvalidClueLabel { variant, label } => write!(f, "The {} clue label \"{}\" is not a number.", cro_variant_name(*variant), label),
+            CroError::InvalidClueText { variant, label } => write!(f, "The text of clue {} {} is not a string.", label, cro_variant_name(*variant)),
+            CroError::UnknownClue { variant, label } => write!(f, "There is a clue for {} {} which is not in the grid.", label, cro_variant_name(*variant)),
+            CroError::InvalidSolutionCheck(e) => write!(f, "{}", e),
+            CroError::InvalidEntryChecksum { variant, label } => write!(f, "The {} entry checksum for \"{}\" is invalid.", cro_variant_name(*variant), label),
+            CroError::SolutionMismatch => write!(f, "The stored solution does not match the grid."),
+        }
+    }
+}
+
+impl std::error::Error for CroError {}
+
+impl From<std::io::Error> for CroError {
+    fn from(e: std::io::Error) -> Self {
+        CroError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for CroError {
+    fn from(e: serde_json::Error) -> Self {
+        CroError::Json(e)
+    }
+}
+
+pub fn get_puzzle_from_cro(path_str: String) -> std::result::Result<puzzle_backend::Puzzle, CroError> {
+    let file_contents = std::fs::read_to_string(path_str)?;
+    get_puzzle_from_cro_str(&file_contents)
+}
+
+// Malformed files come back as errors, never panics.
+pub fn get_puzzle_from_cro_str(file_contents: &str) -> std::result::Result<puzzle_backend::Puzzle, CroError> {
+    let value_contents = migrate_cro(serde_json::from_str(file_contents)?)?;
+    let variant: puzzle_backend::PuzzleType = match &value_contents["variant"] {
+        serde_json::Value::String(s) => {
+            match s.as_str() {
+                "mini" => puzzle_backend::PuzzleType::Mini,
+                "weekday" => puzzle_backend::PuzzleType::Weekday,
+                "weekday_asymmetric" => puzzle_backend::PuzzleType::WeekdayAsymmetric,
+                "sunday" => puzzle_backend::PuzzleType::Sunday,
+                "custom" => {
+                    let w = value_contents["width"].as_u64();
+                    let h = value_contents["height"].as_u64();
+                    match (w, h) {
+                        (Some(w), Some(h)) if puzzle_backend::is_valid_custom_dim(w as usize, h as usize) => puzzle_backend::PuzzleType::Custom(w as usize, h as usize),
+                        _ => { return Err(CroError::InvalidDimensions); },
+                    }
+                },
+                _ => { return Err(CroError::UnknownVariant(s.clone())); },
+            }
+        }
+        serde_json::Value::Null => {
+            return Err(CroError::MissingField("variant"));
+        }
+        _ => {
+            return Err(CroError::InvalidField("variant"));
+        }
+    };
+    let mut puz = puzzle_backend::Puzzle::new(variant);
+
+    for (key, field) in [
+        ("title", puzzle_backend::MetadataField::Title),
+        ("author", puzzle_backend::MetadataField::Author),
+        ("editor", puzzle_backend::MetadataField::Editor),
+        ("copyright", puzzle_backend::MetadataField::Copyright),
+        ("date", puzzle_backend::MetadataField::Date),
+        ("difficulty", puzzle_backend::MetadataField::Difficulty),
+        ("notes", puzzle_backend::MetadataField::Notes),
+    ] {
+        match &value_contents[key] {
+            serde_json::Value::String(s) => puz.set_metadata(field, s.clone()),
+            serde_json::Value::Null => { return Err(CroError::MissingField(key)); },
+            _ => { return Err(CroError::InvalidField(key)); },
+        }
+    }
+    match &value_contents["tags"] {
+        serde_json::Value::Array(v) => {
+            for t in v.iter() {
+                match t.as_str() {
+                    Some(s) => puz.tags.push(s.to_string()),
+                    None => { return Err(CroError::InvalidField("tags")); },
+                }
+            }
+        },
+        serde_json::Value::Null => { return Err(CroError::MissingField("tags")); },
+        _ => { return Err(CroError::InvalidField("tags")); },
+    }
+
+    puz.symmetry = match &value_contents["symmetry"] {
+        serde_json::Value::String(s) => {
+            match s.as_str() {
+                "none" => puzzle_backend::Symmetry::None,
+                "rotational" => puzzle_backend::Symmetry::Rotational,
+                "mirror_left_right" => puzzle_backend::Symmetry::MirrorLeftRight,
+                "mirror_top_bottom" => puzzle_backend::Symmetry::MirrorTopBottom,
+                "diagonal" => puzzle_backend::Symmetry::Diagonal,
+                "four_way" => puzzle_backend::Symmetry::FourWay,
+                _ => { return Err(CroError::UnknownSymmetry(s.clone())); },
+            }
+        },
+        serde_json::Value::Null => { return Err(CroError::MissingField("symmetry")); },
+        _ => { return Err(CroError::InvalidField("symmetry")); },
+    };
+
+    let sqs = match &value_contents["squares"] {
+        serde_json::Value::Array(v) => {
+            v
+        },
+        serde_json::Value::Null => {
+            return Err(CroError::MissingField("squares"));
+        },
+        _ => {
+            return Err(CroError::InvalidField("squares"));
+        },
+    };
+    if sqs.len() != puz.squares.len() {
+        return Err(CroError::SquareCount { expected: puz.squares.len(), found: sqs.len() });
+    }
+    for (sq_index, sq_value) in sqs.iter().enumerate() {
+        match sq_value {
+            serde_json::Value::String(s) => {
+                let slash_index = s.find('/');
+                match slash_index {
+                    Some(u) => {
+                        let contents = s[0..u].to_string();
+                        let modifier = match &s[u+1..] {
+                            "n" => None,
+                            "c" => Some(puzzle_backend::SquareModifier::Circle),
+                            "s" => Some(puzzle_backend::SquareModifier::Shading),
+                            m => { return Err(CroError::UnknownModifier { index: sq_index, modifier: m.to_string() }); }
+                        };
+                        puz.squares[sq_index].content = puzzle_backend::SquareContents::TextContent(contents,modifier);
+                    },
+                    None => {
+                        puz.squares[sq_index].across_clue_text = None;
+                        puz.squares[sq_index].down_clue_text = None;
+                        puz.squares[sq_index].content = puzzle_backend::SquareContents::Blocker
+                    }
+                }
+            },
+            _ => {
+                return Err(CroError::InvalidSquare(sq_index));
+            }
+        }
+    }
+    // Squares are constructed, we can calculate clues.
+    puz.calculate_clues();
+
+    // Now we can use the built-in puz clue assignment.
+    for (key, variant) in [("across_clues", puzzle_backend::EntryVariant::Across), ("down_clues", puzzle_backend::EntryVariant::Down)] {
+        let clues = match &value_contents[key] {
+            serde_json::Value::Object(m) => {
+                m
+            },
+            serde_json::Value::Null => {
+                return Err(CroError::MissingField(key));
+            },
+            _ => {
+                return Err(CroError::InvalidField(key));
+            },
+        };
+        for (label_str,clue_content) in clues.iter() {
+            let label = match label_str.parse::<u32>() {
+                Ok(l) => l,
+                Err(_) => { return Err(CroError::InvalidClueLabel { variant, label: label_str.clone() }); },
+            };
+            let clue_text = match clue_content {
+                serde_json::Value::String(s) => {
+                    s
+                },
+                _ => {
+                    return Err(CroError::InvalidClueText { variant, label });
+                }
+            };
+            if !puz.set_clue_text(label, variant, clue_text.clone()) {
+                return Err(CroError::UnknownClue { variant, label });
+            }
+        }
+    }
+
+    match &value_contents["clue_bin"] {
+        serde_json::Value::Array(v) => {
+            for b in v.iter() {
+                match (b["answer"].as_str(), b["clue"].as_str()) {
+                    (Some(answer), Some(clue)) => puz.clue_bin.push(puzzle_backend::BinnedClue { answer: answer.to_string(), clue: clue.to_string() }),
+                    _ => { return Err(CroError::InvalidField("clue_bin")); },
+                }
+            }
+        },
+        serde_json::Value::Null => { return Err(CroError::MissingField("clue_bin")); },
+        _ => { return Err(CroError::InvalidField("clue_bin")); },
+    }
+
+    match &value_contents["hash_string"] {
+        serde_json::Value::String(s) => {
+            match s.as_str() {
+                "NULL" => {
+                    // Do nothing, as the puzzle is not in the final, solved state.
+                },
+                _ => {
+                    puz.solution_check = Some(solution_check::SolutionCheck::parse(s).map_err(CroError::InvalidSolutionCheck)?);
+                    puz.fill_only = true;
+                }
+            }
+        },
+        serde_json::Value::Null => {
+            return Err(CroError::MissingField("hash_string"));
+        },
+        _ => {
+            return Err(CroError::InvalidField("hash_string"));
+        }
+    };
+
+    if let Some(check) = puz.solution_check.as_mut() {
+        for (key, variant) in [("across", puzzle_backend::EntryVariant::Across), ("down", puzzle_backend::EntryVariant::Down)] {
+            if let serde_json::Value::Object(m) = &value_contents["entry_checksums"][key] {
+                for (label_str, checksum) in m.iter() {
+                    match (label_str.parse::<u32>(), checksum.as_str()) {
+                        (Ok(label), Some(c)) => check.add_entry_checksum(label, variant, c.to_string()),
+                        _ => { return Err(CroError::InvalidEntryChecksum { variant, label: label_str.clone() }); },
+                    }
+                }
+            }
+        }
+    }
+
+    // Older solvable files only have the hash, which still tells when the grid is solved.
+    if !value_contents["solution"].is_null() {
+        let solution = match &puz.solution_check {
+            Some(check) => cro_read_solution(&value_contents["solution"], &puz, check)?,
+            None => { return Err(CroError::MissingField("hash_string")); },
+        };
+        puz.solution = Some(solution);
+    }
+
+    if let serde_json::Value::Object(m) = &value_contents {
+        for (key, value) in m.iter().filter(|(k, _v)| !CRO_KEYS.contains(&k.as_str())) {
+            puz.unknown_fields.insert(key.clone(), value.clone());
+        }
+    }
+
+    // Loading is not something the user should be able to undo.
+    puz.clear_history();
+
+    Ok(puz)
+}
+
+// Brings a file up to CRO_FORMAT_VERSION one version at a time. Files from
+// newer builds are read as far as this build understands them.
+fn migrate_cro(mut value: serde_json::Value) -> std::result::Result<serde_json::Value, CroError> {
+    let m = match value.as_object_mut() {
+        Some(m) => m,
+        None => { return Err(CroError::NotAnObject); },
+    };
+    let version = match m.get("format_version") {
+        None | Some(serde_json::Value::Null) => 1,
+        Some(v) => {
+            match v.as_u64() {
+                Some(n) if n >= 1 => n,
+                _ => { return Err(CroError::InvalidField("format_version")); },
+            }
+        },
+    };
+    if version < 2 {
+        migrate_cro_v1(m);
+    }
+    if version < 3 {
+        migrate_cro_v2(m);
+    }
+    Ok(value)
+}
+
+// Version 1 gained fields over time without saying so, so any of the newer ones
+// may be missing. Version 2 requires them all.
+fn migrate_cro_v1(m: &mut serde_json::Map<String, serde_json::Value>) {
+    // Files from before symmetry was a setting use the variant's default.
+    let symmetry = match m.get("variant").and_then(|v| v.as_str()) {
+        Some("weekday") | Some("sunday") => "rotational",
+        _ => "none",
+    };
+    let mut fill_in = |key: &str, default: serde_json::Value| {
+        if m.get(key).map(|v| v.is_null()).unwrap_or(true) {
+            m.insert(key.to_string(), default);
+        }
+    };
+    fill_in("title", json!("New Puzzle"));
+    for key in ["author", "editor", "copyright", "date", "difficulty", "notes"] {
+        fill_in(key, json!(""));
+    }
+    fill_in("tags", json!([]));
+    fill_in("symmetry", json!(symmetry));
+    fill_in("hash_string", json!("NULL"));
+    fill_in("entry_checksums", serde_json::Value::Null);
+    fill_in("solution", serde_json::Value::Null);
+}
+
+// Version 3 added the clue bin, which starts out empty.
+fn migrate_cro_v2(m: &mut serde_json::Map<String, serde_json::Value>) {
+    m.insert("clue_bin".to_string(), json!([]));
+}
+
+// Solutions are stored either as one string per square, or scrambled with the
+// .puz algorithm when every answer is a single letter. The key isn't stored;
+// loading finds it by checking candidates against the solution check.
+fn cro_solution_value(solution: &[String], scramble: bool) -> serde_json::Value {
+    let scrambleable = solution.iter().all(|s| s == "#" || (s.len() == 1 && s.as_bytes()[0].is_ascii_uppercase()));
+    if scramble && scrambleable {
+        let letters: Vec<u8> = solution.iter().filter(|s| *s != "#").flat_map(|s| s.bytes()).collect();
+        let nanos = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.subsec_nanos()).unwrap_or(0);
+        let key = 1000 + (nanos % 9000) as u16;
+        let scrambled = puz_scramble_letters(&letters, key);
+        json!({
+            "scrambled": true,
+            "letters": String::from_utf8_lossy(&scrambled),
+        })
+    } else {
+        json!({
+            "scrambled": false,
+            "squares": solution,
+        })
+    }
+}
+
+fn cro_read_solution(value: &serde_json::Value, puz: &puzzle_backend::Puzzle, check: &solution_check::SolutionCheck) -> std::result::Result<Vec<String>, CroError> {
+    let is_blocker: Vec<bool> = puz.squares.iter().map(|sq| sq.content == puzzle_backend::SquareContents::Blocker).collect();
+    match (&value["scrambled"], &value["letters"], &value["squares"]) {
+        (serde_json::Value::Bool(true), serde_json::Value::String(letters), _) => {
+            let letters = letters.as_bytes();
+            // Only A-Z is ever scrambled, anything else can't be shifted back.
+            if letters.len() != is_blocker.iter().filter(|b| !**b).count() || !letters.iter().all(|c| c.is_ascii_uppercase()) {
+                return Err(CroError::SolutionMismatch);
+            }
+            for key in 1000..=9999 {
+                let plain = puz_unscramble_letters(letters, key);
+                let mut next = plain.iter();
+                let solution: Vec<String> = is_blocker.iter()
+                    .map(|b| if *b { "#".to_string() } else { (*next.next().unwrap_or(&b'?') as char).to_string() })
+                    .collect();
+                if check.matches(&solution) {
+                    return Ok(solution);
+                }
+            }
+            Err(CroError::SolutionMismatch)
+        },
+        (_, _, serde_json::Value::Array(squares)) => {
+            let solution: Vec<String> = squares.iter().map(|v| v.as_str().unwrap_or("").to_string()).collect();
+            if solution.len() != is_blocker.len() || !check.matches(&solution) {
+                return Err(CroError::SolutionMismatch);
+            }
+            Ok(solution)
+        },
+        _ => Err(CroError::InvalidField("solution")),
+    }
+}
+
+// Picks the preset matching an imported grid, falling back to a custom size.
+fn guess_puzzle_type(width: usize, height: usize, blockers: &[bool]) -> std::result::Result<puzzle_backend::PuzzleType, String> {
+    let n = blockers.len();
+    match (width, height) {
+        (5, 5) => Ok(puzzle_backend::PuzzleType::Mini),
+        (15, 15) => {
+            let symmetric = (0..n).all(|i| blockers[i] == blockers[n - 1 - i]);
+            if symmetric {
+                Ok(puzzle_backend::PuzzleType::Weekday)
+            } else {
+                Ok(puzzle_backend::PuzzleType::WeekdayAsymmetric)
+            }
+        },
+        (21, 21) => Ok(puzzle_backend::PuzzleType::Sunday),
+        (w, h) if puzzle_backend::is_valid_custom_dim(w, h) => Ok(puzzle_backend::PuzzleType::Custom(w, h)),
+        (w, h) => Err(format!("A {}x{} grid is not supported.", w, h)),
+    }
+}
+
+// Across Lite .puz support. Layout and checksum rules follow the community
+// documentation of the format (version 1.3).
+const PUZ_MAGIC: &[u8] = b"ACROSS&DOWN\0";
+const PUZ_HEADER_LEN: usize = 0x34;
+const PUZ_SCRAMBLED_TAG: u16 = 0x0004;
+// GEXT flags. Circles use the documented flag; shading has no documented flag,
+// so we borrow an unused bit that other readers will ignore.
+const PUZ_GEXT_CIRCLE: u8 = 0x80;
+const PUZ_GEXT_SHADING: u8 = 0x08;
+const PUZ_EDITOR_SEPARATOR: &str = " / Ed. ";
+
+fn puz_cksum_region(data: &[u8], mut cksum: u16) -> u16 {
+    for b in data.iter() {
+        cksum = cksum.rotate_right(1);
+        cksum = cksum.wrapping_add(*b as u16);
+    }
+    cksum
+}
+
+fn puz_encode(s: &str) -> Vec<u8> {
+    s.chars().map(|c| if (c as u32) < 0x100 { c as u32 as u8 } else { b'?' }).collect()
+}
+
+fn puz_decode(b: &[u8]) -> String {
+    b.iter().map(|c| *c as char).collect()
+}
+
+// Notes only count towards the checksum from version 1.3 of the format on.
+fn puz_text_cksum(title: &[u8], author: &[u8], copyright: &[u8], clues: &[Vec<u8>], notes: &[u8], include_notes: bool, mut cksum: u16) -> u16 {
+    for s in [title, author, copyright].iter() {
+        if !s.is_empty() {
+            cksum = puz_cksum_region(s, cksum);
+            cksum = puz_cksum_region(&[0], cksum);
+        }
+    }
+    for c in clues.iter() {
+        cksum = puz_cksum_region(c, cksum);
+    }
+    if include_notes && !notes.is_empty() {
+        cksum = puz_cksum_region(notes, cksum);
+        cksum = puz_cksum_region(&[0], cksum);
+    }
+    cksum
+}
+
+// The scrambling routines operate on the solution letters read column by
+// column, skipping blockers.
+fn puz_column_major_letters(solution: &[u8], width: usize, height: usize) -> Vec<u8> {
+    let mut letters = Vec::new();
+    for x in 0..width {
+        for y in 0..height {
+            let c = solution[y * width + x];
+            if c != b'.' {
+                letters.push(c);
+            }
+        }
+    }
+    letters
+}
+
+fn puz_restore_column_major(solution: &mut [u8], width: usize, height: usize, letters: &[u8]) {
+    let mut it = letters.iter();
+    for x in 0..width {
+        for y in 0..height {
+            let index = y * width + x;
+            if solution[index] != b'.' {
+                solution[index] = *it.next().unwrap();
+            }
+        }
+    }
+}
+
+fn puz_key_digits(key: u16) -> [u8; 4] {
+    [(key / 1000 % 10) as u8, (key / 100 % 10) as u8, (key / 10 % 10) as u8, (key % 10) as u8]
+}
+
+fn puz_shift(s: &[u8], key: &[u8; 4], forward: bool) -> Vec<u8> {
+    s.iter().enumerate().map(|(i, c)| {
+        let k = key[i % 4];
+        let offset = if forward { k } else { 26 - k };
+        b'A' + (c - b'A' + offset) % 26
+    }).collect()
+}
+
+fn puz_scramble_letters(letters: &[u8], key: u16) -> Vec<u8> {
+    let key = puz_key_digits(key);
+    let mut s = letters.to_vec();
+    for k in key.iter() {
+        s = puz_shift(&s, &key, true);
+        if (*k as usize) < s.len() {
+            s.rotate_left(*k as usize);
+        }
+        // Riffle the back half into the front half.
+        let mid = s.len() / 2;
+        let mut shuffled = Vec::with_capacity(s.len());
+        for i in 0..mid {
+            shuffled.push(s[mid + i]);
+            shuffled.push(s[i]);
+        }
+        if s.len() % 2 == 1 {
+            shuffled.push(s[s.len() - 1]);
+        }
+        s = shuffled;
+    }
+    s
+}
+
+fn puz_unscramble_letters(letters: &[u8], key: u16) -> Vec<u8> {
+    let key = puz_key_digits(key);
+    let mut s = letters.to_vec();
+    for k in key.iter().rev() {
+        let mut unshuffled: Vec<u8> = s.iter().skip(1).step_by(2).cloned().collect();
+        unshuffled.extend(s.iter().step_by(2));
+        s = unshuffled;
+        if (*k as usize) < s.len() {
+            s.rotate_right(*k as usize);
+        }
+        s = puz_shift(&s, &key, false);
+    }
+    s
+}
+
+fn puz_push_extension(out: &mut Vec<u8>, title: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(title);
+    out.extend_from_slice(&(data.len() as u16).to_le_bytes());
+    out.extend_from_slice(&puz_cksum_region(data, 0).to_le_bytes());
+    out.extend_from_slice(data);
+    out.push(0);
+}
+
+// Clues in .puz files are ordered by label, with the across clue before the
+// down clue when a square starts both.
+fn puz_ordered_entries(puzzle: &puzzle_backend::Puzzle) -> Vec<&puzzle_backend::PuzzleEntry> {
+    let mut entries: Vec<&puzzle_backend::PuzzleEntry> = puzzle.across_entries.iter().chain(puzzle.down_entries.iter()).collect();
+    entries.sort_by_key(|e| (e.label, match e.variant {
+        puzzle_backend::EntryVariant::Across => 0,
+        puzzle_backend::EntryVariant::Down => 1,
+    }));
+    entries
+}
+
+pub fn write_puzzle_to_puz(puzzle: &puzzle_backend::Puzzle, path_str: String, scramble_key: Option<u16>) -> std::result::Result<(),std::io::Error> {
+    let invalid = |s: &str| std::io::Error::new(std::io::ErrorKind::InvalidInput, s.to_string());
+    let (width, height) = (puzzle.width(), puzzle.height());
+    if width > 255 || height > 255 {
+        return Err(invalid("Grid is too large for the .puz format."));
+    }
+
+    let mut solution: Vec<u8> = Vec::with_capacity(width * height);
+    let mut state: Vec<u8> = Vec::with_capacity(width * height);
+    let mut rebus_table: Vec<String> = Vec::new();
+    let mut grbs: Vec<u8> = vec![0; width * height];
+    let mut gext: Vec<u8> = vec![0; width * height];
+    for (index, sq) in puzzle.squares.iter().enumerate() {
+        match &sq.content {
+            puzzle_backend::SquareContents::Blocker => {
+                solution.push(b'.');
+                state.push(b'.');
+            },
+            puzzle_backend::SquareContents::TextContent(s,m) => {
+                let upper = square_answer(puzzle, index).to_uppercase();
+                let first = puz_encode(&upper).first().cloned().unwrap_or(b'-');
+                solution.push(first);
+                // The player's grid carries over what has been solved so far.
+                let typed = if puzzle.fill_only { puz_encode(&s.to_uppercase()).first().cloned() } else { None };
+                state.push(typed.unwrap_or(b'-'));
+                if upper.chars().count() > 1 {
+                    let key = match rebus_table.iter().position(|r| *r == upper) {
+                        Some(k) => k,
+                        None => {
+                            rebus_table.push(upper.clone());
+                            rebus_table.len() - 1
+                        },
+                    };
+                    if key >= 255 {
+                        return Err(invalid("Too many distinct rebus entries for the .puz format."));
+                    }
+                    grbs[index] = key as u8 + 1;
+                }
+                gext[index] = match m {
+                    None => 0,
+                    Some(puzzle_backend::SquareModifier::Circle) => PUZ_GEXT_CIRCLE,
+                    Some(puzzle_backend::SquareModifier::Shading) => PUZ_GEXT_SHADING,
+                };
+            },
+        }
+    }
+
+    // .puz has no editor field, so the editor goes after the author the way
+    // published files do it. There is no place for the date, difficulty or tags.
+    let title = puz_encode(&puzzle.title);
+    let author = if puzzle.editor.is_empty() {
+        puz_encode(&puzzle.author)
+    } else {
+        puz_encode(&format!("{}{}{}", puzzle.author, PUZ_EDITOR_SEPARATOR, puzzle.editor))
+    };
+    let copyright = puz_encode(&puzzle.copyright);
+    let notes = puz_encode(&puzzle.notes);
+    let clues: Vec<Vec<u8>> = puz_ordered_entries(puzzle).iter().map(|e| puz_encode(&e.clue)).collect();
+
+    let mut scrambled_cksum: u16 = 0;
+    if let Some(key) = scramble_key {
+        if !(1000..=9999).contains(&key) {
+            return Err(invalid("Scramble key must be a four digit number."));
+        }
+        let letters = puz_column_major_letters(&solution, width, height);
+        if letters.is_empty() || !letters.iter().all(|c| c.is_ascii_uppercase()) {
+            return Err(invalid("Only grids filled entirely with the letters A-Z can be scrambled."));
+        }
+        scrambled_cksum = puz_cksum_region(&letters, 0);
+        puz_restore_column_major(&mut solution, width, height, &puz_scramble_letters(&letters, key));
+    }
+
+    let mut cib: Vec<u8> = Vec::with_capacity(8);
+    cib.push(width as u8);
+    cib.push(height as u8);
+    cib.extend_from_slice(&(clues.len() as u16).to_le_bytes());
+    cib.extend_from_slice(&1u16.to_le_bytes());
+    cib.extend_from_slice(&(if scramble_key.is_some() { PUZ_SCRAMBLED_TAG } else { 0 }).to_le_bytes());
+
+    let cib_cksum = puz_cksum_region(&cib, 0);
+    let sol_cksum = puz_cksum_region(&solution, 0);
+    let state_cksum = puz_cksum_region(&state, 0);
+    let text_cksum = puz_text_cksum(&title, &author, &copyright, &clues, &notes, true, 0);
+    let mut global_cksum = puz_cksum_region(&solution, cib_cksum);
+    global_cksum = puz_cksum_region(&state, global_cksum);
+    global_cksum = puz_text_cksum(&title, &author, &copyright, &clues, &notes, true, global_cksum);
+
+    let parts = [cib_cksum, sol_cksum, state_cksum, text_cksum];
+    let mut masked_low = [0u8; 4];
+    let mut masked_high = [0u8; 4];
+    for i in 0..4 {
+        masked_low[i] = b"ICHE"[i] ^ (parts[i] & 0xFF) as u8;
+        masked_high[i] = b"ATED"[i] ^ (parts[i] >> 8) as u8;
+    }
+
+    let mut out: Vec<u8> = Vec::new();
+    out.extend_from_slice(&global_cksum.to_le_bytes());
+    out.extend_from_slice(PUZ_MAGIC);
+    out.extend_from_slice(&cib_cksum.to_le_bytes());
+    out.extend_from_slice(&masked_low);
+    out.extend_from_slice(&masked_high);
+    out.extend_from_slice(b"1.3\0");
+    out.extend_from_slice(&[0, 0]);
+    out.extend_from_slice(&scrambled_cksum.to_le_bytes());
+    out.extend_from_slice(&[0; 12]);
+    out.extend_from_slice(&cib);
+    out.extend_from_slice(&solution);
+    out.extend_from_slice(&state);
+    for s in [&title, &author, &copyright].iter() {
+        out.extend_from_slice(s);
+        out.push(0);
+    }
+    for c in clues.iter() {
+        out.extend_from_slice(c);
+        out.push(0);
+    }
+    out.extend_from_slice(&notes);
+    out.push(0);
+
+    if !rebus_table.is_empty() {
+        puz_push_extension(&mut out, b"GRBS", &grbs);
+        let mut rtbl = String::new();
+        for (k, r) in rebus_table.iter().enumerate() {
+            rtbl.push_str(&format!("{:>2}:{};", k, r));
+        }
+        puz_push_extension(&mut out, b"RTBL", &puz_encode(&rtbl));
+    }
+    if gext.iter().any(|g| *g != 0) {
+        puz_push_extension(&mut out, b"GEXT", &gext);
+    }
+
+    std::fs::write(path_str, out)
+}
+
+pub fn get_puzzle_from_puz(path_str: String) -> std::result::Result<puzzle_backend::Puzzle, String> {
+    let truncated_err = Err("Unexpected end of .puz file.".to_string());
+
+    let file_contents = match std::fs::read(path_str) {
+        Ok(b) => b,
+        Err(e) => { return Err(e.to_string()); },
+    };
+
+    // Some files carry junk before the header, so locate it by its magic string.
+    let start = match file_contents.windows(PUZ_MAGIC.len()).position(|w| w == PUZ_MAGIC) {
+        Some(p) if p >= 2 => p - 2,
+        _ => { return Err("Not an Across Lite .puz file.".to_string()); },
+    };
+    let data = &file_contents[start..];
+    if data.len() < PUZ_HEADER_LEN {
+        return truncated_err;
+    }
+    let read_u16 = |offset: usize| u16::from_le_bytes([data[offset], data[offset + 1]]);
+
+    let global_cksum = read_u16(0x00);
+    let cib_cksum = read_u16(0x0E);
+    let scrambled_cksum = read_u16(0x1E);
+    let width = data[0x2C] as usize;
+    let height = data[0x2D] as usize;
+    let num_clues = read_u16(0x2E) as usize;
+    let scrambled = read_u16(0x32) & PUZ_SCRAMBLED_TAG != 0;
+    // Version strings look like "1.3\0" or "1.2c".
+    let version_str = puz_decode(&data[0x18..0x1C]);
+    let mut version_parts = version_str.split('.').map(|p| p.chars().take_while(|c| c.is_ascii_digit()).collect::<String>().parse::<u32>().unwrap_or(0));
+    let version = (version_parts.next().unwrap_or(0), version_parts.next().unwrap_or(0));
+    if width == 0 || height == 0 {
+        return Err("The .puz grid has no squares.".to_string());
+    }
+    if puz_cksum_region(&data[0x2C..PUZ_HEADER_LEN], 0) != cib_cksum {
+        return Err("The .puz header checksum does not match.".to_string());
+    }
+
+    let n = width * height;
+    if data.len() < PUZ_HEADER_LEN + 2 * n {
+        return truncated_err;
+    }
+    let mut solution = data[PUZ_HEADER_LEN..PUZ_HEADER_LEN + n].to_vec();
+    let state = &data[PUZ_HEADER_LEN + n..PUZ_HEADER_LEN + 2 * n];
+
+    // Title, author, copyright, the clues and the notes are null-terminated.
+    let mut pos = PUZ_HEADER_LEN + 2 * n;
+    let mut strings: Vec<Vec<u8>> = Vec::with_capacity(num_clues + 4);
+    for _ in 0..num_clues + 4 {
+        match data[pos..].iter().position(|b| *b == 0) {
+            Some(len) => {
+                strings.push(data[pos..pos + len].to_vec());
+                pos += len + 1;
+            },
+            None => { return truncated_err; },
+        }
+    }
+    let (title, author, copyright) = (&strings[0], &strings[1], &strings[2]);
+    let clues = &strings[3..3 + num_clues];
+    let notes = &strings[3 + num_clues];
+
+    let mut global_check = puz_cksum_region(&solution, cib_cksum);
+    global_check = puz_cksum_region(state, global_check);
+    global_check = puz_text_cksum(title, author, copyright, clues, notes, version >= (1, 3), global_check);
+    if global_check != global_cksum {
+        return Err("The .puz file checksum does not match.".to_string());
+    }
+
+    let mut grbs: Option<&[u8]> = None;
+    let mut rtbl: Option<String> = None;
+    let mut gext: Option<&[u8]> = None;
+    while pos + 8 <= data.len() {
+        let section = &data[pos..pos + 4];
+        let len = read_u16(pos + 4) as usize;
+        let body_start = pos + 8;
+        if body_start + len > data.len() {
+            return truncated_err;
+        }
+        let body = &data[body_start..body_start + len];
+        match section {
+            b"GRBS" if len == n => { grbs = Some(body); },
+            b"RTBL" => { rtbl = Some(puz_decode(body)); },
+            b"GEXT" if len == n => { gext = Some(body); },
+            _ => {},
+        }
+        pos = body_start + len + 1;
+    }
+
+    if scrambled {
+        let letters = puz_column_major_letters(&solution, width, height);
+        if !letters.iter().all(|c| c.is_ascii_uppercase()) {
+            return Err("The scrambled .puz solution contains characters other than A-Z.".to_string());
+        }
+        let key = (1000..=9999u16).find(|k| puz_cksum_region(&puz_unscramble_letters(&letters, *k), 0) == scrambled_cksum);
+        match key {
+            Some(k) => puz_restore_column_major(&mut solution, width, height, &puz_unscramble_letters(&letters, k)),
+            None => { return Err("Could not unscramble the .puz solution.".to_string()); },
+        }
+    }
+
+    // Rebus table entries look like " 1:HEART; 2:DIAMOND;"
+    let mut rebus_table: Vec<(u8, String)> = Vec::new();
+    if let Some(t) = &rtbl {
+        for item in t.split(';') {
+            if let Some(colon) = item.find(':') {
+                if let Ok(k) = item[..colon].trim().parse::<u8>() {
+                    rebus_table.push((k, item[colon + 1..].to_string()));
+                }
+            }
+        }
+    }
+
+    let blockers: Vec<bool> = solution.iter().map(|c| *c == b'.').collect();
+    let variant = guess_puzzle_type(width, height, &blockers)?;
+    let mut puz = puzzle_backend::Puzzle::new(variant);
+    puz.title = puz_decode(title);
+    let author = puz_decode(author);
+    match author.find(PUZ_EDITOR_SEPARATOR) {
+        Some(i) => {
+            puz.editor = author[i + PUZ_EDITOR_SEPARATOR.len()..].to_string();
+            puz.author = author[..i].to_string();
+        },
+        None => { puz.author = author; },
+    }
+    puz.copyright = puz_decode(copyright);
+    puz.notes = puz_decode(notes);
+
+    for index in 0..n {
+        if solution[index] == b'.' {
+            puz.squares[index].content = puzzle_backend::SquareContents::Blocker;
+            continue;
+        }
+        let rebus = grbs.and_then(|g| g[index].checked_sub(1))
+            .and_then(|k| rebus_table.iter().find(|(key, _)| *key == k))
+            .map(|(_, r)| r.clone());
+        let contents = match rebus {
+            Some(r) => r,
+            None if solution[index] == b'-' => String::new(),
+            None => (solution[index] as char).to_string(),
+        };
+        let modifier = match gext.map(|g| g[index]) {
+            Some(g) if g & PUZ_GEXT_CIRCLE != 0 => Some(puzzle_backend::SquareModifier::Circle),
+            Some(g) if g & PUZ_GEXT_SHADING != 0 => Some(puzzle_backend::SquareModifier::Shading),
+            _ => None,
+        };
+        puz.squares[index].content = puzzle_backend::SquareContents::TextContent(contents, modifier);
+    }
+    puz.calculate_clues();
+
+    let order: Vec<(u32, puzzle_backend::EntryVariant)> = puz_ordered_entries(&puz).iter().map(|e| (e.label, e.variant)).collect();
+    if order.len() != clues.len() {
+        return Err(format!("The .puz grid has {} entries but the file has {} clues.", order.len(), clues.len()));
+    }
+    for ((label, variant), clue) in order.into_iter().zip(clues.iter()) {
+        puz.set_clue_text(label, variant, puz_decode(clue));
+    }
+
+    puz.clear_history();
+
+    Ok(puz)
+}
+
+// ipuz support. See http://ipuz.org for the specification.
+const IPUZ_VERSION: &str = "http://ipuz.org/v2";
+const IPUZ_KIND: &str = "http://ipuz.org/crossword#1";
+// ipuz has no tags field, and extensions go under namespaced keys.
+const IPUZ_TAGS_KEY: &str = "com.crosser:tags";
+
+fn ipuz_style(m: &Option<puzzle_backend::SquareModifier>) -> Option<serde_json::Value> {
+    match m {
+        None => None,
+        Some(puzzle_backend::SquareModifier::Circle) => Some(json!({ "shapebg": "circle" })),
+        Some(puzzle_backend::SquareModifier::Shading) => Some(json!({ "highlight": true })),
+    }
+}
+
+fn ipuz_modifier(style: &serde_json::Value, named_styles: &serde_json::Value) -> Option<puzzle_backend::SquareModifier> {
+    // Styles may be given inline or by name from the top-level "styles" table.
+    let style = match style {
+        serde_json::Value::String(name) => &named_styles[name.as_str()],
+        _ => style,
+    };
+    if style["shapebg"].as_str() == Some("circle") {
+        Some(puzzle_backend::SquareModifier::Circle)
+    } else if style["highlight"].as_bool() == Some(true) || style["color"].is_string() {
+        Some(puzzle_backend::SquareModifier::Shading)
+    } else {
+        None
+    }
+}
+
+pub fn write_puzzle_to_ipuz(puzzle: &puzzle_backend::Puzzle, path_str: String) -> std::result::Result<(),std::io::Error> {
+
+    let mut grid_rows: Vec<serde_json::Value> = Vec::with_capacity(puzzle.height());
+    let mut solution_rows: Vec<serde_json::Value> = Vec::with_capacity(puzzle.height());
+    for (y, row) in puzzle.squares.chunks(puzzle.width()).enumerate() {
+        let mut grid_row: Vec<serde_json::Value> = Vec::with_capacity(row.len());
+        let mut solution_row: Vec<serde_json::Value> = Vec::with_capacity(row.len());
+        for (x, sq) in row.iter().enumerate() {
+            match &sq.content {
+                puzzle_backend::SquareContents::Blocker => {
+                    grid_row.push(json!("#"));
+                    solution_row.push(json!("#"));
+                },
+                puzzle_backend::SquareContents::TextContent(_s,m) => {
+                    let label = json!(sq.label.unwrap_or(0));
+                    grid_row.push(match ipuz_style(m) {
+                        Some(style) => json!({ "cell": label, "style": style }),
+                        None => label,
+                    });
+                    let answer = square_answer(puzzle, y * puzzle.width() + x);
+                    solution_row.push(if answer.is_empty() { serde_json::Value::Null } else { json!(answer) });
+                },
+            }
+        }
+        grid_rows.push(serde_json::Value::Array(grid_row));
+        solution_rows.push(serde_json::Value::Array(solution_row));
+    }
+
+    let across_clues: Vec<serde_json::Value> = puzzle.across_entries.iter().map(|e| json!([e.label, e.clue])).collect();
+    let down_clues: Vec<serde_json::Value> = puzzle.down_entries.iter().map(|e| json!([e.label, e.clue])).collect();
+
+    let mut json_rep = json!({
+        "version": IPUZ_VERSION,
+        "kind": [IPUZ_KIND],
+        "title": puzzle.title,
+        "author": puzzle.author,
+        "dimensions": { "width": puzzle.width(), "height": puzzle.height() },
+        "block": "#",
+        "empty": 0,
+        "puzzle": grid_rows,
+        "solution": solution_rows,
+        "clues": { "Across": across_clues, "Down": down_clues },
+    });
+    // Optional fields are left out rather than written empty.
+    for (key, value) in [
+        ("editor", &puzzle.editor),
+        ("copyright", &puzzle.copyright),
+        ("date", &puzzle.date),
+        ("difficulty", &puzzle.difficulty),
+        ("notes", &puzzle.notes),
+    ] {
+        if !value.is_empty() {
+            json_rep[key] = json!(value);
+        }
+    }
+    if !puzzle.tags.is_empty() {
+        json_rep[IPUZ_TAGS_KEY] = json!(puzzle.tags);
+    }
+
+    std::fs::write(path_str,serde_json::to_string_pretty(&json_rep).unwrap())
+}
+
+pub fn get_puzzle_from_ipuz(path_str: String) -> std::result::Result<puzzle_backend::Puzzle, String> {
+    let generic_json_err = Err("Error parsing ipuz JSON.".to_string());
+
+    let file_contents = match std::fs::read_to_string(path_str) {
+        Ok(s) => s,
+        Err(e) => { return Err(e.to_string()); },
+    };
+    // Older files wrap the JSON in a JSONP-style "ipuz(...)" call.
+    let mut trimmed = file_contents.trim();
+    if trimmed.starts_with("ipuz(") && trimmed.ends_with(')') {
+        trimmed = &trimmed[5..trimmed.len() - 1];
+    }
+    let value_contents: serde_json::Value = match serde_json::from_str(trimmed) {
+        Ok(v) => v,
+        Err(e) => { return Err(e.to_string()); },
+    };
+
+    let is_crossword = match &value_contents["kind"] {
+        serde_json::Value::Array(kinds) => kinds.iter().any(|k| k.as_str().is_some_and(|s| s.starts_with("http://ipuz.org/crossword"))),
+        _ => false,
+    };
+    if !is_crossword {
+        return Err("The ipuz file is not a crossword.".to_string());
+    }
+
+    let (width, height) = match (value_contents["dimensions"]["width"].as_u64(), value_contents["dimensions"]["height"].as_u64()) {
+        (Some(w), Some(h)) => (w as usize, h as usize),
+        _ => { return generic_json_err; },
+    };
+    // Checked before reading any cells, since the grid is only as big as the file says.
+    if !puzzle_backend::is_valid_custom_dim(width, height) {
+        return Err(format!("A {}x{} grid is not supported.", width, height));
+    }
+    let block = value_contents["block"].as_str().unwrap_or("#");
+    let named_styles = &value_contents["styles"];
+
+    let cell_at = |key: &str, x: usize, y: usize| -> serde_json::Value {
+        value_contents[key][y][x].clone()
+    };
+    let is_block = |cell: &serde_json::Value| -> bool {
+        match cell {
+            // Omitted cells have no square at all, which is the closest thing we have.
+            serde_json::Value::Null => true,
+            serde_json::Value::String(s) => s == block,
+            serde_json::Value::Object(o) => o.get("cell").is_some_and(|c| c.as_str() == Some(block)),
+            _ => false,
+        }
+    };
+
+    if !value_contents["puzzle"].is_array() {
+        return generic_json_err;
+    }
+    let mut blockers: Vec<bool> = Vec::with_capacity(width * height);
+    for y in 0..height {
+        for x in 0..width {
+            blockers.push(is_block(&cell_at("puzzle", x, y)));
+        }
+    }
+
+    let variant = guess_puzzle_type(width, height, &blockers)?;
+    let mut puz = puzzle_backend::Puzzle::new(variant);
+    puz.title = value_contents["title"].as_str().unwrap_or("").to_string();
+    puz.author = value_contents["author"].as_str().unwrap_or("").to_string();
+    puz.editor = value_contents["editor"].as_str().unwrap_or("").to_string();
+    puz.copyright = value_contents["copyright"].as_str().unwrap_or("").to_string();
+    puz.date = value_contents["date"].as_str().unwrap_or("").to_string();
+    puz.difficulty = value_contents["difficulty"].as_str().unwrap_or("").to_string();
+    puz.notes = value_contents["notes"].as_str().unwrap_or("").to_string();
+    if let serde_json::Value::Array(v) = &value_contents[IPUZ_TAGS_KEY] {
+        puz.tags = v.iter().filter_map(|t| t.as_str()).map(|t| t.to_string()).collect();
+    }
+
+    for y in 0..height {
+        for x in 0..width {
+            let index = y * width + x;
+            if blockers[index] {
+                puz.squares[index].content = puzzle_backend::SquareContents::Blocker;
+                continue;
+            }
+            let modifier = ipuz_modifier(&cell_at("puzzle", x, y)["style"], named_styles);
+            let contents = match cell_at("solution", x, y) {
+                serde_json::Value::String(s) if s != block => s,
+                serde_json::Value::Object(o) => o.get("value").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                _ => String::new(),
+            };
+            puz.squares[index].content = puzzle_backend::SquareContents::TextContent(contents, modifier);
+        }
+    }
+    puz.calculate_clues();
+
+    let clues = match &value_contents["clues"] {
+        serde_json::Value::Object(m) => m.clone(),
+        serde_json::Value::Null => serde_json::Map::new(),
+        _ => { return generic_json_err; },
+    };
+    for (direction, list) in clues.iter() {
+        // Directions may carry a display name, as in "Across:Across".
+        let variant = match direction.split(':').next().unwrap_or("") {
+            "Across" => puzzle_backend::EntryVariant::Across,
+            "Down" => puzzle_backend::EntryVariant::Down,
+            _ => { continue; },
+        };
+        let list = match list {
+            serde_json::Value::Array(v) => v,
+            _ => { return generic_json_err; },
+        };
+        for clue in list.iter() {
+            let (label, text) = match clue {
+                serde_json::Value::Array(pair) if pair.len() >= 2 => (pair[0].clone(), pair[1].as_str().unwrap_or("").to_string()),
+                serde_json::Value::Object(o) => (o.get("number").cloned().unwrap_or_default(), o.get("clue").and_then(|c| c.as_str()).unwrap_or("").to_string()),
+                _ => { return generic_json_err; },
+            };
+            let label = match label {
+                serde_json::Value::Number(n) => n.as_u64(),
+                serde_json::Value::String(s) => s.parse::<u64>().ok(),
+                _ => None,
+            };
+            let label = match label {
+                Some(l) => l as u32,
+                None => { return generic_json_err; },
+            };
+            if !puz.set_clue_text(label, variant, text) {
+                return Err(format!("The ipuz file has a clue for {}{} which is not in the grid.", label, match variant {
+                    puzzle_backend::EntryVariant::Across => "A",
+                    puzzle_backend::EntryVariant::Down => "D",
+                }));
+            }
+        }
+    }
+
+    puz.clear_history();
+
+    Ok(puz)
+}
\ No newline at end of file
@@ -0,0 +1,165 @@
+use crate::puzzle_backend::{SolveMark, Square, SquareContents};
+
+// Oldest transactions are dropped once the undo stack grows past this.
+const MAX_HISTORY_LEN: usize = 500;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum EditKind {
+    Structure,
+    Modifier,
+    Typing,
+    Clue,
+    // A clue given to an entry from the clue bin.
+    ClueFromBin,
+    Fill,
+}
+
+// The grid as it was before an edit, to be diffed against it afterwards.
+pub struct GridState {
+    pub squares: Vec<Square>,
+    pub marks: Vec<Option<SolveMark>>,
+}
+
+// The parts of a square that edits can change. Everything else on a square is
+// derived from these by calculate_clues.
+#[derive(Clone, PartialEq)]
+struct SquareState {
+    content: SquareContents,
+    across_clue_text: Option<String>,
+    down_clue_text: Option<String>,
+    mark: Option<SolveMark>,
+}
+
+impl SquareState {
+    fn of(sq: &Square, mark: Option<SolveMark>) -> Self {
+        SquareState {
+            content: sq.content.clone(),
+            across_clue_text: sq.across_clue_text.clone(),
+            down_clue_text: sq.down_clue_text.clone(),
+            mark,
+        }
+    }
+
+    fn apply_to(&self, sq: &mut Square, mark: &mut Option<SolveMark>) {
+        sq.content = self.content.clone();
+        sq.across_clue_text = self.across_clue_text.clone();
+        sq.down_clue_text = self.down_clue_text.clone();
+        *mark = self.mark;
+    }
+}
+
+struct SquareChange {
+    index: usize,
+    before: SquareState,
+    after: SquareState,
+}
+
+struct Transaction {
+    kind: EditKind,
+    changes: Vec<SquareChange>,
+}
+
+pub struct History {
+    undo_stack: Vec<Transaction>,
+    redo_stack: Vec<Transaction>,
+    // Whether typing edits may still be merged into the top transaction.
+    group_open: bool,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl History {
+    pub fn new() -> Self {
+        History {
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            group_open: false,
+        }
+    }
+
+    // Diffs the squares and their marks before and after an edit and pushes the
+    // changes as one undo step. Consecutive typing edits are merged until the
+    // group is ended.
+    pub fn record(&mut self, kind: EditKind, before: &GridState, squares: &[Square], marks: &[Option<SolveMark>]) {
+        let changes: Vec<SquareChange> = before.squares.iter().zip(squares.iter()).enumerate()
+            .map(|(index, (b, a))| SquareChange {
+                index,
+                before: SquareState::of(b, before.marks[index]),
+                after: SquareState::of(a, marks[index]),
+            })
+            .filter(|c| c.before != c.after)
+            .collect();
+
+        if kind != EditKind::Typing {
+            self.group_open = false;
+        }
+        if changes.is_empty() {
+            return;
+        }
+        self.redo_stack.clear();
+
+        if kind == EditKind::Typing && self.group_open {
+            if let Some(top) = self.undo_stack.last_mut() {
+                for c in changes {
+                    match top.changes.iter_mut().find(|x| x.index == c.index) {
+                        Some(existing) => existing.after = c.after,
+                        None => top.changes.push(c),
+                    }
+                }
+                return;
+            }
+        }
+
+        self.undo_stack.push(Transaction { kind, changes });
+        if self.undo_stack.len() > MAX_HISTORY_LEN {
+            self.undo_stack.remove(0);
+        }
+        self.group_open = kind == EditKind::Typing;
+    }
+
+    pub fn end_group(&mut self) {
+        self.group_open = false;
+    }
+
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.group_open = false;
+    }
+
+    // Restores the squares and marks touched by the last transaction. Returns the kind of
+    // edit undone, or None if there was nothing to undo.
+    pub fn undo(&mut self, squares: &mut [Square], marks: &mut [Option<SolveMark>]) -> Option<EditKind> {
+        self.group_open = false;
+        match self.undo_stack.pop() {
+            Some(t) => {
+                for c in t.changes.iter() {
+                    c.before.apply_to(&mut squares[c.index], &mut marks[c.index]);
+                }
+                let kind = t.kind;
+                self.redo_stack.push(t);
+                Some(kind)
+            },
+            None => None,
+        }
+    }
+
+    pub fn redo(&mut self, squares: &mut [Square], marks: &mut [Option<SolveMark>]) -> Option<EditKind> {
+        self.group_open = false;
+        match self.redo_stack.pop() {
+            Some(t) => {
+                for c in t.changes.iter() {
+                    c.after.apply_to(&mut squares[c.index], &mut marks[c.index]);
+                }
+                let kind = t.kind;
+                self.undo_stack.push(t);
+                Some(kind)
+            },
+            None => None,
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use crate::puzzle_backend;
+
+use std::collections::HashMap;
+
+// Shortest entry allowed by publication rules.
+pub const MIN_ENTRY_LEN: usize = 3;
+
+#[derive(Clone, Debug)]
+pub struct Issue {
+    pub message: String,
+    // Indices of the squares at fault. Empty for whole-grid issues like word counts.
+    pub squares: Vec<usize>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ValidationReport {
+    pub issues: Vec<Issue>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    // One flag per square, set if any issue involves that square.
+    pub fn flagged_squares(&self, num_squares: usize) -> Vec<bool> {
+        let mut flagged = vec![false; num_squares];
+        for issue in self.issues.iter() {
+            for i in issue.squares.iter() {
+                flagged[*i] = true;
+            }
+        }
+        flagged
+    }
+
+    pub fn summary(&self) -> String {
+        if self.is_valid() {
+            return "Grid meets publication rules.".to_string();
+        }
+        let messages: Vec<&str> = self.issues.iter().map(|i| i.message.as_str()).collect();
+        format!("{} problem(s): {}", self.issues.len(), messages.join("; "))
+    }
+}
+
+// The most words and blockers a grid of each type may have, if it has limits at all.
+pub fn limits(variant: &puzzle_backend::PuzzleType) -> Option<(usize, usize)> {
+    match variant {
+        puzzle_backend::PuzzleType::Weekday | puzzle_backend::PuzzleType::WeekdayAsymmetric => Some((78, 38)),
+        puzzle_backend::PuzzleType::Sunday => Some((140, 78)),
+        puzzle_backend::PuzzleType::Mini | puzzle_backend::PuzzleType::Custom(_,_) => None,
+    }
+}
+
+pub fn validate(puzzle: &puzzle_backend::Puzzle) -> ValidationReport {
+    let mut issues: Vec<Issue> = Vec::new();
+    let entries: Vec<&puzzle_backend::PuzzleEntry> = puzzle.across_entries.iter().chain(puzzle.down_entries.iter()).collect();
+
+    // Runs of a single square aren't words, they leave the square unchecked.
+    for e in entries.iter() {
+        if e.member_indices.len() > 1 && e.member_indices.len() < MIN_ENTRY_LEN {
+            issues.push(Issue {
+                message: format!("{} is shorter than {} letters", entry_name(e), MIN_ENTRY_LEN),
+                squares: e.member_indices.clone(),
+            });
+        }
+    }
+
+    let mut unchecked: Vec<usize> = entries.iter()
+        .filter(|e| e.member_indices.len() == 1)
+        .map(|e| e.member_indices[0])
+        .collect();
+    unchecked.sort_unstable();
+    unchecked.dedup();
+    if !unchecked.is_empty() {
+        issues.push(Issue {
+            message: format!("{} unchecked square(s)", unchecked.len()),
+            squares: unchecked,
+        });
+    }
+
+    let islands = disconnected_squares(puzzle);
+    if !islands.is_empty() {
+        issues.push(Issue {
+            message: format!("{} square(s) cut off from the rest of the grid", islands.len()),
+            squares: islands,
+        });
+    }
+
+    let asymmetric = puzzle.symmetry_violations();
+    if !asymmetric.is_empty() {
+        issues.push(Issue {
+            message: format!("{} square(s) break the grid's symmetry", asymmetric.len()),
+            squares: asymmetric,
+        });
+    }
+
+    if let Some((max_words, max_blockers)) = limits(&puzzle.variant) {
+        let words = puzzle.word_count();
+        if words > max_words {
+            issues.push(Issue {
+                message: format!("{} words, at most {} allowed", words, max_words),
+                squares: Vec::new(),
+            });
+        }
+        let blockers = puzzle.blocker_count();
+        if blockers > max_blockers {
+            issues.push(Issue {
+                message: format!("{} blockers, at most {} allowed", blockers, max_blockers),
+                squares: Vec::new(),
+            });
+        }
+    }
+
+    // Only complete answers can be duplicates.
+    let mut answers: HashMap<String, Vec<&puzzle_backend::PuzzleEntry>> = HashMap::new();
+    for e in entries.iter().filter(|e| e.member_indices.len() > 1) {
+        if let Some(a) = answer(puzzle, e) {
+            answers.entry(a).or_default().push(e);
+        }
+    }
+    let mut duplicates: Vec<(String, Vec<&puzzle_backend::PuzzleEntry>)> = answers.into_iter().filter(|(_a,v)| v.len() > 1).collect();
+    duplicates.sort_by(|a, b| a.0.cmp(&b.0));
+    for (a, v) in duplicates {
+        let names: Vec<String> = v.iter().map(|e| entry_name(e)).collect();
+        issues.push(Issue {
+            message: format!("{} is used by {}", a, names.join(", ")),
+            squares: v.iter().flat_map(|e| e.member_indices.iter().cloned()).collect(),
+        });
+    }
+
+    ValidationReport { issues }
+}
+
+fn entry_name(e: &puzzle_backend::PuzzleEntry) -> String {
+    let a_or_d = match e.variant {
+        puzzle_backend::EntryVariant::Across => 'A',
+        puzzle_backend::EntryVariant::Down => 'D',
+    };
+    format!("{}{}", e.label, a_or_d)
+}
+
+fn answer(puzzle: &puzzle_backend::Puzzle, e: &puzzle_backend::PuzzleEntry) -> Option<String> {
+    let mut a = String::new();
+    for i in e.member_indices.iter() {
+        match &puzzle.squares[*i].content {
+            puzzle_backend::SquareContents::TextContent(s,_m) if !s.is_empty() => a.push_str(&s.to_uppercase()),
+            _ => { return None; },
+        }
+    }
+    Some(a)
+}
+
+// Every white square outside the largest connected group of white squares.
+fn disconnected_squares(puzzle: &puzzle_backend::Puzzle) -> Vec<usize> {
+    let (w, h) = (puzzle.width(), puzzle.height());
+    let is_white = |i: usize| puzzle.squares[i].content != puzzle_backend::SquareContents::Blocker;
+    let mut group: Vec<Option<usize>> = vec![None; w * h];
+    let mut group_sizes: Vec<usize> = Vec::new();
+
+    for start in 0..w * h {
+        if !is_white(start) || group[start].is_some() {
+            continue;
+        }
+        let g = group_sizes.len();
+        group_sizes.push(0);
+        group[start] = Some(g);
+        let mut stack = vec![start];
+        while let Some(i) = stack.pop() {
+            group_sizes[g] += 1;
+            let (x, y) = (i % w, i / w);
+            let mut neighbours = Vec::with_capacity(4);
+            if x > 0 { neighbours.push(i - 1); }
+            if x + 1 < w { neighbours.push(i + 1); }
+            if y > 0 { neighbours.push(i - w); }
+            if y + 1 < h { neighbours.push(i + w); }
+            for n in neighbours {
+                if is_white(n) && group[n].is_none() {
+                    group[n] = Some(g);
+                    stack.push(n);
+                }
+            }
+        }
+    }
+
+    let largest = (0..group_sizes.len()).max_by_key(|g| group_sizes[*g]);
+    (0..w * h).filter(|i| group[*i].is_some() && group[*i] != largest).collect()
+}
\ No newline at end of file
@@ -0,0 +1,155 @@
+use crate::puzzle_backend;
+
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+// Solution checks written to the .cro "hash_string" field.
+//
+// Version 2 (current): "v2:<salt>:<digest>", where salt is 16 lowercase hex
+// characters chosen when the file is saved, and digest is the lowercase hex
+// SHA-256 of
+//     "crosser-solution-v2\n" + salt + "\n" + square_1 + "\n" + ... + square_n
+// with one line per square in row-major order, each the upper-cased contents
+// of the square or "#" for blockers. Each entry also gets a checksum, the first
+// 16 hex characters of the SHA-256 of
+//     "crosser-entry-v2\n" + salt + "\n" + label + "A" or "D" + "\n" + answer
+// stored in the .cro "entry_checksums" field.
+//
+// Version 1 (legacy): the decimal output of Rust's DefaultHasher over the
+// concatenated squares. DefaultHasher isn't stable between Rust releases, so
+// these are still read but never written.
+pub const SCHEME_VERSION: &str = "v2";
+const ENTRY_CHECKSUM_LEN: usize = 16;
+
+#[derive(Clone, Debug)]
+pub enum SolutionCheck {
+    Legacy(u64),
+    V2 {
+        salt: String,
+        digest: String,
+        entries: HashMap<(u32, puzzle_backend::EntryVariant), String>,
+    },
+}
+
+impl SolutionCheck {
+    // Builds a version 2 check for a solution given as one string per square.
+    pub fn new(solution: &[String], puzzle: &puzzle_backend::Puzzle) -> Self {
+        let salt = new_salt();
+        let digest = grid_digest(&salt, solution);
+        let mut entries = HashMap::new();
+        for e in puzzle.across_entries.iter().chain(puzzle.down_entries.iter()) {
+            let answer: String = e.member_indices.iter().map(|i| solution[*i].to_uppercase()).collect();
+            entries.insert((e.label, e.variant), entry_checksum(&salt, e.label, e.variant, &answer));
+        }
+        SolutionCheck::V2 { salt, digest, entries }
+    }
+
+    pub fn parse(hash_string: &str) -> std::result::Result<Self, String> {
+        let parts: Vec<&str> = hash_string.split(':').collect();
+        match parts.as_slice() {
+            [version, salt, digest] if *version == SCHEME_VERSION => {
+                Ok(SolutionCheck::V2 { salt: salt.to_string(), digest: digest.to_string(), entries: HashMap::new() })
+            },
+            [legacy] => {
+                match legacy.parse::<u64>() {
+                    Ok(h) => Ok(SolutionCheck::Legacy(h)),
+                    Err(_) => Err("Unrecognized solution check.".to_string()),
+                }
+            },
+            _ => Err(format!("Unsupported solution check version: {}", parts[0])),
+        }
+    }
+
+    pub fn to_hash_string(&self) -> String {
+        match self {
+            SolutionCheck::Legacy(h) => h.to_string(),
+            SolutionCheck::V2 { salt, digest, .. } => format!("{}:{}:{}", SCHEME_VERSION, salt, digest),
+        }
+    }
+
+    pub fn add_entry_checksum(&mut self, label: u32, variant: puzzle_backend::EntryVariant, checksum: String) {
+        if let SolutionCheck::V2 { entries, .. } = self {
+            entries.insert((label, variant), checksum);
+        }
+    }
+
+    pub fn entry_checksums(&self) -> Vec<(u32, puzzle_backend::EntryVariant, &str)> {
+        let mut v: Vec<(u32, puzzle_backend::EntryVariant, &str)> = match self {
+            SolutionCheck::Legacy(_) => Vec::new(),
+            SolutionCheck::V2 { entries, .. } => entries.iter().map(|((l,v),c)| (*l, *v, c.as_str())).collect(),
+        };
+        v.sort_by_key(|(l, v, _c)| (*v == puzzle_backend::EntryVariant::Down, *l));
+        v
+    }
+
+    pub fn has_entry_checksums(&self) -> bool {
+        !self.entry_checksums().is_empty()
+    }
+
+    // Whether a grid, one string per square with "#" for blockers, is the solution.
+    pub fn matches<S: AsRef<str>>(&self, contents: &[S]) -> bool {
+        match self {
+            SolutionCheck::Legacy(h) => legacy_hash(contents) == *h,
+            SolutionCheck::V2 { salt, digest, .. } => grid_digest(salt, contents) == *digest,
+        }
+    }
+
+    // Some(true or false) if the entry has a checksum to compare against.
+    pub fn entry_matches(&self, label: u32, variant: puzzle_backend::EntryVariant, answer: &str) -> Option<bool> {
+        match self {
+            SolutionCheck::Legacy(_) => None,
+            SolutionCheck::V2 { salt, entries, .. } => {
+                entries.get(&(label, variant)).map(|c| *c == entry_checksum(salt, label, variant, &answer.to_uppercase()))
+            },
+        }
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn new_salt() -> String {
+    let nanos = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+    let seed = format!("{}:{}", nanos, std::process::id());
+    hex(&Sha256::digest(seed.as_bytes())[..8])
+}
+
+fn grid_digest<S: AsRef<str>>(salt: &str, contents: &[S]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(b"crosser-solution-v2\n");
+    hasher.update(salt.as_bytes());
+    for c in contents.iter() {
+        hasher.update(b"\n");
+        hasher.update(c.as_ref().to_uppercase().as_bytes());
+    }
+    hex(&hasher.finalize())
+}
+
+fn entry_checksum(salt: &str, label: u32, variant: puzzle_backend::EntryVariant, answer: &str) -> String {
+    let a_or_d = match variant {
+        puzzle_backend::EntryVariant::Across => "A",
+        puzzle_backend::EntryVariant::Down => "D",
+    };
+    let mut hasher = Sha256::new();
+    hasher.update(b"crosser-entry-v2\n");
+    hasher.update(salt.as_bytes());
+    hasher.update(format!("\n{}{}\n", label, a_or_d).as_bytes());
+    hasher.update(answer.as_bytes());
+    let mut h = hex(&hasher.finalize());
+    h.truncate(ENTRY_CHECKSUM_LEN);
+    h
+}
+
+fn legacy_hash<S: AsRef<str>>(contents: &[S]) -> u64 {
+    use std::hash::{Hash, Hasher};
+
+    let mut s = String::new();
+    for c in contents.iter() {
+        s.push_str(c.as_ref());
+    }
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    s.hash(&mut hasher);
+    hasher.finish()
+}
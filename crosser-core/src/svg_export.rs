@@ -0,0 +1,71 @@
+use crate::grid_layout;
+use crate::puzzle_backend;
+
+// Nominal size of a square. SVGs scale freely, this only sets the default size.
+const SVG_SQUARE_SIZE: f32 = 36.0;
+const SVG_FONT_FAMILY: &str = "Helvetica, Arial, sans-serif";
+const SVG_SHADING_COLOR: &str = "#b3b3b3";
+
+// Draws the grid the way the canvas does: a black background that shows
+// through as grid lines and blockers, with a white or shaded square on top
+// for every open square.
+pub fn write_puzzle_to_svg(puzzle: &puzzle_backend::Puzzle, path_str: String, show_fill: bool) -> std::result::Result<(),std::io::Error> {
+    let layout = grid_layout::GridLayout::new(SVG_SQUARE_SIZE, puzzle.width() as u32, puzzle.height() as u32);
+    let (grid_w, grid_h) = layout.grid_size();
+
+    let mut squares = String::new();
+    let mut circles = String::new();
+    let mut labels = String::new();
+    let mut letters = String::new();
+    for sq in puzzle.squares.iter() {
+        let (s, m) = match &sq.content {
+            puzzle_backend::SquareContents::Blocker => { continue; },
+            puzzle_backend::SquareContents::TextContent(s,m) => (s, m),
+        };
+        let (left, top) = layout.content_top_left(sq.x, sq.y);
+        let (cx, cy) = layout.center(sq.x, sq.y);
+        let fill = match m {
+            Some(puzzle_backend::SquareModifier::Shading) => SVG_SHADING_COLOR,
+            _ => "white",
+        };
+        squares.push_str(&format!("<rect x=\"{:.2}\" y=\"{:.2}\" width=\"{:.2}\" height=\"{:.2}\" fill=\"{}\"/>\n", left, top, layout.content_width, layout.content_width, fill));
+        if let Some(puzzle_backend::SquareModifier::Circle) = m {
+            circles.push_str(&format!("<circle cx=\"{:.2}\" cy=\"{:.2}\" r=\"{:.2}\"/>\n", cx, cy, layout.circle_radius()));
+        }
+        if let Some(l) = sq.label {
+            labels.push_str(&format!("<text x=\"{:.2}\" y=\"{:.2}\">{}</text>\n", left, top, l));
+        }
+        if show_fill && !s.is_empty() {
+            letters.push_str(&format!("<text x=\"{:.2}\" y=\"{:.2}\" font-size=\"{:.2}\">{}</text>\n", cx, cy, layout.content_text_size(s), xml_escape(s)));
+        }
+    }
+
+    let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.2}\" height=\"{:.2}\" viewBox=\"0 0 {:.2} {:.2}\">\n", grid_w, grid_h, grid_w, grid_h);
+    if !puzzle.title.is_empty() {
+        svg.push_str(&format!("<title>{}</title>\n", xml_escape(&puzzle.title)));
+    }
+    svg.push_str(&format!("<rect x=\"0\" y=\"0\" width=\"{:.2}\" height=\"{:.2}\" fill=\"black\"/>\n", grid_w, grid_h));
+    svg.push_str(&format!("<g class=\"squares\">\n{}</g>\n", squares));
+    svg.push_str(&format!("<g class=\"circles\" fill=\"none\" stroke=\"black\" stroke-width=\"{:.2}\">\n{}</g>\n", layout.circle_stroke_width(), circles));
+    svg.push_str(&format!("<g class=\"labels\" font-family=\"{}\" font-size=\"{:.2}\" dominant-baseline=\"hanging\">\n{}</g>\n", SVG_FONT_FAMILY, layout.label_size, labels));
+    if show_fill {
+        svg.push_str(&format!("<g class=\"fill\" font-family=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">\n{}</g>\n", SVG_FONT_FAMILY, letters));
+    }
+    svg.push_str("</svg>\n");
+
+    std::fs::write(path_str, svg)
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
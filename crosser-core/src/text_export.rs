@@ -0,0 +1,53 @@
+use crate::grid_region::{TEXT_BLOCKER, TEXT_EMPTY};
+use crate::puzzle_backend;
+
+// A plain text rendering for diffs and quick checks: the header, one line per
+// grid row, then the clues. Rebus squares show their first letter.
+pub fn write_puzzle_to_text(puzzle: &puzzle_backend::Puzzle, path_str: String, show_fill: bool) -> std::result::Result<(),std::io::Error> {
+    let mut out = String::new();
+
+    out.push_str(&puzzle.title);
+    out.push('\n');
+    let mut byline: Vec<String> = Vec::new();
+    if !puzzle.author.is_empty() {
+        byline.push(format!("By {}", puzzle.author));
+    }
+    if !puzzle.editor.is_empty() {
+        byline.push(format!("Edited by {}", puzzle.editor));
+    }
+    for line in [byline.join(" / "), puzzle.copyright.clone()] {
+        if !line.is_empty() {
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+    if !puzzle.notes.is_empty() {
+        out.push('\n');
+        out.push_str(&puzzle.notes);
+        out.push('\n');
+    }
+    out.push('\n');
+
+    for row in puzzle.squares.chunks(puzzle.width()) {
+        for sq in row.iter() {
+            let c = match &sq.content {
+                puzzle_backend::SquareContents::Blocker => TEXT_BLOCKER,
+                puzzle_backend::SquareContents::TextContent(s,_m) if show_fill => s.chars().next().unwrap_or(TEXT_EMPTY),
+                puzzle_backend::SquareContents::TextContent(_s,_m) => TEXT_EMPTY,
+            };
+            out.push(c);
+        }
+        out.push('\n');
+    }
+
+    for (heading, entries) in [("ACROSS", &puzzle.across_entries), ("DOWN", &puzzle.down_entries)] {
+        out.push('\n');
+        out.push_str(heading);
+        out.push('\n');
+        for e in entries.iter() {
+            out.push_str(&format!("{}. {}\n", e.label, e.clue));
+        }
+    }
+
+    std::fs::write(path_str, out)
+}
@@ -0,0 +1,189 @@
+use std::collections::{HashMap, HashSet};
+
+// Plain word lists give every word this score.
+pub const DEFAULT_SCORE: i32 = 50;
+
+// Words scoring below this are never offered or used in a fill.
+pub const MIN_USABLE_SCORE: i32 = 1;
+
+// Parses "WORD;score" lines. Lines without a score (plain lists) get DEFAULT_SCORE.
+// Blank lines and lines starting with '#' are skipped.
+pub fn parse_word_list(contents: &str) -> Vec<(String, i32)> {
+    let mut words = Vec::new();
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut parts = line.splitn(2, ';');
+        let word = normalize(parts.next().unwrap_or(""));
+        if word.is_empty() {
+            continue;
+        }
+        let score = parts.next().and_then(|s| s.trim().parse::<i32>().ok()).unwrap_or(DEFAULT_SCORE);
+        words.push((word, score));
+    }
+    words
+}
+
+pub fn load_word_list(path_str: &str) -> std::result::Result<Vec<(String, i32)>, String> {
+    match std::fs::read_to_string(path_str) {
+        Ok(s) => Ok(parse_word_list(&s)),
+        Err(e) => Err(path_str.to_string() + ": " + &e.to_string()),
+    }
+}
+
+// Entries are stored without spaces or punctuation, in upper case.
+pub fn normalize(word: &str) -> String {
+    word.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_ascii_uppercase()
+}
+
+// Where the lists making up a dictionary live. Lists earlier in `lists` win
+// when the same word appears in more than one.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WordListSources {
+    pub lists: Vec<String>,
+    pub blacklist: Option<String>,
+    pub overrides: Option<String>,
+}
+
+impl WordListSources {
+    // Parses the comma separated list paths typed into the Fill controls.
+    pub fn from_paths(lists: &str, blacklist: &str, overrides: &str) -> Self {
+        let optional = |s: &str| if s.trim().is_empty() { None } else { Some(s.trim().to_string()) };
+        WordListSources {
+            lists: lists.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect(),
+            blacklist: optional(blacklist),
+            overrides: optional(overrides),
+        }
+    }
+
+    pub fn load(&self) -> std::result::Result<WordList, String> {
+        if self.lists.is_empty() {
+            return Err("No word list given.".to_string());
+        }
+        let mut lists = Vec::new();
+        for path in self.lists.iter() {
+            lists.push(load_word_list(path)?);
+        }
+        let blacklist = match &self.blacklist {
+            Some(path) => load_word_list(path)?.into_iter().map(|(w,_s)| w).collect(),
+            None => Vec::new(),
+        };
+        let overrides = match &self.overrides {
+            Some(path) => load_word_list(path)?,
+            None => Vec::new(),
+        };
+        let words = WordList::merge(&lists, &blacklist, &overrides);
+        if words.is_empty() {
+            return Err("The word lists contain no usable words.".to_string());
+        }
+        Ok(words)
+    }
+}
+
+// A merged dictionary indexed for pattern lookups. Words are identified by
+// their index, and indices are ordered best score first.
+pub struct WordList {
+    words: Vec<Vec<u8>>,
+    scores: Vec<i32>,
+    // Word ids of each length, in id (score) order.
+    by_length: HashMap<usize, Vec<u32>>,
+    // Word ids with a given letter at a given position, keyed by (length, position, letter).
+    by_position: HashMap<(usize, usize, u8), Vec<u32>>,
+}
+
+impl WordList {
+    // Builds one dictionary from several lists, highest priority first. A word
+    // keeps the score from the first list it appears in, unless it is
+    // blacklisted or has a score override.
+    pub fn merge(lists: &[Vec<(String, i32)>], blacklist: &[String], overrides: &[(String, i32)]) -> Self {
+        let blacklist: HashSet<String> = blacklist.iter().map(|w| normalize(w)).collect();
+        let overrides: HashMap<String, i32> = overrides.iter().map(|(w,s)| (normalize(w), *s)).collect();
+
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut merged: Vec<(String, i32)> = Vec::new();
+        for list in lists.iter() {
+            for (w, s) in list.iter() {
+                let w = normalize(w);
+                if w.is_empty() || blacklist.contains(&w) || !seen.insert(w.clone()) {
+                    continue;
+                }
+                let score = *overrides.get(&w).unwrap_or(s);
+                if score >= MIN_USABLE_SCORE {
+                    merged.push((w, score));
+                }
+            }
+        }
+        merged.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        let mut list = WordList {
+            words: Vec::with_capacity(merged.len()),
+            scores: Vec::with_capacity(merged.len()),
+            by_length: HashMap::new(),
+            by_position: HashMap::new(),
+        };
+        for (id, (w, s)) in merged.into_iter().enumerate() {
+            let letters = w.into_bytes();
+            list.by_length.entry(letters.len()).or_default().push(id as u32);
+            for (pos, c) in letters.iter().enumerate() {
+                list.by_position.entry((letters.len(), pos, *c)).or_default().push(id as u32);
+            }
+            list.words.push(letters);
+            list.scores.push(s);
+        }
+        list
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    pub fn letters(&self, id: u32) -> &[u8] {
+        &self.words[id as usize]
+    }
+
+    pub fn word(&self, id: u32) -> &str {
+        // Only ASCII letters and digits are ever stored.
+        std::str::from_utf8(&self.words[id as usize]).unwrap_or("")
+    }
+
+    pub fn score(&self, id: u32) -> i32 {
+        self.scores[id as usize]
+    }
+
+    // Ids of every word fitting the pattern, best score first. None matches any
+    // letter, so C?T?? is [Some(b'C'), None, Some(b'T'), None, None].
+    pub fn matching(&self, pattern: &[Option<u8>]) -> Vec<u32> {
+        let len = pattern.len();
+        let empty: Vec<u32> = Vec::new();
+
+        // Start from the most selective fixed letter and check the rest directly.
+        let mut base = self.by_length.get(&len).unwrap_or(&empty);
+        for (pos, c) in pattern.iter().enumerate() {
+            if let Some(c) = c {
+                let ids = self.by_position.get(&(len, pos, c.to_ascii_uppercase())).unwrap_or(&empty);
+                if ids.len() < base.len() {
+                    base = ids;
+                }
+            }
+        }
+
+        base.iter().cloned()
+            .filter(|id| {
+                self.words[*id as usize].iter().zip(pattern.iter())
+                    .all(|(l, c)| c.is_none_or(|c| c.to_ascii_uppercase() == *l))
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Debug for WordList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "WordList({} words)", self.words.len())
+    }
+}
\ No newline at end of file
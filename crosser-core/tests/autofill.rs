@@ -0,0 +1,38 @@
+use crosser_core::autofill;
+use crosser_core::puzzle_backend;
+use crosser_core::word_list;
+
+use std::sync::atomic::AtomicBool;
+
+fn words(list: &[&str]) -> word_list::WordList {
+    let scored: Vec<(String, i32)> = list.iter().map(|w| (w.to_string(), 50)).collect();
+    word_list::WordList::merge(&[scored], &[], &[])
+}
+
+#[test]
+fn unchecked_squares_do_not_block_a_fill() {
+    // C A T
+    // ? # ?
+    // T ? N
+    // The middle squares of the top and bottom rows are only in an across entry.
+    let mut puzzle = puzzle_backend::Puzzle::new(puzzle_backend::PuzzleType::Custom(3, 3));
+    puzzle.squares[4].content = puzzle_backend::SquareContents::Blocker;
+    for (index, letter) in [(0, "C"), (2, "T"), (6, "T"), (8, "N")].iter() {
+        puzzle.squares[*index].content = puzzle_backend::SquareContents::TextContent(letter.to_string(), None);
+    }
+    puzzle.calculate_clues();
+    assert!(puzzle.down_entries.iter().any(|e| e.member_indices.len() == 1));
+
+    let problem = autofill::FillProblem::new(&puzzle);
+    match problem.solve(&words(&["CAT", "COT", "TEN", "TIN"]), &AtomicBool::new(false)) {
+        autofill::FillOutcome::Filled(fill) => {
+            puzzle.apply_fill(&fill);
+            let letters: String = puzzle.squares.iter().map(|s| match &s.content {
+                puzzle_backend::SquareContents::TextContent(t, _m) => t.clone(),
+                puzzle_backend::SquareContents::Blocker => "#".to_string(),
+            }).collect();
+            assert_eq!(letters, "CATO#ITEN");
+        },
+        other => panic!("expected a fill, got {:?}", other),
+    }
+}
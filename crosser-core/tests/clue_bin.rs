@@ -0,0 +1,31 @@
+use crosser_core::puzzle_backend;
+
+fn across_clues(puzzle: &puzzle_backend::Puzzle) -> Vec<(Vec<usize>, String)> {
+    puzzle.across_entries.iter().map(|e| (e.member_indices.clone(), e.clue.clone())).collect()
+}
+
+fn bin(puzzle: &puzzle_backend::Puzzle) -> Vec<String> {
+    puzzle.clue_bin.iter().map(|b| b.clue.clone()).collect()
+}
+
+#[test]
+fn repeated_clues_are_rebinned_one_for_one() {
+    // The middle and bottom rows share a clue, and a blocker splits the middle one.
+    let mut puzzle = puzzle_backend::Puzzle::new(puzzle_backend::PuzzleType::Custom(3, 3));
+    assert!(puzzle.set_clue_text(4, puzzle_backend::EntryVariant::Across, "See 1-Down".to_string()));
+    assert!(puzzle.set_clue_text(5, puzzle_backend::EntryVariant::Across, "See 1-Down".to_string()));
+    let clued = across_clues(&puzzle);
+
+    puzzle.cycle_blocker(1, 1);
+    let split = across_clues(&puzzle);
+    assert_eq!(bin(&puzzle), vec!["See 1-Down"]);
+    assert!(split.contains(&(vec![6, 7, 8], "See 1-Down".to_string())));
+
+    assert!(puzzle.undo());
+    assert_eq!(across_clues(&puzzle), clued);
+    assert!(bin(&puzzle).is_empty());
+
+    assert!(puzzle.redo());
+    assert_eq!(across_clues(&puzzle), split);
+    assert_eq!(bin(&puzzle), vec!["See 1-Down"]);
+}
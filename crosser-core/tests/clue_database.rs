@@ -0,0 +1,34 @@
+use crosser_core::clue_database;
+
+fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("crosser-{}-{}.json", name, std::process::id()))
+}
+
+#[test]
+fn clue_databases_round_trip() {
+    let path = temp_path("clues");
+    let mut db = clue_database::ClueDatabase::new();
+    assert!(db.add("heart", "Organ with four chambers", "mini.ipuz", "2020-01-01"));
+    assert!(db.add("HEART", "Love symbol", "other.cro", ""));
+    db.save(&path).unwrap();
+    let read = clue_database::ClueDatabase::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(read.clues_for("HEART"), db.clues_for("HEART"));
+    assert_eq!(read.len(), 2);
+
+    let mut temp = path.into_os_string();
+    temp.push(".tmp");
+    assert!(!std::path::Path::new(&temp).exists());
+}
+
+#[test]
+fn unreadable_clue_databases_are_errors() {
+    let missing = temp_path("no-clues");
+    assert!(clue_database::ClueDatabase::load(&missing).unwrap().is_empty());
+
+    let path = temp_path("bad-clues");
+    std::fs::write(&path, "{\"answers\": [").unwrap();
+    let res = clue_database::ClueDatabase::load(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert!(res.is_err());
+}
@@ -0,0 +1,316 @@
+use crosser_core::puzzle_backend;
+use crosser_core::puzzle_file_io;
+use crosser_core::puzzle_file_io::CroError;
+
+// Unversioned files from before format_version, then files in version 2, the current and a future format.
+const CORPUS: [&str; 9] = [
+    "empty_mini.cro", "filled_mini.cro", "solvable_mini.cro", "scrambled_mini.cro", "custom_blockers.cro", "legacy_solvable_mini.cro",
+    "version2_format.cro", "current_format.cro", "future_format.cro",
+];
+// A bad scrambled solution makes the loader try every key, so the scrambled
+// file is only fuzzed through its solution, which is all that differs from
+// solvable_mini.cro.
+const FUZZ_SEEDS: [&str; 6] = ["empty_mini.cro", "filled_mini.cro", "solvable_mini.cro", "custom_blockers.cro", "current_format.cro", "future_format.cro"];
+
+fn corpus_file(name: &str) -> String {
+    let path = format!("{}/tests/corpus/{}", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e))
+}
+
+fn corpus_json(name: &str) -> serde_json::Value {
+    serde_json::from_str(&corpus_file(name)).unwrap()
+}
+
+// Loads a candidate file and fails the test with the input if the loader panics.
+fn load(contents: &str) -> Result<puzzle_backend::Puzzle, CroError> {
+    let owned = contents.to_string();
+    match std::panic::catch_unwind(move || puzzle_file_io::get_puzzle_from_cro_str(&owned)) {
+        Ok(result) => result,
+        Err(_) => panic!("loader panicked on input:\n{}", contents),
+    }
+}
+
+fn load_value(value: &serde_json::Value) -> Result<puzzle_backend::Puzzle, CroError> {
+    load(&value.to_string())
+}
+
+// Small deterministic generator so failures reproduce.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        self.0 >> 33
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+// Values of every JSON type, with strings and numbers picked to hit parsing edge cases.
+fn odd_values() -> Vec<serde_json::Value> {
+    vec![
+        serde_json::json!(null),
+        serde_json::json!(true),
+        serde_json::json!(-1),
+        serde_json::json!(4294967296u64),
+        serde_json::json!(1.5),
+        serde_json::json!(""),
+        serde_json::json!("#"),
+        serde_json::json!("/"),
+        serde_json::json!("A/x"),
+        serde_json::json!("é/n"),
+        serde_json::json!("custom"),
+        serde_json::json!("v2::"),
+        serde_json::json!("v9:a:b"),
+        serde_json::json!([]),
+        serde_json::json!(["/n"]),
+        serde_json::json!({}),
+        serde_json::json!({"99": "x", "-1": "y", "a": 1}),
+        serde_json::json!({"scrambled": true, "letters": "abc"}),
+        serde_json::json!({"scrambled": true, "letters": "\u{0}\u{0}"}),
+    ]
+}
+
+// Every path to a field, array element or object member in the document.
+fn value_paths(value: &serde_json::Value, prefix: &mut Vec<String>, paths: &mut Vec<Vec<String>>) {
+    paths.push(prefix.clone());
+    match value {
+        serde_json::Value::Object(m) => {
+            for (k, v) in m.iter() {
+                prefix.push(k.clone());
+                value_paths(v, prefix, paths);
+                prefix.pop();
+            }
+        },
+        serde_json::Value::Array(a) => {
+            for (i, v) in a.iter().enumerate() {
+                prefix.push(i.to_string());
+                value_paths(v, prefix, paths);
+                prefix.pop();
+            }
+        },
+        _ => {},
+    }
+}
+
+fn value_at<'a>(value: &'a mut serde_json::Value, path: &[String]) -> &'a mut serde_json::Value {
+    path.iter().fold(value, |v, key| {
+        match v {
+            serde_json::Value::Array(a) => &mut a[key.parse::<usize>().unwrap()],
+            _ => &mut v[key.as_str()],
+        }
+    })
+}
+
+#[test]
+fn corpus_files_load() {
+    for name in CORPUS.iter() {
+        if let Err(e) = load(&corpus_file(name)) {
+            panic!("{} failed to load: {}", name, e);
+        }
+    }
+    let solvable = load(&corpus_file("solvable_mini.cro")).unwrap();
+    let scrambled = load(&corpus_file("scrambled_mini.cro")).unwrap();
+    assert!(solvable.fill_only);
+    assert_eq!(solvable.solution, scrambled.solution);
+}
+
+#[test]
+fn malformed_fields_are_reported() {
+    let base = corpus_json("filled_mini.cro");
+    let with = |key: &str, value: serde_json::Value| {
+        let mut v = base.clone();
+        v[key] = value;
+        load_value(&v)
+    };
+
+    assert!(matches!(load("{"), Err(CroError::Json(_))));
+    assert!(matches!(load("[]"), Err(CroError::NotAnObject)));
+    assert!(matches!(load("{}"), Err(CroError::MissingField("variant"))));
+    assert!(matches!(with("variant", serde_json::json!(5)), Err(CroError::InvalidField("variant"))));
+    assert!(matches!(with("variant", serde_json::json!("huge")), Err(CroError::UnknownVariant(s)) if s == "huge"));
+    // Preset sizes ignore the stored width.
+    assert!(with("width", serde_json::json!(0)).is_ok());
+    let mut custom = base.clone();
+    custom["variant"] = serde_json::json!("custom");
+    custom["width"] = serde_json::json!(0);
+    assert!(matches!(load_value(&custom), Err(CroError::InvalidDimensions)));
+    assert!(matches!(with("symmetry", serde_json::json!("spiral")), Err(CroError::UnknownSymmetry(_))));
+    assert!(matches!(with("title", serde_json::json!(3)), Err(CroError::InvalidField("title"))));
+    assert!(matches!(with("tags", serde_json::json!([1])), Err(CroError::InvalidField("tags"))));
+    assert!(matches!(with("squares", serde_json::json!(null)), Err(CroError::MissingField("squares"))));
+    assert!(matches!(with("down_clues", serde_json::json!([])), Err(CroError::InvalidField("down_clues"))));
+    assert!(matches!(with("hash_string", serde_json::json!("v7:a:b")), Err(CroError::InvalidSolutionCheck(_))));
+    assert!(matches!(with("solution", serde_json::json!({"scrambled": false, "squares": []})), Err(CroError::MissingField("hash_string"))));
+}
+
+#[test]
+fn malformed_squares_and_clues_name_the_culprit() {
+    let base = corpus_json("filled_mini.cro");
+
+    let mut v = base.clone();
+    v["squares"].as_array_mut().unwrap().push(serde_json::json!("/n"));
+    assert!(matches!(load_value(&v), Err(CroError::SquareCount { expected: 25, found: 26 })));
+
+    let mut v = base.clone();
+    v["squares"][7] = serde_json::json!(7);
+    assert!(matches!(load_value(&v), Err(CroError::InvalidSquare(7))));
+
+    let mut v = base.clone();
+    v["squares"][3] = serde_json::json!("A/q");
+    assert!(matches!(load_value(&v), Err(CroError::UnknownModifier { index: 3, modifier }) if modifier == "q"));
+
+    let mut v = base.clone();
+    v["across_clues"]["one"] = serde_json::json!("Uno");
+    assert!(matches!(load_value(&v), Err(CroError::InvalidClueLabel { variant: puzzle_backend::EntryVariant::Across, label }) if label == "one"));
+
+    let mut v = base.clone();
+    v["down_clues"]["4"] = serde_json::json!(4);
+    assert!(matches!(load_value(&v), Err(CroError::InvalidClueText { variant: puzzle_backend::EntryVariant::Down, label: 4 })));
+
+    let mut v = base.clone();
+    v["down_clues"]["12"] = serde_json::json!("Not in this grid");
+    assert!(matches!(load_value(&v), Err(CroError::UnknownClue { variant: puzzle_backend::EntryVariant::Down, label: 12 })));
+
+    let mut v = corpus_json("solvable_mini.cro");
+    v["entry_checksums"]["across"]["x"] = serde_json::json!("0");
+    assert!(matches!(load_value(&v), Err(CroError::InvalidEntryChecksum { variant: puzzle_backend::EntryVariant::Across, .. })));
+
+    let mut v = corpus_json("scrambled_mini.cro");
+    v["solution"]["letters"] = serde_json::json!("a".repeat(25));
+    assert!(matches!(load_value(&v), Err(CroError::SolutionMismatch)));
+}
+
+#[test]
+fn unversioned_files_are_migrated() {
+    let mut v = corpus_json("filled_mini.cro");
+    let m = v.as_object_mut().unwrap();
+    for key in ["title", "editor", "notes", "tags", "symmetry", "hash_string", "entry_checksums", "solution"] {
+        m.remove(key);
+    }
+    let puzzle = load_value(&v).unwrap();
+    assert_eq!(puzzle.title, "New Puzzle");
+    assert_eq!(puzzle.author, "A. Constructor");
+    assert!(puzzle.tags.is_empty());
+    assert_eq!(puzzle.symmetry, puzzle_backend::Symmetry::None);
+    assert!(!puzzle.fill_only);
+
+    let mut weekday = serde_json::Map::new();
+    weekday.insert("variant".to_string(), serde_json::json!("weekday"));
+    weekday.insert("squares".to_string(), serde_json::json!(vec!["/n"; 225]));
+    weekday.insert("across_clues".to_string(), serde_json::json!({}));
+    weekday.insert("down_clues".to_string(), serde_json::json!({}));
+    let puzzle = load_value(&serde_json::Value::Object(weekday)).unwrap();
+    assert_eq!(puzzle.symmetry, puzzle_backend::Symmetry::Rotational);
+}
+
+#[test]
+fn versioned_files_need_every_field() {
+    let base = corpus_json("current_format.cro");
+    assert_eq!(base["format_version"], serde_json::json!(puzzle_file_io::CRO_FORMAT_VERSION));
+    for key in ["title", "tags", "symmetry", "clue_bin", "hash_string"] {
+        let mut v = base.clone();
+        v.as_object_mut().unwrap().remove(key);
+        assert!(matches!(load_value(&v), Err(CroError::MissingField(k)) if k == key));
+    }
+    for bad in [serde_json::json!(0), serde_json::json!("2"), serde_json::json!(-2)] {
+        let mut v = base.clone();
+        v["format_version"] = bad;
+        assert!(matches!(load_value(&v), Err(CroError::InvalidField("format_version"))));
+    }
+    let mut v = base.clone();
+    v["clue_bin"] = serde_json::json!([{"answer": "HEART"}]);
+    assert!(matches!(load_value(&v), Err(CroError::InvalidField("clue_bin"))));
+}
+
+#[test]
+fn version2_files_get_an_empty_clue_bin() {
+    let puzzle = load(&corpus_file("version2_format.cro")).unwrap();
+    assert!(puzzle.clue_bin.is_empty());
+    let puzzle = load(&corpus_file("current_format.cro")).unwrap();
+    assert_eq!(puzzle.clue_bin.len(), 1);
+    assert_eq!(puzzle.clue_bin[0].clue, "Core");
+}
+
+#[test]
+fn unknown_fields_survive_a_round_trip() {
+    let puzzle = load(&corpus_file("future_format.cro")).unwrap();
+    assert_eq!(puzzle.unknown_fields.len(), 2);
+    let path = std::env::temp_dir().join(format!("crosser-round-trip-{}.cro", std::process::id()));
+    puzzle_file_io::write_puzzle_to_cro(&puzzle, path.to_string_lossy().to_string(), false, false).unwrap();
+    let written: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let original = corpus_json("future_format.cro");
+    assert_eq!(written["format_version"], serde_json::json!(puzzle_file_io::CRO_FORMAT_VERSION));
+    assert_eq!(written["reveal_animations"], original["reveal_animations"]);
+    assert_eq!(written["constructor_notes"], original["constructor_notes"]);
+    assert_eq!(written["across_clues"], original["across_clues"]);
+    assert_eq!(written["clue_bin"], original["clue_bin"]);
+}
+
+#[test]
+fn grids_being_solved_keep_their_answers_and_progress() {
+    let mut puzzle = load(&corpus_file("solvable_mini.cro")).unwrap();
+    assert!(puzzle.fill_only);
+    puzzle.squares[0].content = puzzle_backend::SquareContents::TextContent("X".to_string(), None);
+    let path = std::env::temp_dir().join(format!("crosser-progress-{}.cro", std::process::id()));
+    puzzle_file_io::write_puzzle_to_cro(&puzzle, path.to_string_lossy().to_string(), false, false).unwrap();
+    let mut read = load(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(read.fill_only);
+    assert_eq!(read.solution, puzzle.solution);
+    assert!(read.squares[0].content == puzzle_backend::SquareContents::TextContent("X".to_string(), None));
+    assert_eq!(read.check(&[0]), 1);
+}
+
+#[test]
+fn truncated_files_never_panic() {
+    for name in CORPUS.iter() {
+        let contents = corpus_file(name);
+        for (i, _) in contents.char_indices() {
+            assert!(load(&contents[..i]).is_err());
+        }
+    }
+}
+
+#[test]
+fn replaced_fields_never_panic() {
+    for name in CORPUS.iter() {
+        let base = corpus_json(name);
+        let mut paths = Vec::new();
+        value_paths(&base, &mut Vec::new(), &mut paths);
+        let scrambled = *name == "scrambled_mini.cro";
+        for path in paths.iter().filter(|p| !scrambled || p.first().map(|k| k == "solution").unwrap_or(false)) {
+            for odd in odd_values() {
+                let mut v = base.clone();
+                *value_at(&mut v, path) = odd;
+                let _ = load_value(&v);
+            }
+        }
+    }
+}
+
+#[test]
+fn mutated_bytes_never_panic() {
+    let mut rng = Lcg(0x5eed);
+    let alphabet = b"{}[]\":,/#0123456789-.eEnulltrueAZaz \\";
+    for name in FUZZ_SEEDS.iter() {
+        let contents = corpus_file(name).into_bytes();
+        for _ in 0..1000 {
+            let mut bytes = contents.clone();
+            for _ in 0..=rng.below(4) {
+                let i = rng.below(bytes.len());
+                match rng.below(3) {
+                    0 => bytes[i] = alphabet[rng.below(alphabet.len())],
+                    1 => { bytes.remove(i); },
+                    _ => bytes.insert(i, alphabet[rng.below(alphabet.len())]),
+                }
+            }
+            let _ = load(&String::from_utf8_lossy(&bytes));
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use crosser_core::pdf_export;
+use crosser_core::puzzle_backend;
+
+fn temp_path(name: &str) -> String {
+    std::env::temp_dir().join(format!("crosser-{}-{}", std::process::id(), name)).to_string_lossy().to_string()
+}
+
+// A 3x3 grid with a blocker in the corner, a circle and a shaded square.
+fn small_grid() -> puzzle_backend::Puzzle {
+    let mut puzzle = puzzle_backend::Puzzle::new(puzzle_backend::PuzzleType::Custom(3, 3));
+    puzzle.symmetry = puzzle_backend::Symmetry::None;
+    puzzle.title = "Tiny & Tidy".to_string();
+    puzzle.author = "A. Constructor".to_string();
+    puzzle.cycle_blocker(2, 2);
+    for (index, letter) in "CATAREGO".chars().enumerate() {
+        puzzle.modify_sq_contents((index % 3) as u32, (index / 3) as u32, letter, false);
+    }
+    puzzle.cycle_modifier(0, 0);
+    puzzle.cycle_modifier(0, 0);
+    puzzle.cycle_modifier(1, 1);
+    puzzle.set_clue_text(1, puzzle_backend::EntryVariant::Across, "Pet".to_string());
+    puzzle.set_clue_text(3, puzzle_backend::EntryVariant::Down, "Skill".to_string());
+    puzzle
+}
+
+fn export(puzzle: &puzzle_backend::Puzzle, name: &str, show_fill: bool) -> String {
+    let path = temp_path(name);
+    let result = crosser_core::puzzle_file_io::export_puzzle_to_file(puzzle, path.clone(), &pdf_export::PdfOptions::default(), show_fill);
+    let contents = std::fs::read(&path);
+    std::fs::remove_file(&path).ok();
+    result.unwrap();
+    String::from_utf8_lossy(&contents.unwrap()).to_string()
+}
+
+#[test]
+fn pdf_has_the_grid_clues_and_answer_key() {
+    let pdf = export(&small_grid(), "small.pdf", false);
+    assert!(pdf.starts_with("%PDF-1.4\n"));
+    assert!(pdf.ends_with("%%EOF\n"));
+    assert!(pdf.contains("(Tiny & Tidy)"));
+    assert!(pdf.contains("(Pet)"));
+    assert!(pdf.contains("(Skill)"));
+    // The answer key is on a page of its own.
+    assert_eq!(pdf.matches("/Type /Page ").count(), 2);
+}
+
+#[test]
+fn svg_draws_open_squares_over_a_black_grid() {
+    let svg = export(&small_grid(), "small.svg", false);
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"108.00\" height=\"108.00\" viewBox=\"0 0 108.00 108.00\">\n"));
+    assert!(svg.ends_with("</svg>\n"));
+    assert!(svg.contains("<title>Tiny &amp; Tidy</title>"));
+    assert!(svg.contains("<rect x=\"0\" y=\"0\" width=\"108.00\" height=\"108.00\" fill=\"black\"/>"));
+    // Eight open squares on the background, one of them shaded.
+    assert_eq!(svg.matches("<rect ").count(), 9);
+    assert_eq!(svg.matches("fill=\"#b3b3b3\"").count(), 1);
+    assert!(svg.contains("<rect x=\"36.90\" y=\"36.90\" width=\"34.20\" height=\"34.20\" fill=\"#b3b3b3\"/>"));
+    assert_eq!(svg.matches("<circle ").count(), 1);
+    assert!(svg.contains("<circle cx=\"18.00\" cy=\"18.00\" r=\"15.73\"/>"));
+    assert!(svg.contains("<text x=\"0.90\" y=\"0.90\">1</text>"));
+    assert!(!svg.contains("class=\"fill\""));
+}
+
+#[test]
+fn svg_letters_are_only_drawn_when_asked_for() {
+    let svg = export(&small_grid(), "filled.svg", true);
+    assert!(svg.contains("class=\"fill\""));
+    assert!(svg.contains("<text x=\"18.00\" y=\"18.00\" font-size=\"34.20\">C</text>"));
+    assert_eq!(svg.matches("font-size=\"34.20\">").count(), 8);
+}
+
+#[test]
+fn text_lists_the_grid_and_clues() {
+    let text = export(&small_grid(), "small.txt", false);
+    assert_eq!(text, "Tiny & Tidy\nBy A. Constructor\n\n...\n...\n..#\n\nACROSS\n1. Pet\n4. \n5. \n\nDOWN\n1. \n2. \n3. Skill\n");
+    let filled = export(&small_grid(), "filled.txt", true);
+    assert!(filled.contains("\n\nCAT\nARE\nGO#\n\n"));
+}
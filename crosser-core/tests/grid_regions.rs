@@ -0,0 +1,58 @@
+use crosser_core::grid_region::GridRegion;
+use crosser_core::puzzle_backend;
+
+fn region(text: &str) -> GridRegion {
+    GridRegion::from_text(text).unwrap_or_else(|e| panic!("{}: {}", text, e))
+}
+
+fn letter(s: &str) -> puzzle_backend::SquareContents {
+    puzzle_backend::SquareContents::TextContent(s.to_string(), None)
+}
+
+#[test]
+fn text_round_trips_blockers_and_rebus_squares() {
+    let text = "C#[REBUS]\n.A#\nT.[OK]";
+    let r = region(text);
+    assert_eq!((r.width, r.height), (3, 3));
+    assert!(*r.at(1, 0) == puzzle_backend::SquareContents::Blocker);
+    assert!(*r.at(2, 0) == letter("REBUS"));
+    assert!(*r.at(0, 1) == letter(""));
+    assert!(*r.at(2, 2) == letter("OK"));
+    assert_eq!(r.to_text(), text);
+    assert!(region(&r.to_text()) == r);
+}
+
+#[test]
+fn typed_text_is_tidied_up() {
+    // Lower case, spaces, underscores and short rows all read as a full grid.
+    let r = region("ab\n_ c[xy]\n#\n");
+    assert_eq!((r.width, r.height), (4, 3));
+    assert_eq!(r.to_text(), "AB..\n..C[XY]\n#...");
+}
+
+#[test]
+fn text_that_isnt_a_grid_is_rejected() {
+    assert!(GridRegion::from_text("").is_err());
+    assert!(GridRegion::from_text("\n\n").is_err());
+    assert_eq!(GridRegion::from_text("AB\nC!").err().unwrap(), "'!' on line 2 isn't a letter, '#' or '.'.");
+    assert_eq!(GridRegion::from_text("A[]").err().unwrap(), "Line 1 has a bracketed square that isn't letters.");
+    assert!(GridRegion::from_text("A[B C]").is_err());
+}
+
+#[test]
+fn shifting_wraps_around_the_edges() {
+    let r = region("ABC\nDEF");
+    assert_eq!(r.shifted(1, 0).to_text(), "CAB\nFDE");
+    assert_eq!(r.shifted(0, 1).to_text(), "DEF\nABC");
+    assert_eq!(r.shifted(-1, -1).to_text(), "EFD\nBCA");
+    assert_eq!(r.shifted(3, 2).to_text(), r.to_text());
+}
+
+#[test]
+fn rotating_swaps_width_and_height() {
+    let r = region("AB#\nD[EE]F");
+    let turned = r.rotated();
+    assert_eq!((turned.width, turned.height), (2, 3));
+    assert_eq!(turned.to_text(), "DA\n[EE]B\nF#");
+    assert!(turned.rotated().rotated().rotated() == r);
+}
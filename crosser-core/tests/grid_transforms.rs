@@ -0,0 +1,72 @@
+use crosser_core::puzzle_backend;
+
+fn letters(puzzle: &puzzle_backend::Puzzle) -> String {
+    puzzle.squares.iter().map(|sq| match &sq.content {
+        puzzle_backend::SquareContents::TextContent(s, _) if s.is_empty() => ".".to_string(),
+        puzzle_backend::SquareContents::TextContent(s, _) => s.clone(),
+        puzzle_backend::SquareContents::Blocker => "#".to_string(),
+    }).collect()
+}
+
+fn typed(width: usize, height: usize, text: &str) -> puzzle_backend::Puzzle {
+    let mut puzzle = puzzle_backend::Puzzle::new(puzzle_backend::PuzzleType::Custom(width, height));
+    for (i, c) in text.chars().enumerate() {
+        if c != '.' {
+            puzzle.modify_sq_contents((i % width) as u32, (i / width) as u32, c, false);
+        }
+    }
+    puzzle
+}
+
+fn clue_of(entries: &[puzzle_backend::PuzzleEntry], members: &[usize]) -> Option<String> {
+    entries.iter().find(|e| e.member_indices == members).map(|e| e.clue.clone())
+}
+
+#[test]
+fn rotating_turns_the_grid_clockwise() {
+    let mut puzzle = typed(3, 2, "ABCDEF");
+    assert!(puzzle.transform(puzzle_backend::GridTransform::Rotate));
+    assert_eq!((puzzle.width(), puzzle.height()), (2, 3));
+    assert_eq!(letters(&puzzle), "DAEBFC");
+}
+
+#[test]
+fn rotating_an_empty_grid_is_still_a_change() {
+    let mut puzzle = puzzle_backend::Puzzle::new(puzzle_backend::PuzzleType::Custom(4, 7));
+    let before = puzzle.snapshot();
+    assert!(puzzle.transform(puzzle_backend::GridTransform::Rotate));
+    assert!(before != puzzle.snapshot());
+}
+
+#[test]
+fn transposing_swaps_across_and_down() {
+    let mut puzzle = typed(3, 3, "CAT......");
+    assert!(puzzle.set_clue_text(1, puzzle_backend::EntryVariant::Across, "Feline".to_string()));
+    assert!(puzzle.transform(puzzle_backend::GridTransform::Transpose));
+    assert_eq!(letters(&puzzle), "C..A..T..");
+    assert_eq!(clue_of(&puzzle.down_entries, &[0, 3, 6]), Some("Feline".to_string()));
+    assert_eq!(clue_of(&puzzle.across_entries, &[0, 1, 2]), Some(String::new()));
+}
+
+#[test]
+fn rotated_clues_follow_their_squares() {
+    let mut puzzle = typed(3, 3, "CAT......");
+    assert!(puzzle.set_clue_text(1, puzzle_backend::EntryVariant::Across, "Feline".to_string()));
+    assert!(puzzle.transform(puzzle_backend::GridTransform::Rotate));
+    // The top row ends up as the right column, reading downwards.
+    assert_eq!(letters(&puzzle), "..C..A..T");
+    assert_eq!(clue_of(&puzzle.down_entries, &[2, 5, 8]), Some("Feline".to_string()));
+}
+
+#[test]
+fn shifting_wraps_around_the_edges() {
+    let mut puzzle = typed(3, 3, "CAT.....Z");
+    assert!(puzzle.set_clue_text(1, puzzle_backend::EntryVariant::Across, "Feline".to_string()));
+    assert!(puzzle.transform(puzzle_backend::GridTransform::Shift(1, 1)));
+    assert_eq!(letters(&puzzle), "Z..TCA...");
+    assert_eq!(clue_of(&puzzle.across_entries, &[3, 4, 5]), Some("Feline".to_string()));
+
+    assert!(puzzle.transform(puzzle_backend::GridTransform::Shift(-1, -1)));
+    assert_eq!(letters(&puzzle), "CAT.....Z");
+    assert_eq!(clue_of(&puzzle.across_entries, &[0, 1, 2]), Some("Feline".to_string()));
+}
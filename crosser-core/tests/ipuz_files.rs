@@ -0,0 +1,120 @@
+use crosser_core::puzzle_backend;
+use crosser_core::puzzle_file_io;
+
+fn sample(name: &str) -> puzzle_backend::Puzzle {
+    let path = format!("{}/../samples/{}", env!("CARGO_MANIFEST_DIR"), name);
+    puzzle_file_io::get_puzzle_from_ipuz(path.clone()).unwrap_or_else(|e| panic!("{}: {}", path, e))
+}
+
+// A 4x3 custom grid with blockers in two corners.
+fn custom_blockers() -> puzzle_backend::Puzzle {
+    let mut puzzle = puzzle_backend::Puzzle::new(puzzle_backend::PuzzleType::Custom(4, 3));
+    for (index, letter) in "#ABCDEFGHIJ#".chars().enumerate() {
+        puzzle.squares[index].content = match letter {
+            '#' => puzzle_backend::SquareContents::Blocker,
+            l => puzzle_backend::SquareContents::TextContent(l.to_string(), None),
+        };
+    }
+    puzzle.calculate_clues();
+    puzzle.set_clue_text(1, puzzle_backend::EntryVariant::Across, "First row".to_string());
+    puzzle.set_clue_text(1, puzzle_backend::EntryVariant::Down, "Second column".to_string());
+    puzzle
+}
+
+fn letters(puzzle: &puzzle_backend::Puzzle) -> Vec<String> {
+    puzzle.squares.iter().map(|s| match &s.content {
+        puzzle_backend::SquareContents::TextContent(t, _m) => t.clone(),
+        puzzle_backend::SquareContents::Blocker => "#".to_string(),
+    }).collect()
+}
+
+fn modifier(puzzle: &puzzle_backend::Puzzle, index: usize) -> Option<puzzle_backend::SquareModifier> {
+    match &puzzle.squares[index].content {
+        puzzle_backend::SquareContents::TextContent(_t, m) => m.clone(),
+        puzzle_backend::SquareContents::Blocker => None,
+    }
+}
+
+fn clues(entries: &[puzzle_backend::PuzzleEntry]) -> Vec<(u32, &str)> {
+    entries.iter().map(|e| (e.label, e.clue.as_str())).collect()
+}
+
+fn blocker_count(puzzle: &puzzle_backend::Puzzle) -> usize {
+    letters(puzzle).iter().filter(|l| *l == "#").count()
+}
+
+#[test]
+fn mini_sample_loads() {
+    let puzzle = sample("mini.ipuz");
+    assert_eq!((puzzle.width(), puzzle.height()), (5, 5));
+    assert_eq!(blocker_count(&puzzle), 0);
+    assert_eq!(puzzle.title, "Sample Mini");
+    assert_eq!(letters(&puzzle).concat(), "HEARTEMBERABUSERESINTREND");
+    assert!(modifier(&puzzle, 0) == Some(puzzle_backend::SquareModifier::Shading));
+    assert!(modifier(&puzzle, 12) == Some(puzzle_backend::SquareModifier::Circle));
+    assert!(modifier(&puzzle, 24) == Some(puzzle_backend::SquareModifier::Shading));
+    assert_eq!(clues(&puzzle.across_entries), vec![
+        (1, "Organ with four chambers"), (6, "Glowing coal"), (7, "Misuse"), (8, "Sticky tree secretion"), (9, "Fad"),
+    ]);
+    assert_eq!(clues(&puzzle.down_entries), vec![
+        (1, "Love symbol"), (2, "Fireplace remnant"), (3, "Mistreat"), (4, "Amber, originally"), (5, "Social media topic"),
+    ]);
+}
+
+#[test]
+fn rebus_sample_loads() {
+    // Wrapped in ipuz(...), with named styles, object cells and clue objects.
+    let puzzle = sample("rebus.ipuz");
+    assert_eq!((puzzle.width(), puzzle.height()), (3, 3));
+    assert_eq!(blocker_count(&puzzle), 0);
+    assert_eq!(letters(&puzzle), vec!["C", "A", "T", "A", "ROM", "A", "T", "A", "N"]);
+    assert!(modifier(&puzzle, 4) == Some(puzzle_backend::SquareModifier::Circle));
+    assert!(modifier(&puzzle, 8) == Some(puzzle_backend::SquareModifier::Shading));
+    assert_eq!(clues(&puzzle.across_entries), vec![(1, "Feline pet"), (4, "Coffee shop smell"), (5, "Beach souvenir?")]);
+    assert_eq!(clues(&puzzle.down_entries), vec![(1, "Tabby, e.g."), (2, "Scent"), (3, "Bronze shade")]);
+}
+
+#[test]
+fn ipuz_files_round_trip() {
+    let path = std::env::temp_dir().join(format!("crosser-round-trip-{}.ipuz", std::process::id())).to_string_lossy().to_string();
+    let originals = [sample("mini.ipuz"), sample("rebus.ipuz"), custom_blockers()];
+    for original in originals.iter() {
+        puzzle_file_io::write_puzzle_to_ipuz(original, path.clone()).unwrap();
+        let read = puzzle_file_io::get_puzzle_from_ipuz(path.clone()).unwrap();
+        assert_eq!((read.width(), read.height()), (original.width(), original.height()));
+        assert_eq!(blocker_count(&read), blocker_count(original));
+        assert_eq!(letters(&read), letters(original));
+        for i in 0..original.squares.len() {
+            assert!(modifier(&read, i) == modifier(original, i), "square {}", i);
+        }
+        assert_eq!(clues(&read.across_entries), clues(&original.across_entries));
+        assert_eq!(clues(&read.down_entries), clues(&original.down_entries));
+        assert_eq!(read.title, original.title);
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn solvable_grids_keep_their_solution_in_ipuz_files() {
+    let path = format!("{}/tests/corpus/solvable_mini.cro", env!("CARGO_MANIFEST_DIR"));
+    let puzzle = puzzle_file_io::get_puzzle_from_cro(path).unwrap();
+    assert!(puzzle.fill_only);
+    let path = std::env::temp_dir().join(format!("crosser-solvable-{}.ipuz", std::process::id())).to_string_lossy().to_string();
+    puzzle_file_io::write_puzzle_to_ipuz(&puzzle, path.clone()).unwrap();
+    let read = puzzle_file_io::get_puzzle_from_ipuz(path.clone()).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(letters(&read), puzzle.solution.clone().unwrap());
+    assert_eq!(clues(&read.across_entries), clues(&puzzle.across_entries));
+}
+
+#[test]
+fn oversized_ipuz_grids_are_rejected() {
+    let path = std::env::temp_dir().join(format!("crosser-oversized-{}.ipuz", std::process::id())).to_string_lossy().to_string();
+    for (width, height) in [(4294967296u64, 4294967296u64), (51, 5), (0, 5)] {
+        let contents = format!(r#"{{"version": "http://ipuz.org/v2", "kind": ["http://ipuz.org/crossword#1"], "dimensions": {{"width": {}, "height": {}}}, "puzzle": [], "clues": {{}}}}"#, width, height);
+        std::fs::write(&path, contents).unwrap();
+        let res = puzzle_file_io::get_puzzle_from_ipuz(path.clone());
+        assert!(res.err().unwrap().contains("not supported"), "{}x{}", width, height);
+    }
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,181 @@
+use crosser_core::puzzle_backend;
+use crosser_core::puzzle_file_io;
+
+// Offsets into the .puz header, from the community format description.
+const GLOBAL_CKSUM: usize = 0x00;
+const CIB_CKSUM: usize = 0x0E;
+const SCRAMBLED_TAG: usize = 0x32;
+const HEADER_LEN: usize = 0x34;
+
+// A 5x5 word square with a circle in the first square and shading in the middle one.
+fn filled_mini() -> puzzle_backend::Puzzle {
+    let mut puzzle = puzzle_backend::Puzzle::new(puzzle_backend::PuzzleType::Mini);
+    puzzle.title = "Word Square".to_string();
+    for (index, letter) in "HEARTEMBERABUSERESINTREND".chars().enumerate() {
+        let modifier = match index {
+            0 => Some(puzzle_backend::SquareModifier::Circle),
+            12 => Some(puzzle_backend::SquareModifier::Shading),
+            _ => None,
+        };
+        puzzle.squares[index].content = puzzle_backend::SquareContents::TextContent(letter.to_string(), modifier);
+    }
+    puzzle.calculate_clues();
+    let across = ["Organ that pumps", "Glowing coal", "Misuse", "Sticky tree sap", "Fashion movement"];
+    let down = ["Listen", "Bring on board", "Stroke of a brush... or verb", "Rest", "Pattern"];
+    for (label, clue) in [1, 6, 7, 8, 9].iter().zip(across.iter()) {
+        puzzle.set_clue_text(*label, puzzle_backend::EntryVariant::Across, clue.to_string());
+    }
+    for (label, clue) in [1, 2, 3, 4, 5].iter().zip(down.iter()) {
+        puzzle.set_clue_text(*label, puzzle_backend::EntryVariant::Down, clue.to_string());
+    }
+    puzzle
+}
+
+fn corpus_puzzle(name: &str) -> puzzle_backend::Puzzle {
+    let path = format!("{}/tests/corpus/{}", env!("CARGO_MANIFEST_DIR"), name);
+    puzzle_file_io::get_puzzle_from_cro(path.clone()).unwrap_or_else(|e| panic!("{}: {}", path, e))
+}
+
+fn temp_path(name: &str) -> String {
+    std::env::temp_dir().join(format!("crosser-{}-{}.puz", name, std::process::id())).to_string_lossy().to_string()
+}
+
+// Writes the puzzle and returns the bytes of the file.
+fn write_bytes(puzzle: &puzzle_backend::Puzzle, name: &str, scramble_key: Option<u16>) -> Vec<u8> {
+    let path = temp_path(name);
+    puzzle_file_io::write_puzzle_to_puz(puzzle, path.clone(), scramble_key).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    bytes
+}
+
+fn read_bytes(bytes: &[u8], name: &str) -> Result<puzzle_backend::Puzzle, String> {
+    let path = temp_path(name);
+    std::fs::write(&path, bytes).unwrap();
+    let puzzle = puzzle_file_io::get_puzzle_from_puz(path.clone());
+    std::fs::remove_file(&path).unwrap();
+    puzzle
+}
+
+// The checksum is worked out here independently of the writer.
+fn cksum(data: &[u8], mut sum: u16) -> u16 {
+    for b in data.iter() {
+        sum = sum.rotate_right(1).wrapping_add(*b as u16);
+    }
+    sum
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn clues(puzzle: &puzzle_backend::Puzzle) -> Vec<(u32, String)> {
+    puzzle.across_entries.iter().chain(puzzle.down_entries.iter()).map(|e| (e.label, e.clue.clone())).collect()
+}
+
+fn contents(puzzle: &puzzle_backend::Puzzle) -> Vec<puzzle_backend::SquareContents> {
+    puzzle.squares.iter().map(|s| s.content.clone()).collect()
+}
+
+#[test]
+fn puz_files_round_trip() {
+    let puzzle = filled_mini();
+    let read = read_bytes(&write_bytes(&puzzle, "round-trip", None), "round-trip").unwrap();
+    assert_eq!((read.width(), read.height()), (puzzle.width(), puzzle.height()));
+    assert_eq!(read.title, puzzle.title);
+    assert_eq!(clues(&read), clues(&puzzle));
+    assert!(contents(&read) == contents(&puzzle));
+}
+
+#[test]
+fn circles_and_shading_survive_gext() {
+    let puzzle = filled_mini();
+    let modifier = |p: &puzzle_backend::Puzzle, i: usize| match &p.squares[i].content {
+        puzzle_backend::SquareContents::TextContent(_s, m) => m.clone(),
+        puzzle_backend::SquareContents::Blocker => None,
+    };
+
+    let bytes = write_bytes(&puzzle, "gext", None);
+    let gext = bytes.windows(4).position(|w| w == b"GEXT").expect("no GEXT section");
+    let body = &bytes[gext + 8..gext + 8 + 25];
+    assert_eq!(body[0], 0x80);
+    assert_eq!(body[12], 0x08);
+    assert!(body.iter().enumerate().all(|(i, g)| i == 0 || i == 12 || *g == 0));
+
+    let read = read_bytes(&bytes, "gext").unwrap();
+    for i in 0..puzzle.squares.len() {
+        assert!(modifier(&read, i) == modifier(&puzzle, i), "square {}", i);
+    }
+}
+
+#[test]
+fn puz_checksums_are_written_and_checked() {
+    let puzzle = filled_mini();
+    let bytes = write_bytes(&puzzle, "checksums", None);
+    let n = puzzle.width() * puzzle.height();
+    let cib = cksum(&bytes[0x2C..HEADER_LEN], 0);
+    assert_eq!(read_u16(&bytes, CIB_CKSUM), cib);
+    // The global checksum covers the grids and the strings, without their terminators.
+    let mut global = cksum(&bytes[HEADER_LEN..HEADER_LEN + 2 * n], cib);
+    let mut pos = HEADER_LEN + 2 * n;
+    let num_clues = read_u16(&bytes, 0x2E) as usize;
+    for i in 0..num_clues + 4 {
+        let len = bytes[pos..].iter().position(|b| *b == 0).unwrap();
+        // Empty title, author, copyright and notes don't count.
+        let is_clue = i >= 3 && i < 3 + num_clues;
+        if len > 0 {
+            let with_terminator = if is_clue { len } else { len + 1 };
+            global = cksum(&bytes[pos..pos + with_terminator], global);
+        }
+        pos += len + 1;
+    }
+    assert_eq!(read_u16(&bytes, GLOBAL_CKSUM), global);
+
+    // Any change to the header or the clues is caught.
+    let mut bad_header = bytes.clone();
+    bad_header[0x30] ^= 1;
+    assert!(read_bytes(&bad_header, "bad-header").err().unwrap().contains("header checksum"));
+    let mut bad_clue = bytes.clone();
+    let clue_start = HEADER_LEN + 2 * n + puzzle.title.len() + 3;
+    bad_clue[clue_start] ^= 1;
+    assert!(read_bytes(&bad_clue, "bad-clue").err().unwrap().contains("file checksum"));
+}
+
+#[test]
+fn scrambled_puz_files_unscramble() {
+    let puzzle = filled_mini();
+    let plain = write_bytes(&puzzle, "plain", None);
+    let scrambled = write_bytes(&puzzle, "scrambled", Some(1234));
+    let n = puzzle.width() * puzzle.height();
+    assert_eq!(read_u16(&scrambled, SCRAMBLED_TAG) & 0x0004, 0x0004);
+    assert_ne!(&scrambled[HEADER_LEN..HEADER_LEN + n], &plain[HEADER_LEN..HEADER_LEN + n]);
+
+    let read = read_bytes(&scrambled, "scrambled").unwrap();
+    assert!(contents(&read) == contents(&puzzle));
+
+    // Keys must have four digits, and only grids filled with A-Z can be scrambled.
+    let path = temp_path("bad-key");
+    assert!(puzzle_file_io::write_puzzle_to_puz(&puzzle, path.clone(), Some(999)).is_err());
+    let empty = puzzle_backend::Puzzle::new(puzzle_backend::PuzzleType::Mini);
+    assert!(puzzle_file_io::write_puzzle_to_puz(&empty, path, Some(1234)).is_err());
+}
+
+#[test]
+fn solvable_grids_keep_their_solution_in_puz_files() {
+    let mut puzzle = corpus_puzzle("solvable_mini.cro");
+    assert!(puzzle.fill_only);
+    let solution = puzzle.solution.clone().unwrap();
+    // What the solver typed goes in the player's grid, not the solution.
+    puzzle.squares[1].content = puzzle_backend::SquareContents::TextContent("X".to_string(), None);
+    let bytes = write_bytes(&puzzle, "solvable", None);
+    let n = puzzle.width() * puzzle.height();
+    assert_eq!(&bytes[HEADER_LEN..HEADER_LEN + n], solution.concat().as_bytes());
+    assert_eq!(bytes[HEADER_LEN + n + 1], b'X');
+
+    let read = read_bytes(&bytes, "solvable").unwrap();
+    let letters: Vec<String> = read.squares.iter().map(|s| match &s.content {
+        puzzle_backend::SquareContents::TextContent(t, _m) => t.clone(),
+        puzzle_backend::SquareContents::Blocker => "#".to_string(),
+    }).collect();
+    assert_eq!(letters, solution);
+}
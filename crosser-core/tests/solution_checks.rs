@@ -0,0 +1,90 @@
+use crosser_core::puzzle_backend;
+use crosser_core::puzzle_file_io;
+use crosser_core::solution_check::SolutionCheck;
+
+const ANSWER: &str = "HEARTEMBERABUSERESINTREND";
+
+fn corpus_puzzle(name: &str) -> puzzle_backend::Puzzle {
+    let path = format!("{}/tests/corpus/{}", env!("CARGO_MANIFEST_DIR"), name);
+    let contents = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+    puzzle_file_io::get_puzzle_from_cro_str(&contents).unwrap_or_else(|e| panic!("{}: {}", path, e))
+}
+
+fn squares(letters: &str) -> Vec<String> {
+    letters.chars().map(|c| c.to_string()).collect()
+}
+
+fn type_grid(puzzle: &mut puzzle_backend::Puzzle, letters: &str) {
+    for (i, c) in letters.chars().enumerate() {
+        puzzle.modify_sq_contents((i % 5) as u32, (i / 5) as u32, c, false);
+    }
+}
+
+#[test]
+fn v2_checks_round_trip() {
+    let saved = "v2:300ffe57e6e77bf2:df130201522dc6ed8bd95d7664efda3ace65c943d4387e3bbe59c78f04d39b6e";
+    let check = SolutionCheck::parse(saved).unwrap();
+    assert_eq!(check.to_hash_string(), saved);
+    assert!(check.matches(&squares(ANSWER)));
+    assert!(check.matches(&squares(&ANSWER.to_lowercase())));
+    assert!(!check.matches(&squares("HEARTEMBERABUSERESINTRENT")));
+
+    // A fresh check is salted, but reads back the same way.
+    let puzzle = corpus_puzzle("solvable_mini.cro");
+    let fresh = SolutionCheck::new(&squares(ANSWER), &puzzle);
+    assert!(fresh.to_hash_string() != saved);
+    let reread = SolutionCheck::parse(&fresh.to_hash_string()).unwrap();
+    assert!(reread.matches(&squares(ANSWER)));
+    assert!(!reread.matches(&squares(&ANSWER.replace('H', "X"))));
+}
+
+#[test]
+fn unsupported_checks_are_rejected() {
+    assert!(SolutionCheck::parse("v3:abc:def").is_err());
+    assert!(SolutionCheck::parse("v2:abc").is_err());
+    assert!(SolutionCheck::parse("not a number").is_err());
+}
+
+#[test]
+fn legacy_checks_still_load_and_check() {
+    let mut puzzle = corpus_puzzle("legacy_solvable_mini.cro");
+    assert!(puzzle.fill_only);
+    assert!(matches!(puzzle.solution_check, Some(SolutionCheck::Legacy(_))));
+    assert!(!puzzle.can_check());
+
+    type_grid(&mut puzzle, "HEARTEMBERABUSERESINTRENT");
+    assert!(!puzzle.is_solved());
+    puzzle.modify_sq_contents(4, 4, 'D', false);
+    assert!(puzzle.is_solved());
+
+    // Legacy checks are never upgraded behind the file's back.
+    assert_eq!(puzzle.solution_check.as_ref().unwrap().to_hash_string(), "9384022414516694457");
+}
+
+#[test]
+fn entry_checksums_check_words() {
+    let check = corpus_puzzle("solvable_mini.cro").solution_check.unwrap();
+    assert_eq!(check.entry_checksums().len(), 10);
+    assert_eq!(check.entry_matches(1, puzzle_backend::EntryVariant::Across, "HEART"), Some(true));
+    assert_eq!(check.entry_matches(1, puzzle_backend::EntryVariant::Across, "heart"), Some(true));
+    assert_eq!(check.entry_matches(1, puzzle_backend::EntryVariant::Across, "HEARD"), Some(false));
+    assert_eq!(check.entry_matches(1, puzzle_backend::EntryVariant::Down, "HEART"), Some(true));
+    assert_eq!(check.entry_matches(9, puzzle_backend::EntryVariant::Across, "TREND"), Some(true));
+    assert_eq!(check.entry_matches(9, puzzle_backend::EntryVariant::Down, "TREND"), None);
+}
+
+#[test]
+fn entries_are_checked_without_the_answers() {
+    let mut puzzle = corpus_puzzle("solvable_mini.cro");
+    puzzle.solution = None;
+    assert!(puzzle.can_check());
+
+    // Only the top row is finished, and its last letter is wrong.
+    type_grid(&mut puzzle, "HEARX");
+    assert_eq!(puzzle.check(&(0..25).collect::<Vec<usize>>()), 5);
+    let marked: Vec<usize> = (0..25).filter(|i| puzzle.marks[*i] == Some(puzzle_backend::SolveMark::Wrong)).collect();
+    assert_eq!(marked, vec![0, 1, 2, 3, 4]);
+
+    puzzle.modify_sq_contents(4, 0, 'T', false);
+    assert_eq!(puzzle.check(&[0, 1, 2, 3, 4]), 0);
+}
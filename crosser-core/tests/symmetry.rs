@@ -0,0 +1,67 @@
+use crosser_core::puzzle_backend;
+
+fn partners(variant: puzzle_backend::PuzzleType, symmetry: puzzle_backend::Symmetry, x: u32, y: u32) -> Vec<(u32, u32)> {
+    let mut puzzle = puzzle_backend::Puzzle::new(variant);
+    puzzle.symmetry = symmetry;
+    puzzle.symmetric_partners(x, y)
+}
+
+fn blockers(puzzle: &puzzle_backend::Puzzle) -> Vec<usize> {
+    (0..puzzle.squares.len()).filter(|i| puzzle.squares[*i].content == puzzle_backend::SquareContents::Blocker).collect()
+}
+
+#[test]
+fn each_symmetry_has_its_own_partners() {
+    let wide = puzzle_backend::PuzzleType::Custom(5, 4);
+    assert_eq!(partners(wide.clone(), puzzle_backend::Symmetry::None, 0, 1), vec![]);
+    assert_eq!(partners(wide.clone(), puzzle_backend::Symmetry::Rotational, 0, 1), vec![(4, 2)]);
+    assert_eq!(partners(wide.clone(), puzzle_backend::Symmetry::MirrorLeftRight, 0, 1), vec![(4, 1)]);
+    assert_eq!(partners(wide.clone(), puzzle_backend::Symmetry::MirrorTopBottom, 0, 1), vec![(0, 2)]);
+    assert_eq!(partners(wide.clone(), puzzle_backend::Symmetry::FourWay, 0, 1), vec![(0, 2), (4, 1), (4, 2)]);
+    // Only square grids have a diagonal to reflect across.
+    assert_eq!(partners(wide, puzzle_backend::Symmetry::Diagonal, 0, 1), vec![]);
+    assert_eq!(partners(puzzle_backend::PuzzleType::Custom(4, 4), puzzle_backend::Symmetry::Diagonal, 0, 1), vec![(1, 0)]);
+}
+
+#[test]
+fn squares_on_a_symmetry_line_partner_only_others() {
+    let odd = puzzle_backend::PuzzleType::Custom(5, 5);
+    assert_eq!(partners(odd.clone(), puzzle_backend::Symmetry::Rotational, 2, 2), vec![]);
+    assert_eq!(partners(odd.clone(), puzzle_backend::Symmetry::MirrorLeftRight, 2, 0), vec![]);
+    assert_eq!(partners(odd.clone(), puzzle_backend::Symmetry::FourWay, 2, 0), vec![(2, 4)]);
+    assert_eq!(partners(odd, puzzle_backend::Symmetry::Diagonal, 3, 3), vec![]);
+}
+
+#[test]
+fn blockers_are_placed_with_their_partners() {
+    let mut puzzle = puzzle_backend::Puzzle::new(puzzle_backend::PuzzleType::Custom(5, 4));
+    puzzle.symmetry = puzzle_backend::Symmetry::FourWay;
+    puzzle.cycle_blocker(0, 1);
+    assert_eq!(blockers(&puzzle), vec![5, 9, 10, 14]);
+    assert!(puzzle.symmetry_violations().is_empty());
+}
+
+#[test]
+fn symmetrize_reports_letters_in_the_way() {
+    let mut puzzle = puzzle_backend::Puzzle::new(puzzle_backend::PuzzleType::Custom(3, 3));
+    puzzle.symmetry = puzzle_backend::Symmetry::None;
+    puzzle.cycle_blocker(0, 0);
+    puzzle.cycle_blocker(1, 0);
+    puzzle.modify_sq_contents(2, 2, 'X', false);
+    puzzle.end_edit_group();
+    puzzle.symmetry = puzzle_backend::Symmetry::Rotational;
+    assert_eq!(puzzle.symmetry_violations(), vec![7, 8]);
+
+    // The lettered square blocks the whole change.
+    assert_eq!(puzzle.symmetrize().err().unwrap(), vec![8]);
+    assert_eq!(blockers(&puzzle), vec![0, 1]);
+
+    puzzle.clear_sq_contents(2, 2);
+    puzzle.end_edit_group();
+    assert_eq!(puzzle.symmetrize(), Ok(2));
+    assert_eq!(blockers(&puzzle), vec![0, 1, 7, 8]);
+
+    // Symmetrizing is a single undo step.
+    assert!(puzzle.undo());
+    assert_eq!(blockers(&puzzle), vec![0, 1]);
+}
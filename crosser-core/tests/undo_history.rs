@@ -0,0 +1,93 @@
+use crosser_core::puzzle_backend;
+
+fn letter(puzzle: &puzzle_backend::Puzzle, x: u32, y: u32) -> String {
+    match &puzzle.at(x, y).content {
+        puzzle_backend::SquareContents::TextContent(s, _) => s.clone(),
+        puzzle_backend::SquareContents::Blocker => "#".to_string(),
+    }
+}
+
+fn solvable_puzzle() -> puzzle_backend::Puzzle {
+    let mut puzzle = puzzle_backend::Puzzle::new(puzzle_backend::PuzzleType::Custom(3, 3));
+    puzzle.fill_only = true;
+    puzzle.solution = Some(vec!["A".to_string(); 9]);
+    puzzle
+}
+
+#[test]
+fn typing_runs_merge_into_one_step() {
+    let mut puzzle = puzzle_backend::Puzzle::new(puzzle_backend::PuzzleType::Custom(3, 3));
+    puzzle.modify_sq_contents(0, 0, 'C', false);
+    puzzle.modify_sq_contents(1, 0, 'A', false);
+    puzzle.modify_sq_contents(2, 0, 'T', false);
+    puzzle.end_edit_group();
+    puzzle.modify_sq_contents(0, 1, 'O', false);
+
+    assert!(puzzle.undo());
+    assert_eq!(letter(&puzzle, 0, 1), "");
+    assert_eq!(letter(&puzzle, 2, 0), "T");
+
+    assert!(puzzle.undo());
+    assert_eq!(letter(&puzzle, 0, 0), "");
+    assert_eq!(letter(&puzzle, 1, 0), "");
+    assert_eq!(letter(&puzzle, 2, 0), "");
+    assert!(!puzzle.undo());
+}
+
+#[test]
+fn history_keeps_the_last_500_steps() {
+    let mut puzzle = puzzle_backend::Puzzle::new(puzzle_backend::PuzzleType::Custom(3, 3));
+    for _ in 0..501 {
+        puzzle.cycle_blocker(0, 0);
+    }
+    let mut undone = 0;
+    while puzzle.undo() {
+        undone += 1;
+    }
+    assert_eq!(undone, 500);
+    // The oldest step was dropped, so the first blocker is still there.
+    assert_eq!(letter(&puzzle, 0, 0), "#");
+}
+
+#[test]
+fn a_new_edit_clears_redo() {
+    let mut puzzle = puzzle_backend::Puzzle::new(puzzle_backend::PuzzleType::Custom(3, 3));
+    puzzle.modify_sq_contents(0, 0, 'C', false);
+    assert!(puzzle.undo());
+    puzzle.modify_sq_contents(1, 1, 'A', false);
+    assert!(!puzzle.redo());
+    assert_eq!(letter(&puzzle, 0, 0), "");
+    assert_eq!(letter(&puzzle, 1, 1), "A");
+}
+
+#[test]
+fn undoing_a_reveal_takes_its_mark_away() {
+    let mut puzzle = solvable_puzzle();
+    puzzle.modify_sq_contents(0, 0, 'B', false);
+    puzzle.end_edit_group();
+    puzzle.reveal(&[0]);
+    assert_eq!(letter(&puzzle, 0, 0), "A");
+    assert_eq!(puzzle.marks[0], Some(puzzle_backend::SolveMark::Revealed));
+
+    assert!(puzzle.undo());
+    assert_eq!(letter(&puzzle, 0, 0), "B");
+    assert_eq!(puzzle.marks[0], None);
+
+    assert!(puzzle.redo());
+    assert_eq!(letter(&puzzle, 0, 0), "A");
+    assert_eq!(puzzle.marks[0], Some(puzzle_backend::SolveMark::Revealed));
+}
+
+#[test]
+fn undoing_a_correction_brings_back_the_wrong_mark() {
+    let mut puzzle = solvable_puzzle();
+    puzzle.modify_sq_contents(0, 0, 'B', false);
+    puzzle.end_edit_group();
+    assert_eq!(puzzle.check(&[0]), 1);
+    puzzle.modify_sq_contents(0, 0, 'A', false);
+    assert_eq!(puzzle.marks[0], None);
+
+    assert!(puzzle.undo());
+    assert_eq!(letter(&puzzle, 0, 0), "B");
+    assert_eq!(puzzle.marks[0], Some(puzzle_backend::SolveMark::Wrong));
+}
@@ -0,0 +1,93 @@
+use crosser_core::puzzle_backend;
+use crosser_core::puzzle_validation;
+
+// Builds a grid from rows of letters, '.' for empty squares and '#' for blockers.
+fn grid(variant: puzzle_backend::PuzzleType, rows: &[&str]) -> puzzle_backend::Puzzle {
+    let mut puzzle = puzzle_backend::Puzzle::new(variant);
+    puzzle.symmetry = puzzle_backend::Symmetry::None;
+    for (y, row) in rows.iter().enumerate() {
+        for (x, c) in row.chars().enumerate() {
+            match c {
+                '.' => {},
+                '#' => puzzle.cycle_blocker(x as u32, y as u32),
+                c => puzzle.modify_sq_contents(x as u32, y as u32, c, false),
+            }
+        }
+    }
+    puzzle
+}
+
+fn issue<'a>(report: &'a puzzle_validation::ValidationReport, text: &str) -> Option<&'a puzzle_validation::Issue> {
+    report.issues.iter().find(|i| i.message.contains(text))
+}
+
+fn messages(report: &puzzle_validation::ValidationReport) -> Vec<String> {
+    report.issues.iter().map(|i| i.message.clone()).collect()
+}
+
+#[test]
+fn an_open_grid_is_valid() {
+    let report = grid(puzzle_backend::PuzzleType::Custom(5, 5), &[]).validate();
+    assert!(report.is_valid(), "{}", report.summary());
+}
+
+#[test]
+fn cut_off_squares_are_flagged() {
+    let report = grid(puzzle_backend::PuzzleType::Custom(5, 5), &[".#...", ".#...", ".#...", ".#...", ".#..."]).validate();
+    let islands = issue(&report, "cut off").unwrap();
+    assert_eq!(islands.message, "5 square(s) cut off from the rest of the grid");
+    assert_eq!(islands.squares, vec![0, 5, 10, 15, 20]);
+}
+
+#[test]
+fn squares_in_only_one_entry_are_unchecked() {
+    let report = grid(puzzle_backend::PuzzleType::Custom(3, 3), &["...", ".#.", "..."]).validate();
+    let unchecked = issue(&report, "unchecked").unwrap();
+    assert_eq!(unchecked.message, "4 unchecked square(s)");
+    assert_eq!(unchecked.squares, vec![1, 3, 5, 7]);
+}
+
+#[test]
+fn entries_shorter_than_three_letters_are_flagged() {
+    let report = grid(puzzle_backend::PuzzleType::Custom(5, 5), &["..#.."]).validate();
+    assert_eq!(issue(&report, "1A").unwrap().message, "1A is shorter than 3 letters");
+    assert_eq!(issue(&report, "1A").unwrap().squares, vec![0, 1]);
+    assert_eq!(issue(&report, "3A").unwrap().squares, vec![3, 4]);
+    assert!(issue(&report, "D is shorter").is_none());
+}
+
+#[test]
+fn repeated_answers_are_flagged_once_complete() {
+    let report = grid(puzzle_backend::PuzzleType::Custom(3, 3), &["CAT", "CAT", "CA."]).validate();
+    let duplicate = issue(&report, "is used by").unwrap();
+    assert_eq!(duplicate.message, "CAT is used by 1A, 4A");
+    assert_eq!(duplicate.squares, vec![0, 1, 2, 3, 4, 5]);
+    // The bottom row and right column aren't finished, so they repeat nothing.
+    assert_eq!(report.issues.len(), 1, "{}", report.summary());
+}
+
+#[test]
+fn weekday_and_sunday_grids_have_limits() {
+    assert_eq!(puzzle_validation::limits(&puzzle_backend::PuzzleType::Weekday), Some((78, 38)));
+    assert_eq!(puzzle_validation::limits(&puzzle_backend::PuzzleType::WeekdayAsymmetric), Some((78, 38)));
+    assert_eq!(puzzle_validation::limits(&puzzle_backend::PuzzleType::Sunday), Some((140, 78)));
+    assert_eq!(puzzle_validation::limits(&puzzle_backend::PuzzleType::Mini), None);
+    assert_eq!(puzzle_validation::limits(&puzzle_backend::PuzzleType::Custom(15, 15)), None);
+
+    // A blocker every fourth row and column makes 96 three-letter words.
+    let row = "...#...#...#...";
+    let rows: Vec<&str> = (0..15).map(|y| if y % 4 == 3 { "###############" } else { row }).collect();
+    let weekday = grid(puzzle_backend::PuzzleType::Weekday, &rows).validate();
+    assert!(messages(&weekday).contains(&"96 words, at most 78 allowed".to_string()), "{}", weekday.summary());
+    assert!(messages(&weekday).contains(&"81 blockers, at most 38 allowed".to_string()), "{}", weekday.summary());
+    let custom = grid(puzzle_backend::PuzzleType::Custom(15, 15), &rows).validate();
+    assert!(issue(&custom, "allowed").is_none(), "{}", custom.summary());
+
+    // 40 blockers are too many for a weekday grid but fine on a Sunday.
+    let blocked = ["#".repeat(15), "#".repeat(15), "#".repeat(10)];
+    let blocked: Vec<&str> = blocked.iter().map(|s| s.as_str()).collect();
+    let weekday = grid(puzzle_backend::PuzzleType::Weekday, &blocked).validate();
+    assert!(messages(&weekday).contains(&"40 blockers, at most 38 allowed".to_string()), "{}", weekday.summary());
+    let sunday = grid(puzzle_backend::PuzzleType::Sunday, &blocked).validate();
+    assert!(issue(&sunday, "allowed").is_none(), "{}", sunday.summary());
+}
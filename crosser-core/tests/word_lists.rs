@@ -0,0 +1,72 @@
+use crosser_core::word_list;
+
+fn words(list: &word_list::WordList, ids: &[u32]) -> Vec<(String, i32)> {
+    ids.iter().map(|id| (list.word(*id).to_string(), list.score(*id))).collect()
+}
+
+fn all_words(list: &word_list::WordList) -> Vec<(String, i32)> {
+    words(list, &(0..list.len() as u32).collect::<Vec<u32>>())
+}
+
+fn owned(list: &[(&str, i32)]) -> Vec<(String, i32)> {
+    list.iter().map(|(w, s)| (w.to_string(), *s)).collect()
+}
+
+#[test]
+fn scored_and_plain_lines_are_parsed() {
+    let parsed = word_list::parse_word_list("# comment\nthe cat;60\n\nDOG\nemu; 5\nbad;score\n;40\n");
+    assert_eq!(parsed, owned(&[
+        ("THECAT", 60),
+        ("DOG", word_list::DEFAULT_SCORE),
+        ("EMU", 5),
+        ("BAD", word_list::DEFAULT_SCORE),
+    ]));
+}
+
+#[test]
+fn earlier_lists_win_when_merging() {
+    let first = owned(&[("cat", 40), ("dog", 10)]);
+    let second = owned(&[("CAT", 90), ("emu", 30)]);
+    let list = word_list::WordList::merge(&[first, second], &[], &[]);
+    // Best score first, ties broken alphabetically.
+    assert_eq!(all_words(&list), owned(&[("CAT", 40), ("EMU", 30), ("DOG", 10)]));
+}
+
+#[test]
+fn blacklist_and_overrides_apply_to_every_list() {
+    let first = owned(&[("cat", 40), ("dog", 10)]);
+    let second = owned(&[("emu", 30), ("owl", 20)]);
+    let blacklist = vec!["Dog".to_string()];
+    let overrides = owned(&[("owl", 80), ("emu", 0)]);
+    let list = word_list::WordList::merge(&[first, second], &blacklist, &overrides);
+    // EMU's override takes it below MIN_USABLE_SCORE.
+    assert_eq!(all_words(&list), owned(&[("OWL", 80), ("CAT", 40)]));
+}
+
+#[test]
+fn unusable_scores_are_left_out() {
+    let scores = owned(&[
+        ("ant", word_list::MIN_USABLE_SCORE),
+        ("bee", word_list::MIN_USABLE_SCORE - 1),
+        ("cow", -20),
+    ]);
+    let list = word_list::WordList::merge(&[scores], &[], &[]);
+    assert_eq!(all_words(&list), owned(&[("ANT", word_list::MIN_USABLE_SCORE)]));
+}
+
+#[test]
+fn patterns_match_by_length_and_fixed_letters() {
+    let scores = owned(&[("cater", 50), ("cited", 70), ("coats", 30), ("cat", 90), ("after", 60), ("cutter", 80)]);
+    let list = word_list::WordList::merge(&[scores], &[], &[]);
+
+    let c_t = [Some(b'C'), None, Some(b'T'), None, None];
+    assert_eq!(words(&list, &list.matching(&c_t)), owned(&[("CITED", 70), ("CATER", 50)]));
+
+    // Lower case letters in the pattern match too.
+    let lower = [None, None, Some(b't'), Some(b'e'), Some(b'r')];
+    assert_eq!(words(&list, &list.matching(&lower)), owned(&[("AFTER", 60), ("CATER", 50)]));
+
+    assert_eq!(list.matching(&[None; 5]).len(), 4);
+    assert!(list.matching(&[Some(b'Z'), None, None]).is_empty());
+    assert!(list.matching(&[None; 7]).is_empty());
+}
@@ -1,18 +1,50 @@
-use iced::{Align, Canvas, Column, Element, Length, Row, Sandbox};
-use crate::puzzle_backend;
-use crate::puzzle_canvas;
-use crate::clue_ui;
+use iced::{Application, Column, Command, Element, executor, Row, Subscription, Text};
+use crosser_core::autofill;
+use crosser_core::clue_database;
+use crosser_core::grid_region;
+use crosser_core::puzzle_backend;
+use crate::clue_store;
 use crate::controls_ui;
-use crate::puzzle_file_io;
+use crate::file_dialogs;
+use crosser_core::pdf_export;
+use crosser_core::puzzle_file_io;
+use crate::recent_files;
+use crate::recovery;
+use crate::system_clipboard;
+use crate::tab;
+use crosser_core::word_list;
 
-use std::rc::Rc;
-use std::cell::RefCell;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// How often unsaved changes are written to the recovery files.
+const AUTOSAVE_SECONDS: u64 = 30;
 
 pub struct CrosserUI {
-    puzzle: Rc<RefCell<puzzle_backend::Puzzle>>,
-    puzzle_ui: puzzle_canvas::PuzzleCanvas,
-    clues: clue_ui::CluesBrowser,
+    tabs: Vec<tab::Tab>,
+    // Index into tabs of the tab being shown.
+    active: usize,
+    next_tab_id: usize,
     controls: controls_ui::ControlsRow,
+    // The last word list loaded, and the files it came from.
+    word_list: Option<(word_list::WordListSources, Arc<word_list::WordList>)>,
+    fill_cancel: Option<Arc<AtomicBool>>,
+    // The id of the tab being filled, which may no longer be the active one when the fill ends.
+    fill_tab: Option<usize>,
+    // The blockers and letters the fill was worked out for. The grid can still be
+    // changed with the mouse meanwhile, and a fill for another grid is no use.
+    fill_grid: Vec<Option<String>>,
+    recent_files: recent_files::RecentFiles,
+    // Set while running a message the user agreed may throw away unsaved changes.
+    discard_confirmed: bool,
+    // Puzzles autosaved by a session that didn't end cleanly, until the user restores or discards them.
+    recovered: Vec<(puzzle_backend::Puzzle, Option<String>)>,
+    // The last region copied, shared by every tab.
+    clipboard: Option<grid_region::GridRegion>,
+    // Every clue written so far, by answer. Saving a puzzle adds its clues.
+    clue_db: clue_database::ClueDatabase,
+    // Why the clue database on disk couldn't be read. It isn't saved over while this is set.
+    clue_db_error: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -21,94 +53,475 @@ pub enum Message {
     ClueModified(String),
     ClueLeftModification(u32,puzzle_backend::EntryVariant),
     CluesUpdated,
+    GridChanged,
+    ApplySuggestion(u32,puzzle_backend::EntryVariant,String),
+    UseBinnedClue(usize),
+    DiscardBinnedClue(usize),
+    UsePastClue(String),
+
+    SelectTab(usize),
+    CloseTab(usize),
 
     ControlSetState(controls_ui::State),
+    Validate,
+    EndValidation,
+    EditSymmetry,
+    SymmetrySelected(puzzle_backend::Symmetry),
+    Symmetrize,
+    EditMetadata,
+    MetadataModified(puzzle_backend::MetadataField, String),
+    EditRegion,
+    CopyRegion,
+    CutRegion,
+    PasteRegion,
+    ClearRegion,
+    ShiftRegion(i32, i32),
+    RotateRegion,
+    TransformGrid(puzzle_backend::GridTransform),
+    EditClueDatabase,
+    ImportPathModified(String),
+    BrowseImport,
+    ImportClues,
+    CluesImported(Result<(clue_database::ClueDatabase, clue_database::ImportSummary), String>),
 
     NewPuzzle(puzzle_backend::PuzzleType),
+    CustomWidthModified(String),
+    CustomHeightModified(String),
+    AttemptNewCustom,
 
     SavePathModified(String),
     SaveEmptyGrid(bool),
+    ScrambleSolution(bool),
     AttemptSave,
+    BrowseSave,
+    SaveTo(String),
 
     OpenPathModified(String),
     AttemptOpen,
+    BrowseOpen,
+    OpenPath(String),
+    DiscardChanges(Box<Message>),
+    Autosave,
+    RestoreRecovered,
+    DiscardRecovered,
+    ExportPathModified(String),
+    PageSizeSelected(pdf_export::PageSize),
+    PdfFontSelected(pdf_export::PdfFont),
+    ExportAnswerKey(bool),
+    ExportFill(bool),
+    AttemptExport,
+
+    FillPathModified(String),
+    BlacklistPathModified(String),
+    OverridesPathModified(String),
+    AttemptLoadWordList,
+    WordListLoaded(Result<Arc<word_list::WordList>, String>),
+    AttemptFill,
+    CancelFill,
+    TogglePause,
+    Tick,
+    Check(puzzle_backend::SolveScope),
+    Reveal(puzzle_backend::SolveScope),
+
+    FillFinished(Result<(Arc<word_list::WordList>, autofill::FillOutcome), String>),
 }
 
-impl Sandbox for CrosserUI {
-    type Message = Message; 
+impl Application for CrosserUI {
+    type Executor = executor::Default;
+    type Message = Message;
+    type Flags = Vec<String>;
 
-    fn new() -> Self {
+    // The flags are paths to open.
+    fn new(flags: Vec<String>) -> (Self, Command<Message>) {
         let t = puzzle_backend::PuzzleType::Mini;
-        let p = Rc::new(RefCell::new(puzzle_backend::Puzzle::new(t)));
-        let puzzle_ui = puzzle_canvas::PuzzleCanvas::new(p.clone());
-        let clues = clue_ui::CluesBrowser::new(p.clone());
-        let controls = controls_ui::ControlsRow::new();
-        CrosserUI { 
-                puzzle: p,
-                puzzle_ui,
-                clues,
-                controls,
+        let mut ui = CrosserUI {
+                tabs: vec![tab::Tab::new(0, puzzle_backend::Puzzle::new(t), None)],
+                active: 0,
+                next_tab_id: 1,
+                controls: controls_ui::ControlsRow::new(),
+                word_list: None,
+                fill_cancel: None,
+                fill_tab: None,
+                fill_grid: Vec::new(),
+                recent_files: recent_files::RecentFiles::load(),
+                discard_confirmed: false,
+                recovered: recovery::load_all(),
+                clipboard: None,
+                clue_db: clue_database::ClueDatabase::new(),
+                clue_db_error: None,
+                };
+        match clue_store::load() {
+            Ok(db) => { ui.clue_db = db; },
+            Err(e) => {
+                ui.controls.set_state(controls_ui::State::OperationResult(format!("The clue database couldn't be read, so it won't be saved this session: {}", e)));
+                ui.clue_db_error = Some(e);
+            },
+        }
+        for path in flags {
+            match puzzle_file_io::get_puzzle_from_file(path.clone()) {
+                Ok(p) => {
+                    ui.recent_files.add(&path);
+                    ui.open_tab(p, Some(path));
+                }
+                Err(e) => {
+                    ui.controls.set_state(controls_ui::State::OperationResult(format!("Could not open {}: {}", path, e)));
                 }
+            }
+        }
+        if !ui.recovered.is_empty() {
+            ui.controls.set_state(controls_ui::State::Recover(ui.recovered.len()));
+        }
+        (ui, Command::none())
     }
 
     fn title(&self) -> String {
-        String::from("Crosser -- The Friendly Crossword Puzzle Templating App")
+        let marker = if self.tab().is_dirty() { "*" } else { "" };
+        format!("{}{} -- Crosser -- The Friendly Crossword Puzzle Templating App", marker, self.tab().name())
     }
 
-    fn view(&mut self) -> Element<Message> {
-        Column::new() 
+    fn view(&mut self) -> Element<'_, Message> {
+        let words = self.word_list.as_ref().map(|(_s,w)| w.as_ref());
+        let active = self.active;
+        let mut tab_bar = Row::new().spacing(10);
+        let mut body = None;
+        for (i, t) in self.tabs.iter_mut().enumerate() {
+            let (header, tab_body) = t.view(i == active, words, &self.clue_db);
+            tab_bar = tab_bar.push(header);
+            if tab_body.is_some() {
+                body = tab_body;
+            }
+        }
+
+        Column::new()
         .padding(10)
+        .spacing(10)
         .push(
-            self.controls.view()
+            tab_bar
         )
         .push(
-            Row::new()
-            .padding(20)
-            .align_items(Align::Center)
-            .push(
-                Canvas::new(&mut self.puzzle_ui)
-                .height(Length::Fill)
-                .width(Length::FillPortion(2))
-            )
-            .push(
-                self.clues.view()
-            )
+            self.controls.view(self.recent_files.paths())
+        )
+        .push(
+            body.unwrap_or_else(|| Text::new("").into())
         )
         .into()
     }
 
-    fn update(&mut self, message: Message) {
+    fn subscription(&self) -> Subscription<Message> {
+        let autosave = iced::time::every(std::time::Duration::from_secs(AUTOSAVE_SECONDS)).map(|_| Message::Autosave);
+        // Tick once a second so the solve clock redraws.
+        if self.tab().puzzle.borrow().fill_only && self.tab().solver.clock.is_running() {
+            Subscription::batch(vec![autosave, iced::time::every(std::time::Duration::from_secs(1)).map(|_| Message::Tick)])
+        } else {
+            autosave
+        }
+    }
+
+    fn update(&mut self, message: Message) -> Command<Message> {
+        // Closing a tab throws away its puzzle.
+        let discards = match &message {
+            Message::CloseTab(id) => self.tabs.iter().any(|t| t.id == *id && t.is_dirty()),
+            _ => false,
+        };
+        if discards && !self.discard_confirmed {
+            // Show the tab being closed, so that saving from the prompt saves that one.
+            if let Message::CloseTab(id) = &message {
+                if let Some(index) = self.tabs.iter().position(|t| t.id == *id) {
+                    self.switch_to_tab(index);
+                }
+            }
+            self.controls.set_state(controls_ui::State::ConfirmDiscard(self.tab().name(), Box::new(message)));
+            return Command::none();
+        }
+        let tab = &mut self.tabs[self.active];
         match message {
             Message::ClueEnteredModification(l,v) => {
-                if !self.puzzle.borrow().fill_only {
-                    if self.clues.being_modified.is_none() {
-                        self.puzzle_ui.set_ignore_keystrokes(true);
-                        self.clues.set_being_modified(l, v);
-                    }
+                if !tab.puzzle.borrow().fill_only && tab.clues.being_modified.is_none() {
+                    tab.puzzle_ui.set_ignore_keystrokes(true);
+                    tab.clues.set_being_modified(l, v);
                 }
             }
             Message::ClueLeftModification(_l,_v) => {
-                if !self.puzzle.borrow().fill_only {
-                    self.puzzle_ui.set_ignore_keystrokes(false);
-                    self.clues.unset_being_modified();
+                if !tab.puzzle.borrow().fill_only {
+                    tab.puzzle_ui.set_ignore_keystrokes(false);
+                    tab.clues.unset_being_modified();
                 }
             }
             Message::ClueModified(s) => {
                 // Cache text to prevent mut issues
-                self.clues.set_clue_text(s);
+                tab.clues.set_clue_text(s);
             }
             Message::CluesUpdated => {
-                self.clues.update_clues();
+                tab.clues.update_clues();
+                self.revalidate();
+            }
+            Message::GridChanged => {
+                self.revalidate();
+                self.check_completion();
+            }
+            Message::ApplySuggestion(l,v,word) => {
+                tab.puzzle.borrow_mut().fill_entry(l,v,&word);
+                tab.puzzle_ui.invalidate();
+                self.revalidate();
+            }
+            Message::UseBinnedClue(i) => {
+                match tab.puzzle_ui.selected_entry() {
+                    Some((l,v)) if !tab.puzzle.borrow().fill_only => {
+                        tab.puzzle.borrow_mut().use_binned_clue(i, l, v);
+                        tab.clues.update_clues();
+                        tab.puzzle_ui.invalidate();
+                    }
+                    Some(_e) => {
+                        self.controls.set_state(controls_ui::State::OperationResult("Solvable grids cannot be changed.".to_string()));
+                    }
+                    None => {
+                        self.controls.set_state(controls_ui::State::OperationResult("Select the entry to give the clue to first.".to_string()));
+                    }
+                }
+            }
+            Message::DiscardBinnedClue(i) => {
+                tab.puzzle.borrow_mut().discard_binned_clue(i);
+                tab.clues.update_clues();
+            }
+            Message::UsePastClue(text) => {
+                if let Some((l,v)) = tab.puzzle_ui.selected_entry() {
+                    // Finish any clue being typed first so it doesn't overwrite this one when left.
+                    if tab.clues.being_modified.is_some() {
+                        tab.puzzle_ui.set_ignore_keystrokes(false);
+                        tab.clues.unset_being_modified();
+                    }
+                    tab.puzzle.borrow_mut().set_clue_text(l, v, text);
+                    tab.clues.update_clues();
+                    self.revalidate();
+                }
+            }
+            Message::SelectTab(id) => {
+                if let Some(index) = self.tabs.iter().position(|t| t.id == id) {
+                    self.switch_to_tab(index);
+                }
+            }
+            Message::CloseTab(id) => {
+                if let Some(index) = self.tabs.iter().position(|t| t.id == id) {
+                    // The fill is stopped, and finds its tab gone when it finishes.
+                    if self.fill_tab == Some(id) {
+                        if let Some(c) = &self.fill_cancel {
+                            c.store(true, Ordering::Relaxed);
+                        }
+                    }
+                    recovery::clear(id);
+                    self.tabs.remove(index);
+                    if self.tabs.is_empty() {
+                        self.tabs.push(tab::Tab::new(self.next_tab_id, puzzle_backend::Puzzle::new(puzzle_backend::PuzzleType::Mini), None));
+                        self.next_tab_id += 1;
+                    }
+                    let active = if self.active > index { self.active - 1 } else { self.active };
+                    self.active = active.min(self.tabs.len() - 1);
+                    self.show_active_tab();
+                }
             }
             Message::ControlSetState(s) => {
-                self.puzzle_ui.set_no_selected_square();
+                tab.puzzle_ui.set_no_selected_square();
+                let s = match s {
+                    controls_ui::State::Main => self.main_state(),
+                    s => s,
+                };
                 self.controls.set_state(s);
             }
+            Message::Validate => {
+                tab.validating = true;
+                self.revalidate();
+            }
+            Message::EditSymmetry => {
+                let s = tab.puzzle.borrow().symmetry;
+                tab.puzzle_ui.set_no_selected_square();
+                self.controls.set_state(controls_ui::State::Symmetry(s));
+            }
+            Message::EditMetadata => {
+                self.controls.load_metadata(&tab.puzzle.borrow());
+                tab.puzzle_ui.set_no_selected_square();
+                self.controls.set_state(controls_ui::State::Metadata);
+            }
+            Message::MetadataModified(field, s) => {
+                self.controls.set_metadata_string(field, s.clone());
+                tab.puzzle.borrow_mut().set_metadata(field, s);
+            }
+            Message::EditRegion => {
+                self.show_region_status("Shift-click, shift-drag or shift-arrow to select squares.".to_string());
+            }
+            Message::CopyRegion => {
+                if let Some((w, h, note)) = self.copy_selection() {
+                    self.show_region_status(format!("Copied {}x{}{}.", w, h, note));
+                }
+            }
+            Message::CutRegion => {
+                if let Some((w, h, note)) = self.copy_selection() {
+                    self.edit_selection(|p, x, y, _w, _h| {
+                        p.clear_region(x, y, w, h);
+                        Ok(format!("Cut {}x{}{}.", w, h, note))
+                    });
+                }
+            }
+            Message::PasteRegion => {
+                match self.clipboard_region() {
+                    Ok(region) => {
+                        let (w, h) = (region.width, region.height);
+                        let pasted = self.edit_selection(|p, x, y, _w, _h| {
+                            p.paste_region(x, y, &region);
+                            Ok(format!("Pasted {}x{}.", w, h))
+                        });
+                        // Select what was pasted so it can be shifted or rotated straight away.
+                        if let Some((x, y, _w, _h)) = pasted {
+                            self.tabs[self.active].puzzle_ui.select_region(x as u32, y as u32, w as u32, h as u32);
+                        }
+                    }
+                    Err(e) => {
+                        self.show_region_status(e);
+                    }
+                }
+            }
+            Message::ClearRegion => {
+                self.edit_selection(|p, x, y, w, h| {
+                    p.clear_region(x, y, w, h);
+                    Ok(format!("Cleared {}x{}.", w, h))
+                });
+            }
+            Message::ShiftRegion(dx, dy) => {
+                self.edit_selection(|p, x, y, w, h| {
+                    let region = p.copy_region(x, y, w, h).shifted(dx, dy);
+                    p.paste_region(x, y, &region);
+                    Ok(format!("Shifted {}x{}.", w, h))
+                });
+            }
+            Message::RotateRegion => {
+                self.edit_selection(|p, x, y, w, h| {
+                    if w != h {
+                        return Err("Only square selections can be rotated.".to_string());
+                    }
+                    let region = p.copy_region(x, y, w, h).rotated();
+                    p.paste_region(x, y, &region);
+                    Ok(format!("Rotated {}x{}.", w, h))
+                });
+            }
+            Message::TransformGrid(t) => {
+                if !tab.puzzle.borrow_mut().transform(t) {
+                    self.controls.set_state(controls_ui::State::OperationResult("Solvable grids cannot be changed.".to_string()));
+                    return Command::none();
+                }
+                tab.puzzle_ui.set_no_selected_square();
+                tab.puzzle_ui.invalidate();
+                tab.clues.update_clues();
+                self.revalidate();
+            }
+            Message::EditClueDatabase => {
+                self.controls.clue_db_status = match &self.clue_db_error {
+                    Some(e) => format!("The clue database couldn't be read: {}", e),
+                    None => format!("{} clue(s) on file.", self.clue_db.len()),
+                };
+                self.controls.set_state(controls_ui::State::ClueDatabase);
+            }
+            Message::ImportPathModified(s) => {
+                self.controls.import_path_string = s;
+            }
+            Message::BrowseImport => {
+                match file_dialogs::pick_directory(&self.controls.import_path_string) {
+                    Ok(Some(path)) => {
+                        self.controls.import_path_string = path;
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        self.controls.clue_db_status = format!("No file dialog available ({}). Type a path instead.", e);
+                    }
+                }
+            }
+            Message::ImportClues => {
+                let dir = std::path::PathBuf::from(&self.controls.import_path_string);
+                self.controls.clue_db_status = "Importing clues ...".to_string();
+                // Reading a whole directory of puzzles can take a while, so it happens off the UI thread.
+                return Command::perform(async move {
+                    let mut imported = clue_database::ClueDatabase::new();
+                    let summary = imported.import_directory(&dir)?;
+                    Ok((imported, summary))
+                }, Message::CluesImported);
+            }
+            Message::CluesImported(res) => {
+                self.controls.clue_db_status = match res {
+                    Ok((imported, summary)) => {
+                        let added = self.clue_db.merge(&imported);
+                        let mut s = format!("Added {} new clue(s) from {} file(s).", added, summary.files);
+                        if let Some((file, e)) = summary.failed.first() {
+                            s.push_str(&format!(" {} file(s) couldn't be read, e.g. {}: {}", summary.failed.len(), file, e));
+                        }
+                        match &self.clue_db_error {
+                            Some(e) => s.push_str(&format!(" They weren't saved, since the clue database couldn't be read: {}", e)),
+                            None => {
+                                if let Err(e) = clue_store::save(&self.clue_db) {
+                                    s.push_str(&format!(" The clue database couldn't be saved: {}", e));
+                                }
+                            },
+                        }
+                        s
+                    }
+                    Err(e) => "Import unsuccessful: ".to_string() + &e,
+                };
+            }
+            Message::SymmetrySelected(s) => {
+                tab.puzzle.borrow_mut().symmetry = s;
+                tab.puzzle_ui.invalidate();
+                self.controls.set_state(controls_ui::State::Symmetry(s));
+            }
+            Message::Symmetrize => {
+                if tab.puzzle.borrow().fill_only {
+                    self.controls.set_state(controls_ui::State::OperationResult("Solvable grids cannot be changed.".to_string()));
+                    return Command::none();
+                }
+                let res = tab.puzzle.borrow_mut().symmetrize();
+                match res {
+                    Ok(0) => {
+                        self.controls.set_state(controls_ui::State::OperationResult("Grid is already symmetric.".to_string()));
+                    }
+                    Ok(n) => {
+                        tab.puzzle_ui.invalidate();
+                        tab.clues.update_clues();
+                        self.controls.set_state(controls_ui::State::OperationResult(format!("Added {} blocker(s).", n)));
+                    }
+                    Err(conflicts) => {
+                        // Reuse the validation overlay to show the squares in the way.
+                        let mut flagged = vec![false; tab.puzzle.borrow().squares.len()];
+                        for i in conflicts.iter() {
+                            flagged[*i] = true;
+                        }
+                        tab.puzzle_ui.set_flagged_squares(flagged);
+                        let msg = format!("{} square(s) with letters would become blockers; clear them first.", conflicts.len());
+                        self.controls.set_state(controls_ui::State::Validation(msg));
+                    }
+                }
+            }
+            Message::EndValidation => {
+                tab.validating = false;
+                tab.puzzle_ui.set_flagged_squares(Vec::new());
+                self.controls.set_state(self.main_state());
+            }
             Message::NewPuzzle(v) => {
-                self.puzzle = Rc::new(RefCell::new(puzzle_backend::Puzzle::new(v)));
-                self.puzzle_ui = puzzle_canvas::PuzzleCanvas::new(self.puzzle.clone());
-                self.clues = clue_ui::CluesBrowser::new(self.puzzle.clone());
-                self.controls = controls_ui::ControlsRow::new();
+                self.open_tab(puzzle_backend::Puzzle::new(v), None);
+            }
+            Message::CustomWidthModified(s) => {
+                self.controls.custom_width_string = s;
+            }
+            Message::CustomHeightModified(s) => {
+                self.controls.custom_height_string = s;
+            }
+            Message::AttemptNewCustom => {
+                let w = self.controls.custom_width_string.trim().parse::<usize>();
+                let h = self.controls.custom_height_string.trim().parse::<usize>();
+                match (w, h) {
+                    (Ok(w), Ok(h)) if puzzle_backend::is_valid_custom_dim(w, h) => {
+                        return self.update(Message::NewPuzzle(puzzle_backend::PuzzleType::Custom(w, h)));
+                    }
+                    _ => {
+                        let err = format!("Width and height must be whole numbers from 1 to {}.", puzzle_backend::MAX_CUSTOM_DIM);
+                        self.controls.set_state(controls_ui::State::OperationResult(err));
+                    }
+                }
             }
             Message::SavePathModified(s) => {
                 self.controls.save_path_string = s;
@@ -116,12 +529,50 @@ impl Sandbox for CrosserUI {
             Message::SaveEmptyGrid(b) => {
                 self.controls.save_empty_grid = b;
             }
+            Message::ScrambleSolution(b) => {
+                self.controls.scramble_solution = b;
+            }
             Message::AttemptSave => {
-                let res = puzzle_file_io::write_puzzle_to_cro(self.puzzle.clone(),self.controls.save_path_string.clone(),self.controls.save_empty_grid);
+                let path = puzzle_file_io::with_default_extension(&self.controls.save_path_string);
+                self.controls.save_path_string = path.clone();
+                if std::path::Path::new(&path).is_file() && tab.file_path.as_ref() != Some(&path) {
+                    self.controls.set_state(controls_ui::State::ConfirmOverwrite(path));
+                } else {
+                    return self.update(Message::SaveTo(path));
+                }
+            }
+            Message::BrowseSave => {
+                match file_dialogs::pick_save_path(&self.controls.save_path_string) {
+                    Ok(Some(path)) => {
+                        self.controls.save_path_string = path.clone();
+                        // The dialog already asked about replacing the path it returned, but not one with .cro added.
+                        if puzzle_file_io::file_extension(&path).is_empty() {
+                            return self.update(Message::AttemptSave);
+                        }
+                        return self.update(Message::SaveTo(path));
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        self.controls.set_state(controls_ui::State::OperationResult(format!("No file dialog available ({}). Type a path instead.", e)));
+                    }
+                }
+            }
+            Message::SaveTo(path) => {
+                let res = puzzle_file_io::write_puzzle_to_file(&tab.puzzle.borrow(),path.clone(),self.controls.save_empty_grid,self.controls.scramble_solution);
                 match res {
                     Ok(()) => {
-                        self.controls.set_state(controls_ui::State::OperationResult("File wrote successfully!".to_string()));
-                    } 
+                        self.recent_files.add(&path);
+                        let mut result_str = "File wrote successfully!".to_string();
+                        if let Some(e) = &self.clue_db_error {
+                            result_str = format!("File wrote successfully, but the clue database couldn't be read, so its clues weren't added: {}", e);
+                        } else if self.clue_db.add_puzzle(&tab.puzzle.borrow(), &path) > 0 {
+                            if let Err(e) = clue_store::save(&self.clue_db) {
+                                result_str = format!("File wrote successfully, but its clues couldn't be added to the clue database: {}", e);
+                            }
+                        }
+                        tab.mark_saved(path);
+                        self.controls.set_state(controls_ui::State::OperationResult(result_str));
+                    }
                     Err(e) => {
                         self.controls.set_state(controls_ui::State::OperationResult("Write unsuccessful: ".to_string() + &e.to_string()));
                     }
@@ -131,20 +582,412 @@ impl Sandbox for CrosserUI {
                 self.controls.open_path_string = s;
             }
             Message::AttemptOpen => {
-                let res = puzzle_file_io::get_puzzle_from_cro(self.controls.open_path_string.clone());
+                let path = self.controls.open_path_string.clone();
+                // A file that's already open is shown rather than opened twice.
+                if let Some(index) = self.tabs.iter().position(|t| t.file_path.as_ref() == Some(&path)) {
+                    self.switch_to_tab(index);
+                    return Command::none();
+                }
+                let res = puzzle_file_io::get_puzzle_from_file(path.clone());
                 match res {
                     Ok(p) => {
-                        self.puzzle = Rc::new(RefCell::new(p));
-                        self.puzzle_ui = puzzle_canvas::PuzzleCanvas::new(self.puzzle.clone());
-                        self.clues = clue_ui::CluesBrowser::new(self.puzzle.clone());
-                        self.controls = controls_ui::ControlsRow::new();
+                        self.recent_files.add(&path);
+                        self.open_tab(p, Some(path));
                     }
                     Err(s) => {
                         self.controls.set_state(controls_ui::State::OperationResult("Open unsuccessful: ".to_string() + &s));
                     }
                 }
             }
+            Message::BrowseOpen => {
+                match file_dialogs::pick_open_path(&self.controls.open_path_string) {
+                    Ok(Some(path)) => {
+                        return self.update(Message::OpenPath(path));
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        self.controls.set_state(controls_ui::State::OperationResult(format!("No file dialog available ({}). Type a path instead.", e)));
+                    }
+                }
+            }
+            Message::OpenPath(path) => {
+                self.controls.open_path_string = path;
+                return self.update(Message::AttemptOpen);
+            }
+            Message::DiscardChanges(m) => {
+                self.discard_confirmed = true;
+                let command = self.update(*m);
+                self.discard_confirmed = false;
+                return command;
+            }
+            Message::Autosave => {
+                // This session's files are kept apart from the ones waiting to be recovered.
+                for t in self.tabs.iter_mut() {
+                    t.autosave();
+                }
+            }
+            Message::RestoreRecovered => {
+                // The restored tabs get new ids, so their old recovery files are replaced at the next autosave.
+                // Work from a file goes back to that file when saved.
+                recovery::clear_recovered();
+                for (p, path) in std::mem::take(&mut self.recovered) {
+                    self.open_tab(p, path);
+                    self.tabs[self.active].saved = None;
+                }
+            }
+            Message::DiscardRecovered => {
+                self.recovered.clear();
+                recovery::clear_recovered();
+                self.controls.set_state(controls_ui::State::Main);
+            }
+            Message::ExportPathModified(s) => {
+                self.controls.export_path_string = s;
+            }
+            Message::PageSizeSelected(s) => {
+                self.controls.pdf_options.page_size = s;
+            }
+            Message::PdfFontSelected(f) => {
+                self.controls.pdf_options.font = f;
+            }
+            Message::ExportAnswerKey(b) => {
+                self.controls.pdf_options.answer_key = b;
+            }
+            Message::ExportFill(b) => {
+                self.controls.export_fill = b;
+            }
+            Message::AttemptExport => {
+                let path = self.controls.export_path_string.clone();
+                let res = puzzle_file_io::export_puzzle_to_file(&tab.puzzle.borrow(), path, &self.controls.pdf_options, self.controls.export_fill);
+                match res {
+                    Ok(()) => {
+                        self.controls.set_state(controls_ui::State::OperationResult("File exported successfully!".to_string()));
+                    }
+                    Err(e) => {
+                        self.controls.set_state(controls_ui::State::OperationResult("Export unsuccessful: ".to_string() + &e.to_string()));
+                    }
+                }
+            }
+            Message::FillPathModified(s) => {
+                self.controls.fill_path_string = s;
+            }
+            Message::BlacklistPathModified(s) => {
+                self.controls.blacklist_path_string = s;
+            }
+            Message::OverridesPathModified(s) => {
+                self.controls.overrides_path_string = s;
+            }
+            Message::AttemptLoadWordList => {
+                let sources = self.word_list_sources();
+                self.controls.set_state(controls_ui::State::OperationResult("Loading word lists ...".to_string()));
+                return Command::perform(async move {
+                    sources.load().map(Arc::new)
+                }, Message::WordListLoaded);
+            }
+            Message::WordListLoaded(res) => {
+                let result_str = match res {
+                    Ok(words) => {
+                        let s = format!("Loaded {} words.", words.len());
+                        self.word_list = Some((self.word_list_sources(), words));
+                        for t in self.tabs.iter_mut() {
+                            t.suggestions.invalidate();
+                        }
+                        s
+                    }
+                    Err(e) => "Load unsuccessful: ".to_string() + &e,
+                };
+                self.controls.set_state(controls_ui::State::OperationResult(result_str));
+            }
+            Message::AttemptFill => {
+                if tab.puzzle.borrow().fill_only {
+                    self.controls.set_state(controls_ui::State::OperationResult("Solvable grids cannot be filled.".to_string()));
+                    return Command::none();
+                }
+                // The search runs on the executor's thread pool against a snapshot of the grid.
+                let problem = autofill::FillProblem::new(&tab.puzzle.borrow());
+                let cancel = Arc::new(AtomicBool::new(false));
+                self.fill_cancel = Some(cancel.clone());
+                self.fill_tab = Some(tab.id);
+                self.fill_grid = grid_letters(&tab.puzzle.borrow());
+                tab.puzzle_ui.set_ignore_keystrokes(true);
+                // Reuse the loaded list unless the paths changed since.
+                let sources = self.word_list_sources();
+                let loaded = match &self.word_list {
+                    Some((s,w)) if *s == sources => Some(w.clone()),
+                    _ => None,
+                };
+                self.controls.set_state(controls_ui::State::Filling);
+                return Command::perform(async move {
+                    let words = match loaded {
+                        Some(w) => w,
+                        None => Arc::new(sources.load()?),
+                    };
+                    let outcome = problem.solve(&words, &cancel);
+                    Ok((words, outcome))
+                }, Message::FillFinished);
+            }
+            Message::CancelFill => {
+                if let Some(c) = &self.fill_cancel {
+                    c.store(true, Ordering::Relaxed);
+                }
+            }
+            Message::TogglePause => {
+                if tab.solver.clock.is_running() {
+                    tab.solver.clock.pause();
+                    tab.puzzle_ui.set_ignore_keystrokes(true);
+                } else {
+                    tab.solver.clock.resume();
+                    tab.puzzle_ui.set_ignore_keystrokes(false);
+                }
+            }
+            Message::Tick => {}
+            Message::Check(scope) => {
+                if let Some(indices) = self.solve_scope(scope, false) {
+                    let tab = &mut self.tabs[self.active];
+                    let wrong = tab.puzzle.borrow_mut().check(&indices);
+                    tab.solver.checks_used += 1;
+                    tab.solver.status = match wrong {
+                        0 => "No mistakes found.".to_string(),
+                        n => format!("{} wrong square(s).", n),
+                    };
+                    tab.puzzle_ui.invalidate();
+                }
+            }
+            Message::Reveal(scope) => {
+                if let Some(indices) = self.solve_scope(scope, true) {
+                    let tab = &mut self.tabs[self.active];
+                    tab.puzzle.borrow_mut().reveal(&indices);
+                    tab.solver.status = String::new();
+                    tab.puzzle_ui.invalidate();
+                    self.check_completion();
+                }
+            }
+            Message::FillFinished(res) => {
+                self.fill_cancel = None;
+                if let Ok((words,_o)) = &res {
+                    self.word_list = Some((self.word_list_sources(), words.clone()));
+                }
+                let fill_tab = self.fill_tab.take();
+                let filled_tab = self.tabs.iter_mut().find(|t| Some(t.id) == fill_tab);
+                let result_str = match (res.map(|(_w,o)| o), filled_tab) {
+                    (Ok(autofill::FillOutcome::Filled(_fill)), Some(t)) if grid_letters(&t.puzzle.borrow()) != self.fill_grid => {
+                        t.puzzle_ui.set_ignore_keystrokes(false);
+                        "Fill thrown away, since the grid changed while it ran.".to_string()
+                    }
+                    (Ok(autofill::FillOutcome::Filled(fill)), Some(t)) => {
+                        t.puzzle_ui.set_ignore_keystrokes(false);
+                        t.puzzle.borrow_mut().apply_fill(&fill);
+                        t.puzzle_ui.invalidate();
+                        format!("{} filled!", t.name())
+                    }
+                    (Ok(autofill::FillOutcome::Filled(_fill)), None) => "Fill finished after its tab was closed.".to_string(),
+                    (outcome, t) => {
+                        if let Some(t) = t {
+                            t.puzzle_ui.set_ignore_keystrokes(false);
+                        }
+                        match outcome {
+                            Ok(autofill::FillOutcome::NoSolution) => "No fill exists with this word list.".to_string(),
+                            Ok(_o) => "Fill cancelled.".to_string(),
+                            Err(e) => "Fill unsuccessful: ".to_string() + &e,
+                        }
+                    }
+                };
+                for t in self.tabs.iter_mut() {
+                    t.suggestions.invalidate();
+                }
+                self.controls.set_state(controls_ui::State::OperationResult(result_str));
+            }
+        }
+        Command::none()
+    }
+}
+
+impl CrosserUI {
+    fn tab(&self) -> &tab::Tab {
+        &self.tabs[self.active]
+    }
+
+    // Shows a puzzle in a new tab, or in place of the active tab if nothing has been done in it yet.
+    fn open_tab(&mut self, p: puzzle_backend::Puzzle, file_path: Option<String>) {
+        let t = tab::Tab::new(self.next_tab_id, p, file_path);
+        self.next_tab_id += 1;
+        let filling = self.fill_tab == Some(self.tab().id);
+        if self.tab().is_blank() && !filling {
+            recovery::clear(self.tab().id);
+            self.tabs[self.active] = t;
+            self.show_active_tab();
+        } else {
+            self.tabs.push(t);
+            self.switch_to_tab(self.tabs.len() - 1);
         }
     }
+
+    // The selected rectangle of the active grid as (x, y, width, height), or just the selected square.
+    fn selected_area(&self) -> Option<(usize, usize, usize, usize)> {
+        let puzzle_ui = &self.tab().puzzle_ui;
+        let (x, y, w, h) = match (puzzle_ui.selected_region(), puzzle_ui.selected_square()) {
+            (Some(r), _) => r,
+            (None, Some((x, y, _v))) => (x, y, 1, 1),
+            (None, None) => { return None; },
+        };
+        Some((x as usize, y as usize, w as usize, h as usize))
+    }
+
+    fn show_region_status(&mut self, status: String) {
+        self.controls.region_status = status;
+        self.controls.set_state(controls_ui::State::Region);
+    }
+
+    // Copies the selection to the system clipboard as text, and keeps it here too so a
+    // paste can bring back its modifiers. Gives the size copied, and a note if only the
+    // copy kept here worked.
+    fn copy_selection(&mut self) -> Option<(usize, usize, String)> {
+        let (x, y, w, h) = match self.selected_area() {
+            Some(a) => a,
+            None => {
+                self.show_region_status("Select some squares first.".to_string());
+                return None;
+            }
+        };
+        let region = self.tab().puzzle.borrow().copy_region(x, y, w, h);
+        let note = match system_clipboard::copy_text(&region.to_text()) {
+            Ok(()) => String::new(),
+            Err(e) => format!(", but only within Crosser ({})", e),
+        };
+        self.clipboard = Some(region);
+        Some((w, h, note))
+    }
+
+    // What to paste. Text matching the last copy made here is taken to be that copy,
+    // modifiers and all.
+    fn clipboard_region(&self) -> std::result::Result<grid_region::GridRegion, String> {
+        match (system_clipboard::paste_text(), &self.clipboard) {
+            (Ok(text), Some(region)) if text.trim_end() == region.to_text() => Ok(region.clone()),
+            (Ok(text), _) => grid_region::GridRegion::from_text(&text),
+            (Err(_e), Some(region)) => Ok(region.clone()),
+            (Err(e), None) => Err(format!("Nothing to paste: {}.", e)),
+        }
+    }
+
+    // Runs an edit over the selection in the active grid and shows how it went. Gives the
+    // area edited if the edit went ahead.
+    fn edit_selection<F>(&mut self, edit: F) -> Option<(usize, usize, usize, usize)>
+    where F: FnOnce(&mut puzzle_backend::Puzzle, usize, usize, usize, usize) -> std::result::Result<String, String> {
+        let area = self.selected_area();
+        let tab = &mut self.tabs[self.active];
+        let fill_only = tab.puzzle.borrow().fill_only;
+        let res = match area {
+            _ if fill_only => Err("Solvable grids cannot be changed.".to_string()),
+            None => Err("Select some squares first.".to_string()),
+            Some((x, y, w, h)) => edit(&mut tab.puzzle.borrow_mut(), x, y, w, h),
+        };
+        match res {
+            Ok(status) => {
+                tab.puzzle_ui.invalidate();
+                tab.clues.update_clues();
+                self.show_region_status(status);
+                self.revalidate();
+                area
+            }
+            Err(status) => {
+                self.show_region_status(status);
+                None
+            }
+        }
+    }
+
+    fn switch_to_tab(&mut self, index: usize) {
+        // A solve shouldn't be timed while its grid is out of sight. It stays
+        // paused until resumed, like any other pause.
+        let leaving = &mut self.tabs[self.active];
+        if leaving.puzzle.borrow().fill_only && leaving.solver.clock.is_running() {
+            leaving.solver.clock.pause();
+            leaving.puzzle_ui.set_ignore_keystrokes(true);
+        }
+        self.active = index;
+        // Grids being edited only stop taking keys for a clue being typed or a
+        // running fill, so make sure one comes back as editable as it was left.
+        let filling = self.fill_tab == Some(self.tabs[index].id);
+        let arriving = &mut self.tabs[index];
+        if !arriving.puzzle.borrow().fill_only && !filling && arriving.clues.being_modified.is_none() {
+            arriving.puzzle_ui.set_ignore_keystrokes(false);
+        }
+        self.show_active_tab();
+    }
+
+    // Points the controls at the active tab. A running fill keeps its controls so it can still be cancelled.
+    fn show_active_tab(&mut self) {
+        self.controls.save_path_string = match &self.tab().file_path {
+            Some(path) => path.clone(),
+            None => std::env::current_dir().map(|d| d.to_string_lossy().to_string()).unwrap_or_default(),
+        };
+        if self.fill_cancel.is_none() {
+            self.controls.set_state(self.main_state());
+        }
+    }
+
+    // The controls to go back to when nothing else is showing. An unanswered
+    // recovery prompt comes back until it's answered.
+    fn main_state(&self) -> controls_ui::State {
+        match self.recovered.len() {
+            0 => controls_ui::State::Main,
+            n => controls_ui::State::Recover(n),
+        }
+    }
+
+    // The squares a check or reveal applies to, or None (with a status message) if it can't be done.
+    // Checks also work from entry checksums, reveals need the stored solution.
+    fn solve_scope(&mut self, scope: puzzle_backend::SolveScope, reveal: bool) -> Option<Vec<usize>> {
+        let tab = &mut self.tabs[self.active];
+        let possible = {
+            let puzzle = tab.puzzle.borrow();
+            if reveal { puzzle.solution.is_some() } else { puzzle.can_check() }
+        };
+        if !possible {
+            tab.solver.status = "This file has no stored solution.".to_string();
+            return None;
+        }
+        let scope = tab.puzzle.borrow().solve_scope(scope, tab.puzzle_ui.selected_square());
+        if scope.is_none() {
+            tab.solver.status = "Select a square first.".to_string();
+        }
+        scope
+    }
+
+    fn check_completion(&mut self) {
+        let tab = &mut self.tabs[self.active];
+        let solved = {
+            let p = tab.puzzle.borrow();
+            p.fill_only && p.is_solved()
+        };
+        if solved && tab.solver.completed.is_none() {
+            tab.solver.clock.pause();
+            tab.solver.completed = Some(tab.solver.clock.elapsed());
+            tab.puzzle_ui.set_ignore_keystrokes(true);
+        }
+    }
+
+    fn revalidate(&mut self) {
+        let tab = &mut self.tabs[self.active];
+        if tab.validating {
+            let report = tab.puzzle.borrow().validate();
+            tab.puzzle_ui.set_flagged_squares(report.flagged_squares(tab.puzzle.borrow().squares.len()));
+            self.controls.set_state(controls_ui::State::Validation(report.summary()));
+        }
+    }
+
+    fn word_list_sources(&self) -> word_list::WordListSources {
+        word_list::WordListSources::from_paths(
+            &self.controls.fill_path_string,
+            &self.controls.blacklist_path_string,
+            &self.controls.overrides_path_string,
+        )
+    }
 }
 
+// Each square's letters, or None for a blocker.
+fn grid_letters(puzzle: &puzzle_backend::Puzzle) -> Vec<Option<String>> {
+    puzzle.squares.iter().map(|sq| match &sq.content {
+        puzzle_backend::SquareContents::TextContent(s,_m) => Some(s.clone()),
+        puzzle_backend::SquareContents::Blocker => None,
+    }).collect()
+}
\ No newline at end of file
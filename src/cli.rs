@@ -0,0 +1,278 @@
+use crosser_core::autofill;
+use crosser_core::pdf_export;
+use crosser_core::puzzle_backend;
+use crosser_core::puzzle_file_io;
+use crosser_core::word_list;
+use crate::clue_store;
+
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+
+const USAGE: &str = "Usage: crosser [COMMAND]
+
+With no command, opens the editor.
+
+Commands:
+  new TYPE OUTPUT            Create an empty grid. TYPE is mini, weekday,
+                             weekday-asymmetric, sunday or custom
+                             (with --width N --height N).
+  info FILE                  Print the size, word count and blocker count.
+  validate FILE              Check the grid against publication rules.
+  convert INPUT OUTPUT       Convert between .cro, .puz and .ipuz.
+                             --solvable saves a .cro as a solvable grid,
+                             --scramble also scrambles its solution.
+  export INPUT OUTPUT        Export to .pdf, .svg or .txt.
+                             --page-size letter|a4, --font helvetica|times|courier,
+                             --no-answer-key, and --fill to show the fill
+                             in SVG and text.
+  fill INPUT OUTPUT          Fill the grid from word lists.
+                             --words LIST[,LIST...] (required),
+                             --blacklist FILE, --overrides FILE.
+  import-clues DIR           Add the clues from every .cro file under DIR
+                             to the clue database.
+  clues ANSWER               Print the clues written for ANSWER before.
+  help                       Print this message.";
+
+const COMMANDS: [&str; 11] = ["new", "info", "validate", "convert", "export", "fill", "import-clues", "clues", "help", "--help", "-h"];
+
+// Options that take a value. Every other option is a flag.
+const VALUE_OPTIONS: [&str; 7] = ["width", "height", "page-size", "font", "words", "blacklist", "overrides"];
+
+enum CliError {
+    // The command line itself was wrong.
+    Usage(String),
+    Failed(String),
+}
+
+struct Args {
+    positional: Vec<String>,
+    options: HashMap<String, String>,
+    flags: Vec<String>,
+}
+
+impl Args {
+    fn parse(args: &[String]) -> std::result::Result<Self, CliError> {
+        let mut parsed = Args { positional: Vec::new(), options: HashMap::new(), flags: Vec::new() };
+        let mut iter = args.iter();
+        while let Some(a) = iter.next() {
+            let name = match a.strip_prefix("--") {
+                Some(n) => n,
+                None => {
+                    parsed.positional.push(a.clone());
+                    continue;
+                }
+            };
+            if let Some((n, v)) = name.split_once('=') {
+                parsed.options.insert(n.to_string(), v.to_string());
+            } else if VALUE_OPTIONS.contains(&name) {
+                match iter.next() {
+                    Some(v) => { parsed.options.insert(name.to_string(), v.clone()); },
+                    None => { return Err(CliError::Usage(format!("--{} needs a value.", name))); },
+                }
+            } else {
+                parsed.flags.push(name.to_string());
+            }
+        }
+        Ok(parsed)
+    }
+
+    // Checks the number of positional arguments and that every option is one the command knows.
+    fn expect(&self, positional: usize, allowed: &[&str]) -> std::result::Result<(), CliError> {
+        if self.positional.len() != positional {
+            return Err(CliError::Usage(format!("Expected {} argument(s), got {}.", positional, self.positional.len())));
+        }
+        for name in self.options.keys().chain(self.flags.iter()) {
+            if !allowed.contains(&name.as_str()) {
+                return Err(CliError::Usage(format!("Unknown option --{}.", name)));
+            }
+        }
+        Ok(())
+    }
+
+    fn flag(&self, name: &str) -> bool {
+        self.flags.iter().any(|f| f == name)
+    }
+
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(|s| s.as_str())
+    }
+}
+
+// Anything else on the command line is a file for the editor to open.
+pub fn is_command(arg: &str) -> bool {
+    COMMANDS.contains(&arg)
+}
+
+// Runs one command and returns the exit code: 0 on success, 1 if the command
+// failed, and 2 if the command line was wrong.
+pub fn run(args: &[String]) -> i32 {
+    let (command, rest) = match args.split_first() {
+        Some(split) => split,
+        None => {
+            println!("{}", USAGE);
+            return 0;
+        }
+    };
+    let result = Args::parse(rest).and_then(|a| {
+        match command.as_str() {
+            "new" => new(&a),
+            "info" => info(&a),
+            "validate" => validate(&a),
+            "convert" => convert(&a),
+            "export" => export(&a),
+            "fill" => fill(&a),
+            "import-clues" => import_clues(&a),
+            "clues" => clues(&a),
+            "help" | "--help" | "-h" => {
+                println!("{}", USAGE);
+                Ok(())
+            },
+            _ => Err(CliError::Usage(format!("Unknown command {}.", command))),
+        }
+    });
+    match result {
+        Ok(()) => 0,
+        Err(CliError::Usage(e)) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            2
+        },
+        Err(CliError::Failed(e)) => {
+            eprintln!("{}", e);
+            1
+        },
+    }
+}
+
+fn new(args: &Args) -> std::result::Result<(), CliError> {
+    args.expect(2, &["width", "height"])?;
+    if args.positional[0] != "custom" && (args.option("width").is_some() || args.option("height").is_some()) {
+        return Err(CliError::Usage("Only custom grids take --width and --height.".to_string()));
+    }
+    let variant = match args.positional[0].as_str() {
+        "mini" => puzzle_backend::PuzzleType::Mini,
+        "weekday" => puzzle_backend::PuzzleType::Weekday,
+        "weekday-asymmetric" => puzzle_backend::PuzzleType::WeekdayAsymmetric,
+        "sunday" => puzzle_backend::PuzzleType::Sunday,
+        "custom" => {
+            let w = args.option("width").and_then(|s| s.parse::<usize>().ok());
+            let h = args.option("height").and_then(|s| s.parse::<usize>().ok());
+            match (w, h) {
+                (Some(w), Some(h)) if puzzle_backend::is_valid_custom_dim(w, h) => puzzle_backend::PuzzleType::Custom(w, h),
+                _ => {
+                    return Err(CliError::Usage(format!("Custom grids need --width and --height from 1 to {}.", puzzle_backend::MAX_CUSTOM_DIM)));
+                },
+            }
+        },
+        t => { return Err(CliError::Usage(format!("Unknown puzzle type {}.", t))); },
+    };
+    write(&puzzle_backend::Puzzle::new(variant), &args.positional[1], false, false)
+}
+
+fn info(args: &Args) -> std::result::Result<(), CliError> {
+    args.expect(1, &[])?;
+    let puzzle = read(&args.positional[0])?;
+    println!("Title: {}", puzzle.title);
+    println!("Author: {}", puzzle.author);
+    println!("Type: {:?}", puzzle.variant);
+    println!("Size: {}x{}", puzzle.width(), puzzle.height());
+    println!("Words: {}", puzzle.word_count());
+    println!("Blockers: {}", puzzle.blocker_count());
+    println!("Symmetry: {:?}", puzzle.symmetry);
+    println!("Solvable: {}", if puzzle.fill_only { "yes" } else { "no" });
+    Ok(())
+}
+
+fn validate(args: &Args) -> std::result::Result<(), CliError> {
+    args.expect(1, &[])?;
+    let report = read(&args.positional[0])?.validate();
+    if report.is_valid() {
+        println!("{}", report.summary());
+        return Ok(());
+    }
+    for issue in report.issues.iter() {
+        println!("{}", issue.message);
+    }
+    Err(CliError::Failed(format!("{} problem(s) found.", report.issues.len())))
+}
+
+fn convert(args: &Args) -> std::result::Result<(), CliError> {
+    args.expect(2, &["solvable", "scramble"])?;
+    let puzzle = read(&args.positional[0])?;
+    // Solvable grids stay solvable, their letters only live in the solution.
+    let solvable = puzzle.fill_only || args.flag("solvable");
+    write(&puzzle, &args.positional[1], solvable, args.flag("scramble"))
+}
+
+fn export(args: &Args) -> std::result::Result<(), CliError> {
+    args.expect(2, &["page-size", "font", "no-answer-key", "fill"])?;
+    let page_size = match args.option("page-size") {
+        None | Some("letter") => pdf_export::PageSize::Letter,
+        Some("a4") => pdf_export::PageSize::A4,
+        Some(s) => { return Err(CliError::Usage(format!("Unknown page size {}.", s))); },
+    };
+    let font = match args.option("font") {
+        None | Some("helvetica") => pdf_export::PdfFont::Helvetica,
+        Some("times") => pdf_export::PdfFont::Times,
+        Some("courier") => pdf_export::PdfFont::Courier,
+        Some(s) => { return Err(CliError::Usage(format!("Unknown font {}.", s))); },
+    };
+    let options = pdf_export::PdfOptions { page_size, font, answer_key: !args.flag("no-answer-key") };
+    let puzzle = read(&args.positional[0])?;
+    let path = args.positional[1].clone();
+    puzzle_file_io::export_puzzle_to_file(&puzzle, path, &options, args.flag("fill")).map_err(|e| CliError::Failed(e.to_string()))
+}
+
+fn fill(args: &Args) -> std::result::Result<(), CliError> {
+    args.expect(2, &["words", "blacklist", "overrides"])?;
+    let lists = match args.option("words") {
+        Some(l) => l,
+        None => { return Err(CliError::Usage("fill needs --words.".to_string())); },
+    };
+    let sources = word_list::WordListSources::from_paths(lists, args.option("blacklist").unwrap_or(""), args.option("overrides").unwrap_or(""));
+    let mut puzzle = read(&args.positional[0])?;
+    if puzzle.fill_only {
+        return Err(CliError::Failed("Solvable grids cannot be filled.".to_string()));
+    }
+    let words = sources.load().map_err(CliError::Failed)?;
+    match autofill::FillProblem::new(&puzzle).solve(&words, &AtomicBool::new(false)) {
+        autofill::FillOutcome::Filled(fill) => puzzle.apply_fill(&fill),
+        autofill::FillOutcome::NoSolution => { return Err(CliError::Failed("No fill exists with this word list.".to_string())); },
+        autofill::FillOutcome::Cancelled => { return Err(CliError::Failed("Fill cancelled.".to_string())); },
+    }
+    write(&puzzle, &args.positional[1], false, false)
+}
+
+fn import_clues(args: &Args) -> std::result::Result<(), CliError> {
+    args.expect(1, &[])?;
+    let mut db = clue_store::load().map_err(|e| CliError::Failed(format!("Could not read the clue database: {}", e)))?;
+    let before = db.len();
+    let summary = db.import_directory(std::path::Path::new(&args.positional[0])).map_err(CliError::Failed)?;
+    for (file, e) in summary.failed.iter() {
+        eprintln!("Could not read {}: {}", file, e);
+    }
+    clue_store::save(&db).map_err(|e| CliError::Failed(format!("Could not save the clue database: {}", e)))?;
+    println!("Added {} new clue(s) from {} file(s).", db.len() - before, summary.files);
+    Ok(())
+}
+
+fn clues(args: &Args) -> std::result::Result<(), CliError> {
+    args.expect(1, &[])?;
+    let db = clue_store::load().map_err(|e| CliError::Failed(format!("Could not read the clue database: {}", e)))?;
+    let records = db.clues_for(&args.positional[0]);
+    if records.is_empty() {
+        return Err(CliError::Failed(format!("No clues for {}.", args.positional[0].to_uppercase())));
+    }
+    for r in records.iter() {
+        println!("{}\t{}\t{}", r.clue, r.date, r.source);
+    }
+    Ok(())
+}
+
+fn read(path: &str) -> std::result::Result<puzzle_backend::Puzzle, CliError> {
+    puzzle_file_io::get_puzzle_from_file(path.to_string()).map_err(|e| CliError::Failed(format!("Could not open {}: {}", path, e)))
+}
+
+fn write(puzzle: &puzzle_backend::Puzzle, path: &str, save_solvable_grid: bool, scramble_solution: bool) -> std::result::Result<(), CliError> {
+    puzzle_file_io::write_puzzle_to_file(puzzle, path.to_string(), save_solvable_grid, scramble_solution)
+        .map_err(|e| CliError::Failed(format!("Could not write {}: {}", path, e)))
+}
@@ -0,0 +1,24 @@
+use crosser_core::clue_database;
+
+// The team's clue database, kept between sessions as one JSON file in the
+// user's data directory.
+
+fn store_path() -> Option<std::path::PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("crosser").join("clues.json"))
+}
+
+// A database that can't be read is an error, so that nothing saves over it.
+pub fn load() -> std::result::Result<clue_database::ClueDatabase, String> {
+    match store_path() {
+        Some(p) => clue_database::ClueDatabase::load(&p).map_err(|e| format!("{}: {}", p.display(), e)),
+        None => Ok(clue_database::ClueDatabase::new()),
+    }
+}
+
+pub fn save(db: &clue_database::ClueDatabase) -> std::result::Result<(), String> {
+    let store = store_path().ok_or_else(|| "no data directory".to_string())?;
+    if let Some(dir) = store.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    db.save(&store)
+}
@@ -1,16 +1,27 @@
-use iced::{Align, button, Button, Element, Length, Row, scrollable, Scrollable, Text, text_input, TextInput};
+use iced::{Align, button, Button, Column, Element, Length, Row, scrollable, Scrollable, Text, text_input, TextInput};
 use crate::central_ui;
-use crate::puzzle_backend;
+use crosser_core::clue_database;
+use crosser_core::puzzle_backend;
 
 use std::rc::Rc;
 use std::cell::RefCell;
 
+// The entry, answer and database size a set of past clues was looked up for.
+type PastCluesKey = (Option<(u32, puzzle_backend::EntryVariant)>, String, usize);
+
 pub struct CluesBrowser {
     pub backend: Rc<RefCell<puzzle_backend::Puzzle>>,
     pub a_clues: Vec<ClueEntry>,
     pub a_scroller: scrollable::State,
     pub d_clues: Vec<ClueEntry>,
     pub d_scroller: scrollable::State,
+    pub bin: Vec<BinnedClueEntry>,
+    pub bin_scroller: scrollable::State,
+    // Clues from the database for the answer of the entry selected in the grid.
+    pub past: Vec<PastClueEntry>,
+    pub past_scroller: scrollable::State,
+    past_answer: String,
+    past_for: Option<PastCluesKey>,
     pub being_modified: Option<(u32, puzzle_backend::EntryVariant)>,
 }
 
@@ -22,6 +33,12 @@ impl CluesBrowser {
             a_scroller: Default::default(),
             d_clues: Vec::new(),
             d_scroller: Default::default(),
+            bin: Vec::new(),
+            bin_scroller: Default::default(),
+            past: Vec::new(),
+            past_scroller: Default::default(),
+            past_answer: String::new(),
+            past_for: None,
             being_modified: None,
         };
         c.update_clues();
@@ -41,6 +58,32 @@ impl CluesBrowser {
         for d in d_entries.iter() {
             self.d_clues.push(ClueEntry::new(d.label,d.variant,d.clue.clone()));
         }
+
+        self.bin = self.backend.borrow().clue_bin.iter().enumerate().map(|(i,b)| BinnedClueEntry::new(i,b)).collect();
+    }
+
+    // Looks up the selected entry's answer, keeping the old rows while nothing
+    // changed so their buttons keep working between redraws.
+    pub fn refresh_past_clues(&mut self, db: &clue_database::ClueDatabase, entry: Option<(u32, puzzle_backend::EntryVariant)>) {
+        let answer = {
+            let backend = self.backend.borrow();
+            let entries = match entry {
+                Some((_l, puzzle_backend::EntryVariant::Across)) => &backend.across_entries,
+                _ => &backend.down_entries,
+            };
+            // Offering clues for a grid being solved would give its answers away.
+            entry.filter(|_e| !backend.fill_only)
+                .and_then(|(l,_v)| entries.iter().find(|e| e.label == l))
+                .and_then(|e| clue_database::entry_answer(&backend, &e.member_indices))
+                .unwrap_or_default()
+        };
+        let key = (entry, answer, db.len());
+        if self.past_for.as_ref() == Some(&key) {
+            return;
+        }
+        self.past = db.clues_for(&key.1).iter().map(PastClueEntry::new).collect();
+        self.past_answer = key.1.clone();
+        self.past_for = Some(key);
     }
 
     pub fn set_being_modified(&mut self, label: u32, variant: puzzle_backend::EntryVariant) {
@@ -92,8 +135,8 @@ impl CluesBrowser {
         }
     }
 
-    pub fn view (&mut self) -> Element<central_ui::Message> {
-        Row::new()
+    pub fn view (&mut self) -> Element<'_, central_ui::Message> {
+        let row = Row::new()
         .width(Length::FillPortion(1))
         .spacing(5)
         .align_items(Align::Start)
@@ -106,6 +149,29 @@ impl CluesBrowser {
         self.d_clues.iter_mut().fold(
         Scrollable::new(&mut self.d_scroller)
         , |sc, x| sc.push(x.view())).width(Length::Fill)
+        );
+        // Past clues and the bin only take up room while there's something in them.
+        let row = if self.past.is_empty() {
+            row
+        } else {
+            row.push(
+            self.past.iter_mut().fold(
+            Scrollable::new(&mut self.past_scroller)
+            .spacing(5)
+            .push(Text::new(format!("Past clues for {}", self.past_answer)))
+            , |sc, x| sc.push(x.view())).width(Length::Fill)
+            )
+        };
+        if self.bin.is_empty() {
+            return row.into();
+        }
+        row.push(
+        self.bin.iter_mut().fold(
+        Scrollable::new(&mut self.bin_scroller)
+        .spacing(5)
+        .push(Text::new("Clue bin"))
+        .push(Text::new("Use gives a clue to the entry selected in the grid.").size(14))
+        , |sc, x| sc.push(x.view())).width(Length::Fill)
         )
         .into()
     }
@@ -132,7 +198,7 @@ impl ClueEntry {
         }
     }
 
-    pub fn view(&mut self) -> Element<central_ui::Message> {
+    pub fn view(&mut self) -> Element<'_, central_ui::Message> {
         let a_or_d = match self.variant {
             puzzle_backend::EntryVariant::Across => 'A',
             puzzle_backend::EntryVariant::Down => 'D',
@@ -140,7 +206,7 @@ impl ClueEntry {
 
         let mut prefix = self.label.to_string();
         prefix.push(a_or_d);
-        prefix.push_str(&": ".to_string());
+        prefix.push_str(": ");
 
         let t = Text::new(prefix);
 
@@ -173,4 +239,80 @@ impl ClueEntry {
             .into()
         }
     }
+}
+
+// A clue waiting in the bin, with the answer it used to go with.
+pub struct BinnedClueEntry {
+    pub use_button: button::State,
+    pub discard_button: button::State,
+    pub index: usize,
+    pub answer: String,
+    pub clue: String,
+}
+
+impl BinnedClueEntry {
+    pub fn new(index: usize, binned: &puzzle_backend::BinnedClue) -> Self {
+        BinnedClueEntry {
+            use_button: Default::default(),
+            discard_button: Default::default(),
+            index,
+            answer: binned.answer.clone(),
+            clue: binned.clue.clone(),
+        }
+    }
+
+    pub fn view(&mut self) -> Element<'_, central_ui::Message> {
+        Row::new()
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(
+            Button::new(&mut self.use_button, Text::new("Use"))
+            .on_press(central_ui::Message::UseBinnedClue(self.index))
+        )
+        .push(
+            Column::new()
+            .push(Text::new(self.clue.clone()))
+            .push(Text::new(self.answer.clone()).size(14))
+        )
+        .push(
+            Button::new(&mut self.discard_button, Text::new("x"))
+            .on_press(central_ui::Message::DiscardBinnedClue(self.index))
+        )
+        .into()
+    }
+}
+
+// A clue written for the same answer before, with where it came from.
+pub struct PastClueEntry {
+    pub use_button: button::State,
+    pub clue: String,
+    pub history: String,
+}
+
+impl PastClueEntry {
+    pub fn new(record: &clue_database::ClueRecord) -> Self {
+        let file = std::path::Path::new(&record.source).file_name().map(|f| f.to_string_lossy().to_string()).unwrap_or_default();
+        let history = if record.date.is_empty() { file } else { format!("{}, {}", file, record.date) };
+        PastClueEntry {
+            use_button: Default::default(),
+            clue: record.clue.clone(),
+            history,
+        }
+    }
+
+    pub fn view(&mut self) -> Element<'_, central_ui::Message> {
+        Row::new()
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(
+            Button::new(&mut self.use_button, Text::new("Use"))
+            .on_press(central_ui::Message::UsePastClue(self.clue.clone()))
+        )
+        .push(
+            Column::new()
+            .push(Text::new(self.clue.clone()))
+            .push(Text::new(self.history.clone()).size(14))
+        )
+        .into()
+    }
 }
\ No newline at end of file
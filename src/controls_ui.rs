@@ -1,31 +1,100 @@
-use iced::{Align, button, Button, Checkbox, Element, Row, Text, text_input, TextInput, VerticalAlignment};
+use iced::{Align, button, Button, Checkbox, Column, Element, Length, pick_list, PickList, Radio, Row, Text, text_input, TextInput, VerticalAlignment};
 use crate::central_ui;
-use crate::puzzle_backend;
+use crosser_core::pdf_export;
+use crosser_core::puzzle_backend;
 
 #[derive(Debug, Clone)]
 pub enum State {
     Main,
     New,
+    NewCustom,
     Save,
+    ConfirmOverwrite(String),
     Open,
+    Export,
+    Fill,
+    Filling,
+    Validation(String),
+    Symmetry(puzzle_backend::Symmetry),
+    Metadata,
+    // Asks before running a message that would throw away the unsaved changes in the named tab.
+    ConfirmDiscard(String, Box<central_ui::Message>),
+    // How many autosaved puzzles there are to restore.
+    Recover(usize),
+    Region,
+    Transform,
+    ClueDatabase,
     OperationResult(String),
 }
 
+struct MetadataInput {
+    field: puzzle_backend::MetadataField,
+    name: &'static str,
+    input: text_input::State,
+    value: String,
+}
+
 pub struct ControlsRow {
     new_but: button::State,
     mini_but: button::State,
     weekday_but: button::State,
     weekday_asym_but: button::State,
     sunday_but: button::State,
+    custom_but: button::State,
+    custom_width_field: text_input::State,
+    pub custom_width_string: String,
+    custom_height_field: text_input::State,
+    pub custom_height_string: String,
+    create_but: button::State,
     back_but: button::State,
     state: State,
     save_but: button::State,
     save_field: text_input::State,
     pub save_path_string: String,
     pub save_empty_grid: bool,
+    pub scramble_solution: bool,
+    replace_but: button::State,
     open_but: button::State,
     open_field: text_input::State,
     pub open_path_string: String,
+    browse_but: button::State,
+    recent_list: pick_list::State<String>,
+    export_but: button::State,
+    export_field: text_input::State,
+    pub export_path_string: String,
+    pub pdf_options: pdf_export::PdfOptions,
+    pub export_fill: bool,
+    fill_but: button::State,
+    validate_but: button::State,
+    symmetry_but: button::State,
+    symmetrize_but: button::State,
+    metadata_but: button::State,
+    metadata_inputs: Vec<MetadataInput>,
+    load_but: button::State,
+    fill_field: text_input::State,
+    pub fill_path_string: String,
+    blacklist_field: text_input::State,
+    pub blacklist_path_string: String,
+    overrides_field: text_input::State,
+    pub overrides_path_string: String,
+    cancel_but: button::State,
+    discard_but: button::State,
+    restore_but: button::State,
+    region_but: button::State,
+    copy_but: button::State,
+    cut_but: button::State,
+    paste_but: button::State,
+    clear_but: button::State,
+    shift_buts: [button::State; 4],
+    rotate_but: button::State,
+    transform_but: button::State,
+    transform_buts: [button::State; 8],
+    pub region_status: String,
+    clue_db_but: button::State,
+    import_field: text_input::State,
+    pub import_path_string: String,
+    import_but: button::State,
+    pub clue_db_status: String,
 }
 
 impl ControlsRow {
@@ -36,15 +105,70 @@ impl ControlsRow {
             weekday_but: Default::default(),
             weekday_asym_but: Default::default(),
             sunday_but: Default::default(),
+            custom_but: Default::default(),
+            custom_width_field: Default::default(),
+            custom_width_string: "15".to_string(),
+            custom_height_field: Default::default(),
+            custom_height_string: "15".to_string(),
+            create_but: Default::default(),
             back_but: Default::default(),
             state: State::Main,
             save_but: Default::default(),
             save_field: Default::default(),
             save_path_string: std::env::current_dir().unwrap().to_str().unwrap().to_string(),
             save_empty_grid: false,
+            scramble_solution: false,
+            replace_but: Default::default(),
             open_but: Default::default(),
             open_field: Default::default(),
             open_path_string: std::env::current_dir().unwrap().to_str().unwrap().to_string(),
+            browse_but: Default::default(),
+            recent_list: Default::default(),
+            export_but: Default::default(),
+            export_field: Default::default(),
+            export_path_string: std::env::current_dir().unwrap().to_str().unwrap().to_string(),
+            pdf_options: Default::default(),
+            export_fill: false,
+            fill_but: Default::default(),
+            validate_but: Default::default(),
+            symmetry_but: Default::default(),
+            symmetrize_but: Default::default(),
+            metadata_but: Default::default(),
+            metadata_inputs: [
+                (puzzle_backend::MetadataField::Title, "Title: "),
+                (puzzle_backend::MetadataField::Author, "Author: "),
+                (puzzle_backend::MetadataField::Editor, "Editor: "),
+                (puzzle_backend::MetadataField::Copyright, "Copyright: "),
+                (puzzle_backend::MetadataField::Date, "Date: "),
+                (puzzle_backend::MetadataField::Difficulty, "Difficulty: "),
+                (puzzle_backend::MetadataField::Tags, "Tags: "),
+                (puzzle_backend::MetadataField::Notes, "Notes: "),
+            ].iter().map(|(field, name)| MetadataInput { field: *field, name, input: Default::default(), value: String::new() }).collect(),
+            load_but: Default::default(),
+            fill_field: Default::default(),
+            fill_path_string: std::env::current_dir().unwrap().to_str().unwrap().to_string(),
+            blacklist_field: Default::default(),
+            blacklist_path_string: String::new(),
+            overrides_field: Default::default(),
+            overrides_path_string: String::new(),
+            cancel_but: Default::default(),
+            discard_but: Default::default(),
+            restore_but: Default::default(),
+            region_but: Default::default(),
+            copy_but: Default::default(),
+            cut_but: Default::default(),
+            paste_but: Default::default(),
+            clear_but: Default::default(),
+            shift_buts: Default::default(),
+            rotate_but: Default::default(),
+            transform_but: Default::default(),
+            transform_buts: Default::default(),
+            region_status: String::new(),
+            clue_db_but: Default::default(),
+            import_field: Default::default(),
+            import_path_string: std::env::current_dir().unwrap().to_str().unwrap().to_string(),
+            import_but: Default::default(),
+            clue_db_status: String::new(),
         }
     }
 
@@ -52,7 +176,20 @@ impl ControlsRow {
         self.state = s;
     }
 
-    pub fn view(&mut self) -> Element<central_ui::Message> {
+    // Fills the metadata inputs from the puzzle being edited.
+    pub fn load_metadata(&mut self, puzzle: &puzzle_backend::Puzzle) {
+        for m in self.metadata_inputs.iter_mut() {
+            m.value = puzzle.metadata(m.field);
+        }
+    }
+
+    pub fn set_metadata_string(&mut self, field: puzzle_backend::MetadataField, value: String) {
+        if let Some(m) = self.metadata_inputs.iter_mut().find(|m| m.field == field) {
+            m.value = value;
+        }
+    }
+
+    pub fn view<'a>(&'a mut self, recent_files: &'a [String]) -> Element<'a, central_ui::Message> {
         match &self.state {
             State::Main => {
                 Row::new()
@@ -66,6 +203,30 @@ impl ControlsRow {
                 .push(
                     Button::new(&mut self.open_but, Text::new("Open ...")).on_press(central_ui::Message::ControlSetState(State::Open))
                 )
+                .push(
+                    Button::new(&mut self.export_but, Text::new("Export ...")).on_press(central_ui::Message::ControlSetState(State::Export))
+                )
+                .push(
+                    Button::new(&mut self.fill_but, Text::new("Fill ...")).on_press(central_ui::Message::ControlSetState(State::Fill))
+                )
+                .push(
+                    Button::new(&mut self.validate_but, Text::new("Validate")).on_press(central_ui::Message::Validate)
+                )
+                .push(
+                    Button::new(&mut self.symmetry_but, Text::new("Symmetry ...")).on_press(central_ui::Message::EditSymmetry)
+                )
+                .push(
+                    Button::new(&mut self.metadata_but, Text::new("Details ...")).on_press(central_ui::Message::EditMetadata)
+                )
+                .push(
+                    Button::new(&mut self.region_but, Text::new("Region ...")).on_press(central_ui::Message::EditRegion)
+                )
+                .push(
+                    Button::new(&mut self.transform_but, Text::new("Transform ...")).on_press(central_ui::Message::ControlSetState(State::Transform))
+                )
+                .push(
+                    Button::new(&mut self.clue_db_but, Text::new("Clues ...")).on_press(central_ui::Message::EditClueDatabase)
+                )
                 .into()
             }
             State::New => {
@@ -86,6 +247,37 @@ impl ControlsRow {
                 .push(
                     Button::new(&mut self.sunday_but, Text::new("New Sunday")).on_press(central_ui::Message::NewPuzzle(puzzle_backend::PuzzleType::Sunday))
                 )
+                .push(
+                    Button::new(&mut self.custom_but, Text::new("New custom size ...")).on_press(central_ui::Message::ControlSetState(State::NewCustom))
+                )
+                .into()
+            }
+            State::NewCustom => {
+                Row::new()
+                .spacing(10)
+                .align_items(Align::Center)
+                .push(
+                    Button::new(&mut self.back_but, Text::new("Back")).on_press(central_ui::Message::ControlSetState(State::New))
+                )
+                .push(
+                    Text::new("Width: ").vertical_alignment(VerticalAlignment::Center)
+                )
+                .push(
+                    TextInput::new(&mut self.custom_width_field, "Width..." , &self.custom_width_string, central_ui::Message::CustomWidthModified)
+                    .on_submit(central_ui::Message::AttemptNewCustom)
+                    .width(Length::Units(60))
+                )
+                .push(
+                    Text::new("Height: ").vertical_alignment(VerticalAlignment::Center)
+                )
+                .push(
+                    TextInput::new(&mut self.custom_height_field, "Height..." , &self.custom_height_string, central_ui::Message::CustomHeightModified)
+                    .on_submit(central_ui::Message::AttemptNewCustom)
+                    .width(Length::Units(60))
+                )
+                .push(
+                    Button::new(&mut self.create_but, Text::new("Create")).on_press(central_ui::Message::AttemptNewCustom)
+                )
                 .into()
             }
             State::Save => {
@@ -96,12 +288,15 @@ impl ControlsRow {
                     Button::new(&mut self.back_but, Text::new("Back")).on_press(central_ui::Message::ControlSetState(State::Main))
                 )
                 .push(
-                    Text::new("Save to file (*.cro): ").vertical_alignment(VerticalAlignment::Center)
+                    Text::new("Save to file (*.cro, *.puz, *.ipuz): ").vertical_alignment(VerticalAlignment::Center)
                 )
                 .push(
                     TextInput::new(&mut self.save_field, "Save file path..." , &self.save_path_string, central_ui::Message::SavePathModified)
                     .on_submit(central_ui::Message::AttemptSave)
                 )
+                .push(
+                    Button::new(&mut self.browse_but, Text::new("Browse ...")).on_press(central_ui::Message::BrowseSave)
+                )
                 .push(
                     Checkbox::new(
                         self.save_empty_grid,
@@ -109,27 +304,328 @@ impl ControlsRow {
                         central_ui::Message::SaveEmptyGrid
                     )
                 )
+                .push(
+                    Checkbox::new(
+                        self.scramble_solution,
+                        "Scramble solution?".to_string(),
+                        central_ui::Message::ScrambleSolution
+                    )
+                )
                 .push(
                     Button::new(&mut self.save_but, Text::new("Save")).on_press(central_ui::Message::AttemptSave)
                 )
                 .into()
             }
-            State::Open => {
+            State::ConfirmOverwrite(path) => {
                 Row::new()
                 .spacing(10)
                 .align_items(Align::Center)
+                .push(
+                    Button::new(&mut self.back_but, Text::new("Back")).on_press(central_ui::Message::ControlSetState(State::Save))
+                )
+                .push(
+                    Text::new(format!("{} already exists. Replace it?", path))
+                )
+                .push(
+                    Button::new(&mut self.replace_but, Text::new("Replace")).on_press(central_ui::Message::SaveTo(path.clone()))
+                )
+                .into()
+            }
+            State::Open => {
+                let mut row = Row::new()
+                .spacing(10)
+                .align_items(Align::Center)
                 .push(
                     Button::new(&mut self.back_but, Text::new("Back")).on_press(central_ui::Message::ControlSetState(State::Main))
                 )
                 .push(
-                    Text::new("Open file (*.cro): ").vertical_alignment(VerticalAlignment::Center)
+                    Text::new("Open file (*.cro, *.puz, *.ipuz): ").vertical_alignment(VerticalAlignment::Center)
                 )
                 .push(
                     TextInput::new(&mut self.open_field, "Open file path..." , &self.open_path_string, central_ui::Message::OpenPathModified)
                     .on_submit(central_ui::Message::AttemptOpen)
                 )
+                .push(
+                    Button::new(&mut self.browse_but, Text::new("Browse ...")).on_press(central_ui::Message::BrowseOpen)
+                )
                 .push(
                     Button::new(&mut self.open_but, Text::new("Open")).on_press(central_ui::Message::AttemptOpen)
+                );
+                if !recent_files.is_empty() {
+                    row = row
+                    .push(
+                        Text::new("Recent: ").vertical_alignment(VerticalAlignment::Center)
+                    )
+                    .push(
+                        PickList::new(&mut self.recent_list, recent_files, None, central_ui::Message::OpenPath)
+                    );
+                }
+                row.into()
+            }
+            State::Export => {
+                let page_sizes = [(pdf_export::PageSize::Letter, "Letter"), (pdf_export::PageSize::A4, "A4")];
+                let fonts = [(pdf_export::PdfFont::Helvetica, "Helvetica"), (pdf_export::PdfFont::Times, "Times"), (pdf_export::PdfFont::Courier, "Courier")];
+                let (page_size, font) = (self.pdf_options.page_size, self.pdf_options.font);
+                let row = Row::new()
+                .spacing(10)
+                .align_items(Align::Center)
+                .push(
+                    Button::new(&mut self.back_but, Text::new("Back")).on_press(central_ui::Message::ControlSetState(State::Main))
+                )
+                .push(
+                    Text::new("Export to file (*.pdf, *.svg, *.txt): ").vertical_alignment(VerticalAlignment::Center)
+                )
+                .push(
+                    TextInput::new(&mut self.export_field, "Export file path..." , &self.export_path_string, central_ui::Message::ExportPathModified)
+                    .on_submit(central_ui::Message::AttemptExport)
+                );
+                let row = page_sizes.iter().fold(row, |row, (size, name)| row.push(Radio::new(*size, *name, Some(page_size), central_ui::Message::PageSizeSelected)));
+                let row = fonts.iter().fold(row, |row, (f, name)| row.push(Radio::new(*f, *name, Some(font), central_ui::Message::PdfFontSelected)));
+                row
+                .push(
+                    Checkbox::new(
+                        self.pdf_options.answer_key,
+                        "Answer key?".to_string(),
+                        central_ui::Message::ExportAnswerKey
+                    )
+                )
+                .push(
+                    Checkbox::new(
+                        self.export_fill,
+                        "Show fill in SVG/text?".to_string(),
+                        central_ui::Message::ExportFill
+                    )
+                )
+                .push(
+                    Button::new(&mut self.export_but, Text::new("Export")).on_press(central_ui::Message::AttemptExport)
+                )
+                .into()
+            }
+            State::Fill => {
+                Row::new()
+                .spacing(10)
+                .align_items(Align::Center)
+                .push(
+                    Button::new(&mut self.back_but, Text::new("Back")).on_press(central_ui::Message::ControlSetState(State::Main))
+                )
+                .push(
+                    Text::new("Word lists (comma separated, highest priority first): ").vertical_alignment(VerticalAlignment::Center)
+                )
+                .push(
+                    TextInput::new(&mut self.fill_field, "Word list paths..." , &self.fill_path_string, central_ui::Message::FillPathModified)
+                    .on_submit(central_ui::Message::AttemptFill)
+                )
+                .push(
+                    Text::new("Blacklist: ").vertical_alignment(VerticalAlignment::Center)
+                )
+                .push(
+                    TextInput::new(&mut self.blacklist_field, "Optional..." , &self.blacklist_path_string, central_ui::Message::BlacklistPathModified)
+                    .on_submit(central_ui::Message::AttemptFill)
+                    .width(Length::FillPortion(1))
+                )
+                .push(
+                    Text::new("Score overrides: ").vertical_alignment(VerticalAlignment::Center)
+                )
+                .push(
+                    TextInput::new(&mut self.overrides_field, "Optional..." , &self.overrides_path_string, central_ui::Message::OverridesPathModified)
+                    .on_submit(central_ui::Message::AttemptFill)
+                    .width(Length::FillPortion(1))
+                )
+                .push(
+                    Button::new(&mut self.load_but, Text::new("Load")).on_press(central_ui::Message::AttemptLoadWordList)
+                )
+                .push(
+                    Button::new(&mut self.fill_but, Text::new("Fill")).on_press(central_ui::Message::AttemptFill)
+                )
+                .into()
+            }
+            State::Filling => {
+                Row::new()
+                .spacing(10)
+                .align_items(Align::Center)
+                .push(
+                    Button::new(&mut self.cancel_but, Text::new("Cancel")).on_press(central_ui::Message::CancelFill)
+                )
+                .push(
+                    Text::new("Filling grid ...")
+                )
+                .into()
+            }
+            State::Symmetry(current) => {
+                let modes = [
+                    (puzzle_backend::Symmetry::None, "None"),
+                    (puzzle_backend::Symmetry::Rotational, "Rotational"),
+                    (puzzle_backend::Symmetry::MirrorLeftRight, "Left-right"),
+                    (puzzle_backend::Symmetry::MirrorTopBottom, "Top-bottom"),
+                    (puzzle_backend::Symmetry::Diagonal, "Diagonal"),
+                    (puzzle_backend::Symmetry::FourWay, "Four-way"),
+                ];
+                let current = *current;
+                modes.iter().fold(
+                    Row::new()
+                    .spacing(10)
+                    .align_items(Align::Center)
+                    .push(
+                        Button::new(&mut self.back_but, Text::new("Back")).on_press(central_ui::Message::ControlSetState(State::Main))
+                    ),
+                    |row, (mode, name)| row.push(Radio::new(*mode, *name, Some(current), central_ui::Message::SymmetrySelected))
+                )
+                .push(
+                    Button::new(&mut self.symmetrize_but, Text::new("Symmetrize")).on_press(central_ui::Message::Symmetrize)
+                )
+                .into()
+            }
+            State::Metadata => {
+                // Notes and tags tend to run long, so they get a row of their own.
+                let (first, second) = self.metadata_inputs.split_at_mut(6);
+                let first_row = metadata_row(first, Row::new()
+                    .spacing(10)
+                    .align_items(Align::Center)
+                    .push(
+                        Button::new(&mut self.back_but, Text::new("Done")).on_press(central_ui::Message::ControlSetState(State::Main))
+                    )
+                );
+                let second_row = metadata_row(second, Row::new().spacing(10).align_items(Align::Center));
+                Column::new()
+                .spacing(5)
+                .push(first_row)
+                .push(second_row)
+                .into()
+            }
+            State::Validation(s) => {
+                Row::new()
+                .spacing(10)
+                .align_items(Align::Center)
+                .push(
+                    Button::new(&mut self.back_but, Text::new("Done")).on_press(central_ui::Message::EndValidation)
+                )
+                .push(
+                    Text::new(s)
+                )
+                .into()
+            }
+            State::ConfirmDiscard(name, m) => {
+                Row::new()
+                .spacing(10)
+                .align_items(Align::Center)
+                .push(
+                    Button::new(&mut self.back_but, Text::new("Back")).on_press(central_ui::Message::ControlSetState(State::Main))
+                )
+                .push(
+                    Text::new(format!("{} has unsaved changes.", name))
+                )
+                .push(
+                    Button::new(&mut self.save_but, Text::new("Save ...")).on_press(central_ui::Message::ControlSetState(State::Save))
+                )
+                .push(
+                    Button::new(&mut self.discard_but, Text::new("Discard changes")).on_press(central_ui::Message::DiscardChanges(m.clone()))
+                )
+                .into()
+            }
+            State::Recover(n) => {
+                Row::new()
+                .spacing(10)
+                .align_items(Align::Center)
+                .push(
+                    Text::new(format!("Crosser didn't close properly last time. Restore {} unsaved puzzle(s)?", n))
+                )
+                .push(
+                    Button::new(&mut self.restore_but, Text::new("Restore")).on_press(central_ui::Message::RestoreRecovered)
+                )
+                .push(
+                    Button::new(&mut self.discard_but, Text::new("Discard")).on_press(central_ui::Message::DiscardRecovered)
+                )
+                .into()
+            }
+            State::Region => {
+                let [left, right, up, down] = &mut self.shift_buts;
+                Row::new()
+                .spacing(10)
+                .align_items(Align::Center)
+                .push(
+                    Button::new(&mut self.back_but, Text::new("Back")).on_press(central_ui::Message::ControlSetState(State::Main))
+                )
+                .push(
+                    Button::new(&mut self.copy_but, Text::new("Copy")).on_press(central_ui::Message::CopyRegion)
+                )
+                .push(
+                    Button::new(&mut self.cut_but, Text::new("Cut")).on_press(central_ui::Message::CutRegion)
+                )
+                .push(
+                    Button::new(&mut self.paste_but, Text::new("Paste")).on_press(central_ui::Message::PasteRegion)
+                )
+                .push(
+                    Button::new(&mut self.clear_but, Text::new("Clear")).on_press(central_ui::Message::ClearRegion)
+                )
+                .push(
+                    Text::new("Shift:")
+                )
+                .push(
+                    Button::new(left, Text::new("Left")).on_press(central_ui::Message::ShiftRegion(-1, 0))
+                )
+                .push(
+                    Button::new(right, Text::new("Right")).on_press(central_ui::Message::ShiftRegion(1, 0))
+                )
+                .push(
+                    Button::new(up, Text::new("Up")).on_press(central_ui::Message::ShiftRegion(0, -1))
+                )
+                .push(
+                    Button::new(down, Text::new("Down")).on_press(central_ui::Message::ShiftRegion(0, 1))
+                )
+                .push(
+                    Button::new(&mut self.rotate_but, Text::new("Rotate")).on_press(central_ui::Message::RotateRegion)
+                )
+                .push(
+                    Text::new(&self.region_status)
+                )
+                .into()
+            }
+            State::Transform => {
+                let transforms = [
+                    ("Rotate", puzzle_backend::GridTransform::Rotate),
+                    ("Flip left/right", puzzle_backend::GridTransform::FlipHorizontal),
+                    ("Flip top/bottom", puzzle_backend::GridTransform::FlipVertical),
+                    ("Transpose", puzzle_backend::GridTransform::Transpose),
+                    ("Shift left", puzzle_backend::GridTransform::Shift(-1, 0)),
+                    ("Shift right", puzzle_backend::GridTransform::Shift(1, 0)),
+                    ("Shift up", puzzle_backend::GridTransform::Shift(0, -1)),
+                    ("Shift down", puzzle_backend::GridTransform::Shift(0, 1)),
+                ];
+                let mut row = Row::new()
+                .spacing(10)
+                .align_items(Align::Center)
+                .push(
+                    Button::new(&mut self.back_but, Text::new("Back")).on_press(central_ui::Message::ControlSetState(State::Main))
+                );
+                for ((name, t), b) in transforms.iter().zip(self.transform_buts.iter_mut()) {
+                    row = row.push(
+                        Button::new(b, Text::new(*name)).on_press(central_ui::Message::TransformGrid(*t))
+                    );
+                }
+                row.into()
+            }
+            State::ClueDatabase => {
+                Row::new()
+                .spacing(10)
+                .align_items(Align::Center)
+                .push(
+                    Button::new(&mut self.back_but, Text::new("Back")).on_press(central_ui::Message::ControlSetState(State::Main))
+                )
+                .push(
+                    Text::new("Import clues from the .cro files in: ").vertical_alignment(VerticalAlignment::Center)
+                )
+                .push(
+                    TextInput::new(&mut self.import_field, "Directory path..." , &self.import_path_string, central_ui::Message::ImportPathModified)
+                    .on_submit(central_ui::Message::ImportClues)
+                )
+                .push(
+                    Button::new(&mut self.browse_but, Text::new("Browse ...")).on_press(central_ui::Message::BrowseImport)
+                )
+                .push(
+                    Button::new(&mut self.import_but, Text::new("Import")).on_press(central_ui::Message::ImportClues)
+                )
+                .push(
+                    Text::new(&self.clue_db_status)
                 )
                 .into()
             }
@@ -147,4 +643,16 @@ impl ControlsRow {
             }
         }
     }
+}
+
+fn metadata_row<'a>(inputs: &'a mut [MetadataInput], start: Row<'a, central_ui::Message>) -> Row<'a, central_ui::Message> {
+    inputs.iter_mut().fold(start, |row, m| {
+        let field = m.field;
+        row.push(
+            Text::new(m.name).vertical_alignment(VerticalAlignment::Center)
+        )
+        .push(
+            TextInput::new(&mut m.input, "", &m.value, move |s| central_ui::Message::MetadataModified(field, s))
+        )
+    })
 }
\ No newline at end of file
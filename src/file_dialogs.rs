@@ -0,0 +1,54 @@
+use native_dialog::FileDialog;
+
+// Native open, save and directory dialogs. They block until the user picks
+// something or cancels, and start from the path currently typed into the controls.
+
+const PUZZLE_EXTENSIONS: [&str; 3] = ["cro", "puz", "ipuz"];
+
+fn start_location(start: &str) -> (std::path::PathBuf, Option<String>) {
+    let path = std::path::Path::new(start);
+    if path.is_dir() {
+        return (path.to_path_buf(), None);
+    }
+    let dir = match path.parent() {
+        Some(p) if p.is_dir() => p.to_path_buf(),
+        _ => std::env::current_dir().unwrap_or_default(),
+    };
+    (dir, path.file_name().and_then(|f| f.to_str()).map(|f| f.to_string()))
+}
+
+fn path_string(picked: native_dialog::Result<Option<std::path::PathBuf>>) -> std::result::Result<Option<String>, String> {
+    match picked {
+        Ok(Some(p)) => Ok(Some(p.to_string_lossy().to_string())),
+        Ok(None) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+pub fn pick_open_path(start: &str) -> std::result::Result<Option<String>, String> {
+    let (dir, _file) = start_location(start);
+    path_string(FileDialog::new()
+        .set_location(&dir)
+        .add_filter("Crossword puzzles", &PUZZLE_EXTENSIONS)
+        .show_open_single_file())
+}
+
+// The dialog asks before replacing an existing file.
+pub fn pick_save_path(start: &str) -> std::result::Result<Option<String>, String> {
+    let (dir, file) = start_location(start);
+    let file = file.unwrap_or_else(|| "puzzle.cro".to_string());
+    path_string(FileDialog::new()
+        .set_location(&dir)
+        .set_filename(&file)
+        .add_filter("Crosser puzzle", &["cro"])
+        .add_filter("Across Lite puzzle", &["puz"])
+        .add_filter("ipuz puzzle", &["ipuz"])
+        .show_save_single_file())
+}
+
+pub fn pick_directory(start: &str) -> std::result::Result<Option<String>, String> {
+    let (dir, _file) = start_location(start);
+    path_string(FileDialog::new()
+        .set_location(&dir)
+        .show_open_single_dir())
+}
@@ -1,12 +1,25 @@
 mod central_ui;
+mod cli;
+mod clue_store;
 mod clue_ui;
 mod controls_ui;
-mod puzzle_backend;
+mod file_dialogs;
 mod puzzle_canvas;
-mod puzzle_file_io;
+mod recent_files;
+mod recovery;
+mod solver_ui;
+mod suggestions_ui;
+mod system_clipboard;
+mod tab;
 
 use iced::{Application, Settings};
 
 fn main() -> iced::Result {
-    central_ui::CrosserUI::run(Settings::default())
+    // A command makes this a command line run, which never opens a window.
+    // Otherwise the arguments are files to open, e.g. from the file manager.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|a| cli::is_command(a)) {
+        std::process::exit(cli::run(&args));
+    }
+    central_ui::CrosserUI::run(Settings::with_flags(args))
 }
@@ -3,7 +3,8 @@ use iced::{
        mouse, keyboard, Color, Point, Rectangle, Size, HorizontalAlignment, VerticalAlignment,
        };
 use crate::central_ui;
-use crate::puzzle_backend;
+use crosser_core::grid_layout;
+use crosser_core::puzzle_backend;
 
 struct SquareUIInfo {
     x: u32,
@@ -14,9 +15,8 @@ struct SquareUIInfo {
 
 struct GridUIInfo {
     min_size: f32,
-    square_width: f32,
-    content_width: f32,
-    label_size: f32,
+    height: u32,
+    layout: grid_layout::GridLayout,
     frame_square_infos: Vec<SquareUIInfo>,
     clue_start: Point,
     clue_height: f32,
@@ -25,24 +25,19 @@ struct GridUIInfo {
 }
 
 impl GridUIInfo {
-    fn new(bounds: &Rectangle, dim: u32) -> Self {
+    fn new(bounds: &Rectangle, width: u32, height: u32) -> Self {
         let (canv_w, canv_h) = (bounds.size().width, bounds.size().height);
         let min_size = match canv_w < canv_h { 
             true => canv_w,
             false  => canv_h,
         };
         const SCALING_FACTOR: f32 = 0.85;
-        let square_width = SCALING_FACTOR * (min_size / dim as f32);
+        // The longer side of the grid spans the same extent a square grid would.
+        let square_width = SCALING_FACTOR * (min_size / width.max(height) as f32);
+        let layout = grid_layout::GridLayout::new(square_width, width, height);
+        let frame_square_infos = GridUIInfo::get_frame_square_infos(&layout, width, height);
 
-        const PADDING_FACTOR: f32 = 0.05;
-        let padding_width = PADDING_FACTOR * square_width;
-        let content_width = square_width - padding_width;
-
-        const LABEL_FACTOR: f32 = 0.30;
-        let label_size = LABEL_FACTOR * square_width;
-        let frame_square_infos = GridUIInfo::get_frame_square_infos(square_width, content_width, dim);
-
-        let clue_start = Point::new(0.0, SCALING_FACTOR * min_size);
+        let clue_start = Point::new(0.0, square_width * height as f32);
         let clue_height = (1.0 - SCALING_FACTOR) * min_size;
         let clue_width = SCALING_FACTOR * min_size;
         const CLUE_PADDING_FACTOR: f32 = 0.01;
@@ -50,9 +45,8 @@ impl GridUIInfo {
  
         GridUIInfo {
             min_size,
-            square_width,
-            content_width,
-            label_size,
+            height,
+            layout,
             frame_square_infos,
             clue_start,
             clue_height,
@@ -61,19 +55,15 @@ impl GridUIInfo {
         }
     }
 
-    fn get_frame_square_infos(sq_w: f32, c_w: f32, dim: u32) -> Vec<SquareUIInfo>{
-        let mut frame_square_infos: Vec<SquareUIInfo> = Vec::with_capacity(dim as usize * dim as usize);
+    fn get_frame_square_infos(layout: &grid_layout::GridLayout, width: u32, height: u32) -> Vec<SquareUIInfo>{
+        let mut frame_square_infos: Vec<SquareUIInfo> = Vec::with_capacity(width as usize * height as usize);
         // Column-major
-        for x in 0..dim {
-            for y in 0..dim {
-                let p = Point {
-                    x: sq_w * ((x as f32) + 0.5),
-                    y: sq_w * ((y as f32) + 0.5),
-                };
-                let c = Point {
-                    x: p.x - c_w / 2.0,
-                    y: p.y - c_w / 2.0,
-                };
+        for x in 0..width {
+            for y in 0..height {
+                let (px, py) = layout.center(x, y);
+                let p = Point::new(px, py);
+                let (cx, cy) = layout.content_top_left(x, y);
+                let c = Point::new(cx, cy);
                 frame_square_infos.push(SquareUIInfo {
                     x,
                     y,
@@ -86,7 +76,11 @@ impl GridUIInfo {
         frame_square_infos
     }
 
-    fn update(&mut self, bounds: &Rectangle, dim: u32) -> bool {
+    fn square_info(&self, x: u32, y: u32) -> &SquareUIInfo {
+        &self.frame_square_infos[(x * self.height + y) as usize]
+    }
+
+    fn update(&mut self, bounds: &Rectangle, width: u32, height: u32) -> bool {
         // If the square size hasn't changed, then nothing else has either.
         let (canv_w, canv_h) = (bounds.size().width, bounds.size().height);
         let min_size = match canv_w < canv_h { 
@@ -94,7 +88,7 @@ impl GridUIInfo {
             false  => canv_h,
         };
         if min_size != self.min_size {
-            let new_g_ui_info = GridUIInfo::new(bounds, dim);
+            let new_g_ui_info = GridUIInfo::new(bounds, width, height);
             *self = new_g_ui_info;
             true
         } else {
@@ -108,15 +102,23 @@ use std::cell::RefCell;
 
 pub struct PuzzleCanvas {
     backend: Rc<RefCell<puzzle_backend::Puzzle>>,
-    dim: u32,
+    width: u32,
+    height: u32,
     grid_info: GridUIInfo,
     cursor_pos: Point,
     ignore_keystrokes: bool,
     lctrl_held: bool,
     rctrl_held: bool,
+    lshift_held: bool,
+    rshift_held: bool,
+    // Whether the mouse is held down in the middle of a shift-drag.
+    dragging: bool,
     hovered_square: Option<(u32, u32)>,
     selected_square: Option<(u32, u32)>,
     selected_variant: puzzle_backend::EntryVariant,
+    // Opposite corners of the rectangle picked with shift-drag or shift-arrows. The
+    // first stays put, the second follows the mouse or arrow keys.
+    selected_region: Option<((u32, u32), (u32, u32))>,
     grid_cache: canvas::Cache,
     label_cache: canvas::Cache,
     content_cache: canvas::Cache,
@@ -124,22 +126,31 @@ pub struct PuzzleCanvas {
     highlighter_cache: canvas::Cache,
     clues_cache: canvas::Cache,
     solved_highlight_cache: canvas::Cache,
+    // Squares marked by the last validation, in backend order.
+    flagged_squares: Vec<bool>,
+    validation_cache: canvas::Cache,
+    marks_cache: canvas::Cache,
 }
 
 impl PuzzleCanvas {
     pub fn new(backend: Rc<RefCell<puzzle_backend::Puzzle>>) -> PuzzleCanvas {
-        let d = puzzle_backend::match_puzzle_dim(&backend.borrow().variant);
+        let (w, h) = (backend.borrow().width() as u32, backend.borrow().height() as u32);
         PuzzleCanvas {
             backend,
-            grid_info: GridUIInfo::new(&Rectangle::with_size(Size::new(1.0,1.0)), d as u32),
-            dim: d as u32,
+            grid_info: GridUIInfo::new(&Rectangle::with_size(Size::new(1.0,1.0)), w, h),
+            width: w,
+            height: h,
             cursor_pos: Point::new(0.0,0.0),
             ignore_keystrokes: false,
             lctrl_held: false,
             rctrl_held: false,
+            lshift_held: false,
+            rshift_held: false,
+            dragging: false,
             hovered_square: None,
             selected_square: None,
             selected_variant: puzzle_backend::EntryVariant::Across,
+            selected_region: None,
             grid_cache: Default::default(),
             label_cache: Default::default(),
             content_cache: Default::default(),
@@ -147,23 +158,101 @@ impl PuzzleCanvas {
             highlighter_cache: Default::default(),
             clues_cache: Default::default(),
             solved_highlight_cache: Default::default(),
+            flagged_squares: Vec::new(),
+            validation_cache: Default::default(),
+            marks_cache: Default::default(),
         }
     }
 
     pub fn set_ignore_keystrokes(&mut self, val: bool) {
         self.selected_square = None;
+        self.selected_region = None;
         self.ignore_keystrokes = val;
     }
 
     pub fn set_no_selected_square(&mut self) {
         self.selected_square = None;
+        self.selected_region = None;
+    }
+
+    // The selected rectangle as (x, y, width, height), or None without one.
+    pub fn selected_region(&self) -> Option<(u32, u32, u32, u32)> {
+        let ((ax,ay),(bx,by)) = self.selected_region?;
+        Some((ax.min(bx), ay.min(by), ax.max(bx) - ax.min(bx) + 1, ay.max(by) - ay.min(by) + 1))
+    }
+
+    pub fn select_region(&mut self, x: u32, y: u32, width: u32, height: u32) {
+        let far = ((x + width).min(self.width) - 1, (y + height).min(self.height) - 1);
+        self.selected_region = Some(((x,y), far));
+        self.highlighter_cache.clear();
+    }
+
+    fn shift_held(&self) -> bool {
+        self.lshift_held || self.rshift_held
+    }
+
+    // Moves the free corner of the selected rectangle, starting one at the selected square if there isn't one yet.
+    fn extend_region(&mut self, dx: i32, dy: i32) -> bool {
+        let (anchor, (ex,ey)) = match (self.selected_region, self.selected_square) {
+            (Some(r), _) => r,
+            (None, Some(sq)) => (sq, sq),
+            (None, None) => { return false; },
+        };
+        let nx = (ex as i32 + dx).max(0).min(self.width as i32 - 1) as u32;
+        let ny = (ey as i32 + dy).max(0).min(self.height as i32 - 1) as u32;
+        self.selected_region = Some((anchor, (nx,ny)));
+        true
+    }
+
+    pub fn selected_square(&self) -> Option<(u32, u32, puzzle_backend::EntryVariant)> {
+        self.selected_square.map(|(x,y)| (x, y, self.selected_variant))
+    }
+
+    // The entry the cursor is in, as (label, variant).
+    pub fn selected_entry(&self) -> Option<(u32, puzzle_backend::EntryVariant)> {
+        let (x,y) = self.selected_square?;
+        let backend = self.backend.borrow();
+        let (a,d) = backend.get_clue_entries(x,y);
+        let entry = match self.selected_variant {
+            puzzle_backend::EntryVariant::Across => a,
+            puzzle_backend::EntryVariant::Down => d,
+        };
+        entry.map(|e| (e.label, e.variant))
+    }
+
+    // Forces a full redraw after the backend was changed from outside the canvas.
+    pub fn invalidate(&mut self) {
+        // Transforming the grid can swap its sides over.
+        let (w, h) = (self.backend.borrow().width() as u32, self.backend.borrow().height() as u32);
+        if (w, h) != (self.width, self.height) {
+            self.width = w;
+            self.height = h;
+            self.grid_info = GridUIInfo::new(&Rectangle::with_size(Size::new(1.0,1.0)), w, h);
+            self.hovered_square = None;
+            self.selected_square = None;
+            self.selected_region = None;
+        }
+        self.grid_cache.clear();
+        self.label_cache.clear();
+        self.content_cache.clear();
+        self.modifier_cache.clear();
+        self.highlighter_cache.clear();
+        self.clues_cache.clear();
+        self.solved_highlight_cache.clear();
+        self.validation_cache.clear();
+        self.marks_cache.clear();
+    }
+
+    pub fn set_flagged_squares(&mut self, flagged: Vec<bool>) {
+        self.flagged_squares = flagged;
+        self.validation_cache.clear();
     }
 }
 
-fn project_cursor_into_square(cursor_pos: &Point, sq_width: &f32, grid_dim: &u32) -> Option<(u32,u32)> {
+fn project_cursor_into_square(cursor_pos: &Point, sq_width: &f32, grid_width: &u32, grid_height: &u32) -> Option<(u32,u32)> {
     let t_x: i64 = (cursor_pos.x / sq_width).floor() as i64;
     let t_y: i64 = (cursor_pos.y / sq_width).floor() as i64;
-    match ((t_x < (*grid_dim).into()) && (t_x >= 0)) && ((t_y < (*grid_dim).into()) && (t_y >= 0)) {
+    match ((t_x < (*grid_width).into()) && (t_x >= 0)) && ((t_y < (*grid_height).into()) && (t_y >= 0)) {
         true => Some((t_x as u32,t_y as u32)),
         false => None,
     }
@@ -172,11 +261,15 @@ fn project_cursor_into_square(cursor_pos: &Point, sq_width: &f32, grid_dim: &u32
 type Message = central_ui::Message;
 impl canvas::Program<Message> for PuzzleCanvas {
     fn update(&mut self, event: Event, bounds: Rectangle, cursor: Cursor) -> (event::Status, Option<Message>) {
-        let mut ui_updated = self.grid_info.update(&bounds,self.dim);
+        let mut ui_updated = self.grid_info.update(&bounds,self.width,self.height);
         let mut e = event::Status::Captured;
-        let mut m: Option<Message> = None;
+        let mut msg: Option<Message> = None;
+        let prev_entry = self.selected_entry();
         match event {
             Event::Mouse(mouse_event) => {
+                if let mouse::Event::ButtonReleased(mouse::Button::Left) = mouse_event {
+                    self.dragging = false;
+                }
                 if cursor.position_in(&bounds).is_none() {
                     return (event::Status::Ignored,None)
                 }
@@ -184,45 +277,61 @@ impl canvas::Program<Message> for PuzzleCanvas {
                     mouse::Event::CursorMoved { .. } => {
                         if let Some(position) = cursor.position_in(&bounds) {
                             self.cursor_pos = position;
-                            let new_sq = project_cursor_into_square(&self.cursor_pos, &self.grid_info.square_width, &self.dim);
+                            let new_sq = project_cursor_into_square(&self.cursor_pos, &self.grid_info.layout.square_width, &self.width, &self.height);
                             if self.hovered_square != new_sq {
                                 self.hovered_square = new_sq;
                                 self.highlighter_cache.clear();
                                 self.clues_cache.clear();
+                                if let (true, Some(sq), Some((anchor,_e))) = (self.dragging, new_sq, self.selected_region) {
+                                    self.selected_region = Some((anchor, sq));
+                                }
                             }
                         } else {
                             e = event::Status::Ignored;
                         }
                     },
                     mouse::Event::ButtonPressed(mouse::Button::Left) => {
-                        if self.lctrl_held || self.rctrl_held {
+                        if self.selected_region.is_some() && !self.shift_held() {
+                            self.selected_region = None;
+                            self.highlighter_cache.clear();
+                        }
+                        if self.shift_held() {
+                            // Shift-click extends from the selected square, shift-drag also works from nothing.
+                            if let Some(sq) = self.hovered_square {
+                                let anchor = match (self.selected_region, self.selected_square) {
+                                    (Some((a,_e)), _) => a,
+                                    (None, Some(s)) => s,
+                                    (None, None) => sq,
+                                };
+                                self.selected_region = Some((anchor, sq));
+                                self.dragging = true;
+                                self.highlighter_cache.clear();
+                            } else {
+                                e = event::Status::Ignored;
+                            }
+                        } else if self.lctrl_held || self.rctrl_held {
                             if let Some((tx,ty)) = self.hovered_square {
                                 let did_modify_sq = self.backend.borrow_mut().cycle_modifier(tx,ty);
                                 ui_updated = did_modify_sq;
                             }
-                        } else {
-                            if self.hovered_square == None {
-                                e = event::Status::Ignored;
+                        } else if let Some((hx,hy)) = self.hovered_square {
+                            if self.hovered_square == self.selected_square {
+                                self.selected_variant = match self.selected_variant {
+                                    puzzle_backend::EntryVariant::Across => puzzle_backend::EntryVariant::Down,
+                                    puzzle_backend::EntryVariant::Down => puzzle_backend::EntryVariant::Across,
+                                }
                             } else {
-                                if self.hovered_square == self.selected_square {
-                                    self.selected_variant = match self.selected_variant {
-                                        puzzle_backend::EntryVariant::Across => puzzle_backend::EntryVariant::Down,
-                                        puzzle_backend::EntryVariant::Down => puzzle_backend::EntryVariant::Across,
-                                    }
-                                } else {
-                                    // Can unwrap because we have already handled None case.
-                                    let (hx,hy) = self.hovered_square.unwrap();
-                                    match self.backend.borrow().at(hx,hy).content {
-                                        puzzle_backend::SquareContents::TextContent(_,_) => {
-                                            self.selected_square = self.hovered_square;
-                                            ui_updated = true;
-                                        }
-                                        _ => {}
-                                    }
+                                let is_text = matches!(self.backend.borrow().at(hx,hy).content, puzzle_backend::SquareContents::TextContent(_,_));
+                                if is_text {
+                                    self.selected_square = self.hovered_square;
+                                    self.backend.borrow_mut().end_edit_group();
+                                    ui_updated = true;
                                 }
-                                self.clues_cache.clear();
-                                self.highlighter_cache.clear();
-                            }   
+                            }
+                            self.clues_cache.clear();
+                            self.highlighter_cache.clear();
+                        } else {
+                            e = event::Status::Ignored;
                         }
                     },
                     mouse::Event::ButtonPressed(mouse::Button::Right) => {
@@ -234,9 +343,9 @@ impl canvas::Program<Message> for PuzzleCanvas {
                             },
                             None => {
                                 if let Some((tx,ty)) = self.hovered_square {
-                                    self.backend.borrow_mut().cycle_blocker(tx,ty,false);
+                                    self.backend.borrow_mut().cycle_blocker(tx,ty);
                                     ui_updated = true;
-                                    m = Some(Message::CluesUpdated);
+                                    msg = Some(Message::CluesUpdated);
                                 } else {
                                     e = event::Status::Ignored;
                                 }
@@ -250,11 +359,59 @@ impl canvas::Program<Message> for PuzzleCanvas {
                 if !self.ignore_keystrokes {
                     match keyboard_event {
                         keyboard::Event::KeyPressed { key_code: kc, modifiers: m }  => {
+                            // Moving the cursor ends the current typing run for undo purposes.
+                            if matches!(kc, iced::keyboard::KeyCode::Escape | iced::keyboard::KeyCode::Tab
+                                | iced::keyboard::KeyCode::Up | iced::keyboard::KeyCode::Down
+                                | iced::keyboard::KeyCode::Left | iced::keyboard::KeyCode::Right) {
+                                self.backend.borrow_mut().end_edit_group();
+                            }
+                            // Anything but a region shortcut or another shift-arrow lets go of the region.
+                            let region_key = (m.shift && matches!(kc, iced::keyboard::KeyCode::Up | iced::keyboard::KeyCode::Down
+                                | iced::keyboard::KeyCode::Left | iced::keyboard::KeyCode::Right))
+                                || (m.control && matches!(kc, iced::keyboard::KeyCode::C | iced::keyboard::KeyCode::X | iced::keyboard::KeyCode::V))
+                                || matches!(kc, iced::keyboard::KeyCode::Delete | iced::keyboard::KeyCode::Backspace
+                                | iced::keyboard::KeyCode::LShift | iced::keyboard::KeyCode::RShift
+                                | iced::keyboard::KeyCode::LControl | iced::keyboard::KeyCode::RControl);
+                            let had_region = self.selected_region.is_some();
+                            if had_region && !region_key {
+                                self.selected_region = None;
+                                self.highlighter_cache.clear();
+                            }
                             match kc {
                                 iced::keyboard::KeyCode::Escape => {
                                     self.selected_square = None;
                                     self.highlighter_cache.clear();
                                 },
+                                iced::keyboard::KeyCode::LShift => {
+                                    self.lshift_held = true;
+                                },
+                                iced::keyboard::KeyCode::RShift => {
+                                    self.rshift_held = true;
+                                },
+                                iced::keyboard::KeyCode::C if m.control && had_region => {
+                                    msg = Some(Message::CopyRegion);
+                                },
+                                iced::keyboard::KeyCode::X if m.control && had_region => {
+                                    msg = Some(Message::CutRegion);
+                                },
+                                iced::keyboard::KeyCode::V if m.control && had_region => {
+                                    msg = Some(Message::PasteRegion);
+                                },
+                                iced::keyboard::KeyCode::Delete | iced::keyboard::KeyCode::Backspace if had_region => {
+                                    msg = Some(Message::ClearRegion);
+                                },
+                                iced::keyboard::KeyCode::Up if m.shift => {
+                                    ui_updated = self.extend_region(0, -1);
+                                },
+                                iced::keyboard::KeyCode::Down if m.shift => {
+                                    ui_updated = self.extend_region(0, 1);
+                                },
+                                iced::keyboard::KeyCode::Left if m.shift => {
+                                    ui_updated = self.extend_region(-1, 0);
+                                },
+                                iced::keyboard::KeyCode::Right if m.shift => {
+                                    ui_updated = self.extend_region(1, 0);
+                                },
                                 iced::keyboard::KeyCode::LControl => {
                                     self.lctrl_held = true;
                                 },
@@ -312,7 +469,7 @@ impl canvas::Program<Message> for PuzzleCanvas {
                                     if let Some((tx,ty)) = self.selected_square {
                                         match self.selected_variant {
                                             puzzle_backend::EntryVariant::Down => {
-                                                if ty < self.dim - 1 {
+                                                if ty < self.height - 1 {
                                                     self.selected_square = Some((tx,ty+1));
                                                     ui_updated = true;
                                                 }
@@ -344,7 +501,7 @@ impl canvas::Program<Message> for PuzzleCanvas {
                                     if let Some((tx,ty)) = self.selected_square {
                                         match self.selected_variant {
                                             puzzle_backend::EntryVariant::Across => {
-                                                if tx < self.dim - 1 {
+                                                if tx < self.width - 1 {
                                                     self.selected_square = Some((tx+1,ty));
                                                     ui_updated = true;
                                                 }
@@ -451,6 +608,22 @@ impl canvas::Program<Message> for PuzzleCanvas {
                                         ui_updated = true;
                                     }
                                 },
+                                iced::keyboard::KeyCode::Z if m.control => {
+                                    let changed = if m.shift {
+                                        self.backend.borrow_mut().redo()
+                                    } else {
+                                        self.backend.borrow_mut().undo()
+                                    };
+                                    if changed {
+                                        if let Some((sx,sy)) = self.selected_square {
+                                            if let puzzle_backend::SquareContents::Blocker = self.backend.borrow().at(sx,sy).content {
+                                                self.selected_square = None;
+                                            }
+                                        }
+                                        ui_updated = true;
+                                        msg = Some(Message::CluesUpdated);
+                                    }
+                                },
                                 _ => {
                                     if let Some(c) = match_keycode_to_char(&kc) {
                                         if let Some((tx,ty)) = self.selected_square {
@@ -487,6 +660,12 @@ impl canvas::Program<Message> for PuzzleCanvas {
                                 iced::keyboard::KeyCode::RControl => {
                                     self.rctrl_held = false;
                                 },
+                                iced::keyboard::KeyCode::LShift => {
+                                    self.lshift_held = false;
+                                },
+                                iced::keyboard::KeyCode::RShift => {
+                                    self.rshift_held = false;
+                                },
                                 _ => {
                                     e = event::Status::Ignored;
                                 },
@@ -499,16 +678,15 @@ impl canvas::Program<Message> for PuzzleCanvas {
         };
 
         if ui_updated {
-            self.grid_cache.clear();
-            self.label_cache.clear();
-            self.content_cache.clear();
-            self.modifier_cache.clear();
-            self.highlighter_cache.clear();
-            self.clues_cache.clear();
-            self.solved_highlight_cache.clear();
+            self.invalidate();
         }
 
-        (e, m)
+        // Let the rest of the UI know the grid or the selected entry changed.
+        if msg.is_none() && (ui_updated || prev_entry != self.selected_entry()) {
+            msg = Some(Message::GridChanged);
+        }
+
+        (e, msg)
     }
 
     fn draw(&self, bounds: Rectangle, _cursor: Cursor,) -> Vec<canvas::Geometry> {
@@ -519,22 +697,23 @@ impl canvas::Program<Message> for PuzzleCanvas {
             false  => canv_h,
         }; 
         let uncached_frame_grid_info = if min_size != self.grid_info.min_size {
-            Some(GridUIInfo::new(&bounds,self.dim))
+            Some(GridUIInfo::new(&bounds,self.width,self.height))
         } else {
             None
         };
 
         let frame_grid_info = match &uncached_frame_grid_info {
-            Some(g) => &g,
+            Some(g) => g,
             None => &self.grid_info,
         };
 
         let grid = self.grid_cache.draw(bounds.size(), |frame| {
-            let dark_bg = Path::rectangle(Point::new(0.0,0.0), Size::new(frame_grid_info.square_width * self.dim as f32,frame_grid_info.square_width * self.dim as f32));
+            let (grid_w, grid_h) = frame_grid_info.layout.grid_size();
+            let dark_bg = Path::rectangle(Point::new(0.0,0.0), Size::new(grid_w, grid_h));
             frame.fill(&dark_bg, Color::BLACK);
             for sq in &frame_grid_info.frame_square_infos {
                 if let puzzle_backend::SquareContents::TextContent(_s,m) = &self.backend.borrow().at(sq.x, sq.y).content {
-                    let sq_path = Path::rectangle(sq.content_top_left_corner,Size::new(frame_grid_info.content_width,frame_grid_info.content_width));
+                    let sq_path = Path::rectangle(sq.content_top_left_corner,Size::new(frame_grid_info.layout.content_width,frame_grid_info.layout.content_width));
                     let color = match m {
                         Some(puzzle_backend::SquareModifier::Shading) => Color::from_rgba(0.7, 0.7, 0.7, 1.0),
                         _ => Color::WHITE,
@@ -550,7 +729,7 @@ impl canvas::Program<Message> for PuzzleCanvas {
                     let text = Text {
                         color: Color::BLACK,
                         position: sq.content_top_left_corner,
-                        size: frame_grid_info.label_size,
+                        size: frame_grid_info.layout.label_size,
                         content: l.to_string(),
                         ..Text::default()
                     };
@@ -562,11 +741,7 @@ impl canvas::Program<Message> for PuzzleCanvas {
         let content = self.content_cache.draw(bounds.size(), |frame| {
             for sq in &frame_grid_info.frame_square_infos {
                 if let puzzle_backend::SquareContents::TextContent(s,_m) = &self.backend.borrow().at(sq.x,sq.y).content {
-                    let num_chars = s.len();
-                    let sq_text_size = match num_chars {
-                        1 => frame_grid_info.content_width,
-                        _ => 1.2 * frame_grid_info.content_width / num_chars as f32,
-                    };
+                    let sq_text_size = frame_grid_info.layout.content_text_size(s);
 
                     let text = Text {
                         color: Color::BLACK,
@@ -585,47 +760,47 @@ impl canvas::Program<Message> for PuzzleCanvas {
         let modifiers = self.modifier_cache.draw(bounds.size(), |frame| {
             let stroke = Stroke {
                 color: Color::BLACK,
-                width: frame_grid_info.content_width * 0.03,
+                width: frame_grid_info.layout.circle_stroke_width(),
                 ..Default::default()
             };
             for sq in &frame_grid_info.frame_square_infos {
                 if let puzzle_backend::SquareContents::TextContent(_s,Some(puzzle_backend::SquareModifier::Circle)) = &self.backend.borrow().at(sq.x,sq.y).content {
-                    frame.stroke(&Path::circle(sq.center, 0.92 * frame_grid_info.content_width / 2.0),stroke);
+                    frame.stroke(&Path::circle(sq.center, frame_grid_info.layout.circle_radius()),stroke);
                 }
             }
         });
 
         let highlighter = self.highlighter_cache.draw(bounds.size(), |frame| {
+            if let Some((rx,ry,rw,rh)) = self.selected_region() {
+                let corner = frame_grid_info.square_info(rx,ry).content_top_left_corner;
+                let far = frame_grid_info.square_info(rx + rw - 1,ry + rh - 1).content_top_left_corner;
+                let size = Size::new(far.x - corner.x + frame_grid_info.layout.content_width, far.y - corner.y + frame_grid_info.layout.content_width);
+                frame.fill(&Path::rectangle(corner, size), Color::from_rgba(0.5,0.0,1.0,0.25));
+            }
             match self.selected_square {
                 None => {
-                    if let Some((sx,sy)) = project_cursor_into_square(&self.cursor_pos,&frame_grid_info.square_width, &self.dim) {
+                    if let Some((sx,sy)) = project_cursor_into_square(&self.cursor_pos,&frame_grid_info.layout.square_width, &self.width, &self.height) {
                         let r_path = Path::rectangle(
-                                frame_grid_info.frame_square_infos[sx as usize * self.dim as usize + sy as usize].content_top_left_corner,
-                                Size::new(frame_grid_info.content_width,frame_grid_info.content_width)
+                                frame_grid_info.square_info(sx,sy).content_top_left_corner,
+                                Size::new(frame_grid_info.layout.content_width,frame_grid_info.layout.content_width)
                             );
                         let r_c = Color::from_rgba(0.0,0.0,1.0,0.2);
                         frame.fill(&r_path,r_c);
-                        match self.backend.borrow().variant {
-                            puzzle_backend::PuzzleType::Weekday | puzzle_backend::PuzzleType::Sunday => {
-                                if (sx != self.dim / 2 as u32) || (sy != self.dim / 2 as u32) {
-                                    let index = (self.dim - sx - 1) as usize * self.dim as usize + (self.dim - sy - 1) as usize;
-                                    let s_path = Path::rectangle(
-                                        frame_grid_info.frame_square_infos[index].content_top_left_corner,
-                                        Size::new(frame_grid_info.content_width, frame_grid_info.content_width)
-                                    );
-                                    let s_c = Color::from_rgba(0.0, 0.0, 1.0, 0.2);
-                                    frame.fill(&s_path,s_c);
-                                }
-                            },
-                            _ => (),
+                        for (sym_x, sym_y) in self.backend.borrow().symmetric_partners(sx, sy) {
+                            let s_path = Path::rectangle(
+                                frame_grid_info.square_info(sym_x,sym_y).content_top_left_corner,
+                                Size::new(frame_grid_info.layout.content_width, frame_grid_info.layout.content_width)
+                            );
+                            let s_c = Color::from_rgba(0.0, 0.0, 1.0, 0.2);
+                            frame.fill(&s_path,s_c);
                         }
                     } 
                 },
                 Some((hx,hy)) => {
                     // Fill Selected with green
                     let r_path = Path::rectangle(
-                        frame_grid_info.frame_square_infos[hx as usize * self.dim as usize + hy as usize].content_top_left_corner,
-                        Size::new(frame_grid_info.content_width,frame_grid_info.content_width)
+                        frame_grid_info.square_info(hx,hy).content_top_left_corner,
+                        Size::new(frame_grid_info.layout.content_width,frame_grid_info.layout.content_width)
                     );
                     let r_c = Color::from_rgba(0.0,1.0,0.0,0.5);
                     frame.fill(&r_path,r_c);
@@ -642,18 +817,15 @@ impl canvas::Program<Message> for PuzzleCanvas {
                     } else {
                         None
                     };
-                    match entries {
-                        Some(v) => {
-                            for sq_index in v {
-                                let sq = &self.backend.borrow().squares[sq_index];
-                                let r_path = Path::rectangle(
-                                    frame_grid_info.frame_square_infos[(sq.x * self.dim + sq.y) as usize].content_top_left_corner,
-                                    Size::new(frame_grid_info.content_width,frame_grid_info.content_width));
-                                let r_c = Color::from_rgba(1.0,1.0,0.0,0.3);
-                                frame.fill(&r_path,r_c);
-                            }
-                        },
-                        None => {},
+                    if let Some(v) = entries {
+                        for sq_index in v {
+                            let sq = &self.backend.borrow().squares[sq_index];
+                            let r_path = Path::rectangle(
+                                frame_grid_info.square_info(sq.x,sq.y).content_top_left_corner,
+                                Size::new(frame_grid_info.layout.content_width,frame_grid_info.layout.content_width));
+                            let r_c = Color::from_rgba(1.0,1.0,0.0,0.3);
+                            frame.fill(&r_path,r_c);
+                        }
                     }
                },
             };
@@ -740,13 +912,57 @@ impl canvas::Program<Message> for PuzzleCanvas {
             if self.backend.borrow().is_solved() {
                 let color = Color::from_rgba(0.0,1.0,0.0,0.3);
                 for sq in &frame_grid_info.frame_square_infos {
-                    let sq_path = Path::rectangle(sq.content_top_left_corner,Size::new(frame_grid_info.content_width,frame_grid_info.content_width));
+                    let sq_path = Path::rectangle(sq.content_top_left_corner,Size::new(frame_grid_info.layout.content_width,frame_grid_info.layout.content_width));
                     frame.fill(&sq_path, color);
                 };
             }
         });
         
-        vec![grid,labels,content,modifiers,highlighter,clues,solved]
+        let flagged = self.validation_cache.draw(bounds.size(), |frame| {
+            let color = Color::from_rgba(1.0,0.0,0.0,0.35);
+            for sq in &frame_grid_info.frame_square_infos {
+                let index = sq.y as usize * self.width as usize + sq.x as usize;
+                if self.flagged_squares.get(index) == Some(&true) {
+                    let sq_path = Path::rectangle(sq.content_top_left_corner,Size::new(frame_grid_info.layout.content_width,frame_grid_info.layout.content_width));
+                    frame.fill(&sq_path, color);
+                }
+            };
+        });
+        
+        let marks = self.marks_cache.draw(bounds.size(), |frame| {
+            let red = Color::from_rgb(0.8, 0.0, 0.0);
+            let stroke = Stroke {
+                color: red,
+                width: frame_grid_info.layout.content_width * 0.05,
+                ..Default::default()
+            };
+            let backend = self.backend.borrow();
+            for sq in &frame_grid_info.frame_square_infos {
+                let index = sq.y as usize * self.width as usize + sq.x as usize;
+                let corner = sq.content_top_left_corner;
+                let w = frame_grid_info.layout.content_width;
+                match backend.marks.get(index) {
+                    Some(Some(puzzle_backend::SolveMark::Wrong)) => {
+                        // Slash through wrong answers.
+                        let slash = Path::line(Point::new(corner.x, corner.y + w), Point::new(corner.x + w, corner.y));
+                        frame.stroke(&slash, stroke);
+                    },
+                    Some(Some(puzzle_backend::SolveMark::Revealed)) => {
+                        // Dog-ear the top right corner of revealed squares.
+                        let ear = Path::new(|p| {
+                            p.move_to(Point::new(corner.x + 0.7 * w, corner.y));
+                            p.line_to(Point::new(corner.x + w, corner.y));
+                            p.line_to(Point::new(corner.x + w, corner.y + 0.3 * w));
+                            p.close();
+                        });
+                        frame.fill(&ear, red);
+                    },
+                    _ => (),
+                }
+            }
+        });
+        
+        vec![grid,labels,content,modifiers,highlighter,clues,solved,flagged,marks]
     }
 }
 
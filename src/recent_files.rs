@@ -0,0 +1,47 @@
+// Files recently opened or saved, newest first. The list is kept between
+// sessions as one path per line in the user's config directory.
+
+const MAX_RECENT_FILES: usize = 8;
+
+pub struct RecentFiles {
+    paths: Vec<String>,
+}
+
+fn store_path() -> Option<std::path::PathBuf> {
+    dirs::config_dir().map(|d| d.join("crosser").join("recent_files.txt"))
+}
+
+impl RecentFiles {
+    // Files that have been moved or deleted since are dropped.
+    pub fn load() -> Self {
+        let contents = store_path().and_then(|p| std::fs::read_to_string(p).ok()).unwrap_or_default();
+        let paths = contents.lines()
+            .filter(|l| std::path::Path::new(l).is_file())
+            .take(MAX_RECENT_FILES)
+            .map(|l| l.to_string())
+            .collect();
+        RecentFiles { paths }
+    }
+
+    pub fn paths(&self) -> &[String] {
+        &self.paths
+    }
+
+    pub fn add(&mut self, path: &str) {
+        let path = std::fs::canonicalize(path).ok().and_then(|p| p.to_str().map(|s| s.to_string())).unwrap_or_else(|| path.to_string());
+        self.paths.retain(|p| *p != path);
+        self.paths.insert(0, path);
+        self.paths.truncate(MAX_RECENT_FILES);
+        self.save();
+    }
+
+    // The list is a convenience, so failing to store it isn't worth bothering the user about.
+    fn save(&self) {
+        if let Some(store) = store_path() {
+            if let Some(dir) = store.parent() {
+                let _ = std::fs::create_dir_all(dir);
+            }
+            let _ = std::fs::write(store, self.paths.join("\n"));
+        }
+    }
+}
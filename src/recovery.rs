@@ -0,0 +1,184 @@
+use crosser_core::puzzle_backend;
+use crosser_core::puzzle_file_io;
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, OnceLock};
+
+// Unsaved work in each tab is autosaved to a recovery file, which is removed
+// again once the work is saved or thrown away on purpose. Finding any at
+// launch means the last session ended some other way. Work from a file keeps
+// that file's path beside it, so it can be saved back there once restored.
+//
+// Several copies of the app can run at once, so every file is named after the
+// instance that wrote it, and each instance holds a lock on its own lock file
+// for as long as it runs. Only files whose owner's lock is free are recovered.
+
+// This instance, and the files it took over from instances that are gone.
+struct Owner {
+    key: String,
+    _lock: Option<std::fs::File>,
+    claimed: Mutex<Claimed>,
+}
+
+#[derive(Default)]
+struct Claimed {
+    files: Vec<std::path::PathBuf>,
+    locks: Vec<(std::path::PathBuf, std::fs::File)>,
+}
+
+fn recovery_dir() -> Option<std::path::PathBuf> {
+    dirs::data_local_dir().map(|d| d.join("crosser"))
+}
+
+fn owner() -> &'static Owner {
+    static OWNER: OnceLock<Owner> = OnceLock::new();
+    OWNER.get_or_init(|| {
+        // The start time keeps the key unique when process ids get reused.
+        let started = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0);
+        let key = format!("{}_{}", std::process::id(), started);
+        if let Some(dir) = recovery_dir() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        let lock = lock_path(&key).and_then(|p| try_lock(&p));
+        Owner { key, _lock: lock, claimed: Default::default() }
+    })
+}
+
+fn lock_path(key: &str) -> Option<std::path::PathBuf> {
+    recovery_dir().map(|d| d.join(format!("recovery-{}.lock", key)))
+}
+
+fn recovery_path(tab_id: usize) -> Option<std::path::PathBuf> {
+    recovery_dir().map(|d| d.join(format!("recovery-{}-{}.cro", owner().key, tab_id)))
+}
+
+// The lock, or None while another instance holds it. Where files can't be
+// locked at all, every instance's files count as left behind.
+fn try_lock(path: &std::path::Path) -> Option<std::fs::File> {
+    let file = std::fs::OpenOptions::new().create(true).truncate(false).write(true).open(path).ok()?;
+    match file.try_lock() {
+        Ok(()) => Some(file),
+        Err(std::fs::TryLockError::WouldBlock) => None,
+        Err(std::fs::TryLockError::Error(_e)) => Some(file),
+    }
+}
+
+// Recovery files are "recovery-KEY-TAB.cro", the paths of the files they came
+// from "recovery-KEY-TAB.path", and lock files "recovery-KEY.lock".
+fn file_owner(path: &std::path::Path) -> Option<String> {
+    let name = path.file_name().and_then(|n| n.to_str())?;
+    let stem = name.strip_prefix("recovery-")?.strip_suffix(".cro")?;
+    stem.rsplit_once('-').map(|(key, _tab)| key.to_string())
+}
+
+fn origin_path(recovery_file: &std::path::Path) -> std::path::PathBuf {
+    recovery_file.with_extension("path")
+}
+
+fn lock_owner(path: &std::path::Path) -> Option<String> {
+    let name = path.file_name().and_then(|n| n.to_str())?;
+    name.strip_prefix("recovery-")?.strip_suffix(".lock").map(|k| k.to_string())
+}
+
+fn dir_entries() -> Vec<std::path::PathBuf> {
+    let entries = match recovery_dir().and_then(|d| std::fs::read_dir(d).ok()) {
+        Some(e) => e,
+        None => { return Vec::new(); },
+    };
+    let mut files: Vec<std::path::PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+    files.sort();
+    files
+}
+
+pub fn save(tab_id: usize, puzzle: &puzzle_backend::Puzzle, file_path: Option<&str>) -> std::result::Result<(), String> {
+    let path = match recovery_path(tab_id) {
+        Some(p) => p,
+        None => { return Err("No data directory to autosave to.".to_string()); },
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    puzzle_file_io::write_puzzle_to_cro(puzzle, path.to_string_lossy().to_string(), false, false).map_err(|e| e.to_string())?;
+    match file_path {
+        Some(f) => std::fs::write(origin_path(&path), f).map_err(|e| e.to_string()),
+        None => {
+            let _ = std::fs::remove_file(origin_path(&path));
+            Ok(())
+        },
+    }
+}
+
+// Takes over the files of instances that aren't running any more and loads
+// them, each with the path of the file it came from if it had one. Files that
+// can't be read any more are skipped.
+pub fn load_all() -> Vec<(puzzle_backend::Puzzle, Option<String>)> {
+    let own = owner();
+    let entries = dir_entries();
+    let mut by_owner: BTreeMap<String, Vec<std::path::PathBuf>> = BTreeMap::new();
+    for path in entries.iter() {
+        if let Some(key) = file_owner(path) {
+            by_owner.entry(key).or_default().push(path.clone());
+        }
+    }
+
+    let mut claimed = own.claimed.lock().unwrap();
+    for (key, files) in by_owner {
+        if key == own.key {
+            continue;
+        }
+        match lock_path(&key) {
+            Some(p) if p.is_file() => {
+                if let Some(lock) = try_lock(&p) {
+                    claimed.files.extend(files);
+                    claimed.locks.push((p, lock));
+                }
+            },
+            // An owner that never took its lock can't be told apart from one long gone.
+            _ => claimed.files.extend(files),
+        }
+    }
+
+    // Instances that closed cleanly leave only their lock file behind.
+    for path in entries.iter() {
+        let key = match lock_owner(path) {
+            Some(k) => k,
+            None => { continue; },
+        };
+        let in_use = key == own.key || claimed.locks.iter().any(|(p,_l)| p == path) || entries.iter().any(|e| file_owner(e).as_ref() == Some(&key));
+        if !in_use {
+            if let Some(lock) = try_lock(path) {
+                drop(lock);
+                let _ = std::fs::remove_file(path);
+            }
+        }
+    }
+
+    claimed.files.iter()
+        .filter_map(|p| {
+            let puzzle = puzzle_file_io::get_puzzle_from_cro(p.to_string_lossy().to_string()).ok()?;
+            Some((puzzle, std::fs::read_to_string(origin_path(p)).ok()))
+        })
+        .collect()
+}
+
+pub fn clear(tab_id: usize) {
+    if let Some(path) = recovery_path(tab_id) {
+        let _ = std::fs::remove_file(origin_path(&path));
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+// Removes the files taken over from other instances once they have been
+// restored or thrown away. This instance's own files are left to its tabs.
+pub fn clear_recovered() {
+    let own = owner();
+    let mut claimed = own.claimed.lock().unwrap();
+    for path in std::mem::take(&mut claimed.files) {
+        let _ = std::fs::remove_file(origin_path(&path));
+        let _ = std::fs::remove_file(path);
+    }
+    for (path, lock) in std::mem::take(&mut claimed.locks) {
+        drop(lock);
+        let _ = std::fs::remove_file(path);
+    }
+}
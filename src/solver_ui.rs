@@ -0,0 +1,140 @@
+use iced::{Align, button, Button, Column, Element, Row, Text};
+use crate::central_ui;
+use crosser_core::puzzle_backend;
+
+use std::time::{Duration, Instant};
+
+// Elapsed solving time, which only counts while running.
+pub struct SolveClock {
+    elapsed: Duration,
+    running_since: Option<Instant>,
+}
+
+impl SolveClock {
+    pub fn new() -> Self {
+        SolveClock {
+            elapsed: Duration::from_secs(0),
+            running_since: Some(Instant::now()),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running_since.is_some()
+    }
+
+    pub fn pause(&mut self) {
+        if let Some(start) = self.running_since.take() {
+            self.elapsed += start.elapsed();
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if self.running_since.is_none() {
+            self.running_since = Some(Instant::now());
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        match self.running_since {
+            Some(start) => self.elapsed + start.elapsed(),
+            None => self.elapsed,
+        }
+    }
+}
+
+pub fn format_elapsed(d: Duration) -> String {
+    let secs = d.as_secs();
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, (secs / 60) % 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+pub struct SolverRow {
+    pub clock: SolveClock,
+    // Set once the grid is solved, with the final time.
+    pub completed: Option<Duration>,
+    pub checks_used: u32,
+    pub status: String,
+    pause_but: button::State,
+    check_square_but: button::State,
+    check_word_but: button::State,
+    check_puzzle_but: button::State,
+    reveal_square_but: button::State,
+    reveal_word_but: button::State,
+    reveal_puzzle_but: button::State,
+}
+
+impl SolverRow {
+    pub fn new() -> Self {
+        SolverRow {
+            clock: SolveClock::new(),
+            completed: None,
+            checks_used: 0,
+            status: String::new(),
+            pause_but: Default::default(),
+            check_square_but: Default::default(),
+            check_word_but: Default::default(),
+            check_puzzle_but: Default::default(),
+            reveal_square_but: Default::default(),
+            reveal_word_but: Default::default(),
+            reveal_puzzle_but: Default::default(),
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.completed.is_none() && !self.clock.is_running()
+    }
+
+    pub fn view(&mut self, revealed: usize) -> Element<'_, central_ui::Message> {
+        if let Some(time) = self.completed {
+            let mut details = format!("Solved in {}.", format_elapsed(time));
+            if self.checks_used > 0 || revealed > 0 {
+                details.push_str(&format!(" Checks used: {}. Squares revealed: {}.", self.checks_used, revealed));
+            }
+            return Column::new()
+            .spacing(5)
+            .push(
+                Text::new("Congratulations!").size(40)
+            )
+            .push(
+                Text::new(details)
+            )
+            .into();
+        }
+
+        let pause_text = if self.clock.is_running() { "Pause" } else { "Resume" };
+        Row::new()
+        .spacing(10)
+        .align_items(Align::Center)
+        .push(
+            Text::new(format_elapsed(self.clock.elapsed())).size(30)
+        )
+        .push(
+            Button::new(&mut self.pause_but, Text::new(pause_text)).on_press(central_ui::Message::TogglePause)
+        )
+        .push(
+            Button::new(&mut self.check_square_but, Text::new("Check square")).on_press(central_ui::Message::Check(puzzle_backend::SolveScope::Square))
+        )
+        .push(
+            Button::new(&mut self.check_word_but, Text::new("Check word")).on_press(central_ui::Message::Check(puzzle_backend::SolveScope::Word))
+        )
+        .push(
+            Button::new(&mut self.check_puzzle_but, Text::new("Check puzzle")).on_press(central_ui::Message::Check(puzzle_backend::SolveScope::Puzzle))
+        )
+        .push(
+            Button::new(&mut self.reveal_square_but, Text::new("Reveal square")).on_press(central_ui::Message::Reveal(puzzle_backend::SolveScope::Square))
+        )
+        .push(
+            Button::new(&mut self.reveal_word_but, Text::new("Reveal word")).on_press(central_ui::Message::Reveal(puzzle_backend::SolveScope::Word))
+        )
+        .push(
+            Button::new(&mut self.reveal_puzzle_but, Text::new("Reveal puzzle")).on_press(central_ui::Message::Reveal(puzzle_backend::SolveScope::Puzzle))
+        )
+        .push(
+            Text::new(self.status.clone())
+        )
+        .into()
+    }
+}
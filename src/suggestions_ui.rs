@@ -0,0 +1,205 @@
+use iced::{button, Button, Element, Length, scrollable, Scrollable, Text};
+use crate::central_ui;
+use crosser_core::puzzle_backend;
+use crosser_core::word_list;
+
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+// Only the best scoring matches are ranked by their crossings, which keeps
+// lookups interactive on large grids.
+const MAX_CANDIDATES: usize = 300;
+const MAX_SHOWN: usize = 100;
+
+// The selected entry and grid contents a set of suggestions was computed for.
+type RefreshKey = (Option<(u32, puzzle_backend::EntryVariant)>, Vec<puzzle_backend::SquareContents>);
+
+pub struct SuggestionsPanel {
+    backend: Rc<RefCell<puzzle_backend::Puzzle>>,
+    scroller: scrollable::State,
+    computed_for: Option<RefreshKey>,
+    heading: String,
+    suggestions: Vec<Suggestion>,
+}
+
+impl SuggestionsPanel {
+    pub fn new(backend: Rc<RefCell<puzzle_backend::Puzzle>>) -> Self {
+        SuggestionsPanel {
+            backend,
+            scroller: Default::default(),
+            computed_for: None,
+            heading: String::new(),
+            suggestions: Vec::new(),
+        }
+    }
+
+    // Forces the next refresh to recompute, e.g. after a new word list was loaded.
+    pub fn invalidate(&mut self) {
+        self.computed_for = None;
+    }
+
+    pub fn refresh(&mut self, words: Option<&word_list::WordList>, entry: Option<(u32, puzzle_backend::EntryVariant)>) {
+        let contents: Vec<puzzle_backend::SquareContents> = self.backend.borrow().squares.iter().map(|s| s.content.clone()).collect();
+        let key = (entry, contents);
+        if self.computed_for.as_ref() == Some(&key) {
+            return;
+        }
+        self.computed_for = Some(key);
+        self.suggestions.clear();
+
+        let words = match words {
+            Some(w) => w,
+            None => {
+                self.heading = "Load a word list from Fill ... to see suggestions.".to_string();
+                return;
+            }
+        };
+        let (label, variant) = match entry {
+            Some(e) => e,
+            None => {
+                self.heading = "Select a square to see suggestions.".to_string();
+                return;
+            }
+        };
+        let backend = self.backend.borrow();
+        if backend.fill_only {
+            self.heading = "Solvable grids have no suggestions.".to_string();
+            return;
+        }
+        let a_or_d = match variant {
+            puzzle_backend::EntryVariant::Across => 'A',
+            puzzle_backend::EntryVariant::Down => 'D',
+        };
+        let members = match find_entry(&backend, label, variant) {
+            Some(e) => e.member_indices.clone(),
+            None => {
+                self.heading = String::new();
+                return;
+            }
+        };
+        let pattern = match entry_pattern(&backend, &members) {
+            Some(p) => p,
+            None => {
+                self.heading = format!("{}{} has a rebus square.", label, a_or_d);
+                return;
+            }
+        };
+
+        // For every empty square, the crossing entry's members and the position of the square in it.
+        let crossing_variant = match variant {
+            puzzle_backend::EntryVariant::Across => puzzle_backend::EntryVariant::Down,
+            puzzle_backend::EntryVariant::Down => puzzle_backend::EntryVariant::Across,
+        };
+        let mut crossings: Vec<(usize, Vec<Option<u8>>, usize)> = Vec::new();
+        for (pos, sq_index) in members.iter().enumerate() {
+            if pattern[pos].is_some() {
+                continue;
+            }
+            let sq = &backend.squares[*sq_index];
+            let crossing_label = match crossing_variant {
+                puzzle_backend::EntryVariant::Across => sq.across_entry,
+                puzzle_backend::EntryVariant::Down => sq.down_entry,
+            };
+            if let Some(c) = crossing_label.and_then(|l| find_entry(&backend, l, crossing_variant)) {
+                // Crossings through rebus squares can't be looked up, so they don't count.
+                if let Some(p) = entry_pattern(&backend, &c.member_indices) {
+                    let crossing_pos = c.member_indices.iter().position(|i| i == sq_index).unwrap_or(0);
+                    crossings.push((pos, p, crossing_pos));
+                }
+            }
+        }
+
+        let mut option_counts: HashMap<(usize, u8), usize> = HashMap::new();
+        let mut ranked: Vec<(u32, usize)> = Vec::new();
+        for id in words.matching(&pattern).into_iter().take(MAX_CANDIDATES) {
+            let letters = words.letters(id);
+            let mut fewest = usize::MAX;
+            for (pos, crossing_pattern, crossing_pos) in crossings.iter() {
+                let count = *option_counts.entry((*pos, letters[*pos])).or_insert_with(|| {
+                    let mut p = crossing_pattern.clone();
+                    p[*crossing_pos] = Some(letters[*pos]);
+                    words.matching(&p).len()
+                });
+                fewest = fewest.min(count);
+            }
+            ranked.push((id, fewest));
+        }
+        // Words that leave a crossing with no options sink to the bottom; the
+        // rest go by score, then by how open they leave the crossings.
+        ranked.sort_by_key(|(id, fewest)| (*fewest == 0, std::cmp::Reverse(words.score(*id)), std::cmp::Reverse(*fewest)));
+
+        self.heading = format!("Suggestions for {}{}:", label, a_or_d);
+        if ranked.is_empty() {
+            self.heading.push_str(" none");
+        }
+        for (id, fewest) in ranked.into_iter().take(MAX_SHOWN) {
+            let crossing_options = if crossings.is_empty() { None } else { Some(fewest) };
+            self.suggestions.push(Suggestion::new(label, variant, words.word(id).to_string(), words.score(id), crossing_options));
+        }
+    }
+
+    pub fn view(&mut self) -> Element<'_, central_ui::Message> {
+        let heading = Text::new(self.heading.clone());
+        self.suggestions.iter_mut().fold(
+            Scrollable::new(&mut self.scroller).spacing(2).push(heading),
+            |sc, x| sc.push(x.view())
+        )
+        .width(Length::Units(220))
+        .into()
+    }
+}
+
+fn find_entry(puzzle: &puzzle_backend::Puzzle, label: u32, variant: puzzle_backend::EntryVariant) -> Option<&puzzle_backend::PuzzleEntry> {
+    let entries = match variant {
+        puzzle_backend::EntryVariant::Across => &puzzle.across_entries,
+        puzzle_backend::EntryVariant::Down => &puzzle.down_entries,
+    };
+    entries.iter().find(|e| e.label == label)
+}
+
+// The letters already in an entry, or None if it runs through a rebus square.
+fn entry_pattern(puzzle: &puzzle_backend::Puzzle, members: &[usize]) -> Option<Vec<Option<u8>>> {
+    let mut pattern = Vec::with_capacity(members.len());
+    for i in members.iter() {
+        match &puzzle.squares[*i].content {
+            puzzle_backend::SquareContents::TextContent(s,_m) if s.is_empty() => pattern.push(None),
+            puzzle_backend::SquareContents::TextContent(s,_m) if s.len() == 1 => pattern.push(s.to_uppercase().bytes().next()),
+            _ => { return None; },
+        }
+    }
+    Some(pattern)
+}
+
+pub struct Suggestion {
+    button: button::State,
+    label: u32,
+    variant: puzzle_backend::EntryVariant,
+    word: String,
+    score: i32,
+    crossing_options: Option<usize>,
+}
+
+impl Suggestion {
+    fn new(label: u32, variant: puzzle_backend::EntryVariant, word: String, score: i32, crossing_options: Option<usize>) -> Self {
+        Suggestion {
+            button: Default::default(),
+            label,
+            variant,
+            word,
+            score,
+            crossing_options,
+        }
+    }
+
+    fn view(&mut self) -> Element<'_, central_ui::Message> {
+        let text = match self.crossing_options {
+            Some(n) => format!("{} ({}, {} crossing)", self.word, self.score, n),
+            None => format!("{} ({})", self.word, self.score),
+        };
+        Button::new(&mut self.button, Text::new(text).size(16))
+        .on_press(central_ui::Message::ApplySuggestion(self.label, self.variant, self.word.clone()))
+        .width(Length::Fill)
+        .into()
+    }
+}
\ No newline at end of file
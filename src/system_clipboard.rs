@@ -0,0 +1,78 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+// iced can read the clipboard but not write it, so both directions go through
+// whichever clipboard tool the platform has, the same way the file dialogs do.
+
+#[cfg(target_os = "macos")]
+fn copy_commands() -> Vec<(&'static str, Vec<&'static str>)> {
+    vec![("pbcopy", vec![])]
+}
+
+#[cfg(target_os = "macos")]
+fn paste_commands() -> Vec<(&'static str, Vec<&'static str>)> {
+    vec![("pbpaste", vec![])]
+}
+
+#[cfg(target_os = "windows")]
+fn copy_commands() -> Vec<(&'static str, Vec<&'static str>)> {
+    vec![("clip", vec![])]
+}
+
+#[cfg(target_os = "windows")]
+fn paste_commands() -> Vec<(&'static str, Vec<&'static str>)> {
+    vec![("powershell", vec!["-NoProfile", "-Command", "Get-Clipboard"])]
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn copy_commands() -> Vec<(&'static str, Vec<&'static str>)> {
+    let mut commands = vec![("xclip", vec!["-selection", "clipboard"]), ("xsel", vec!["--clipboard", "--input"])];
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        commands.insert(0, ("wl-copy", vec![]));
+    }
+    commands
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn paste_commands() -> Vec<(&'static str, Vec<&'static str>)> {
+    let mut commands = vec![("xclip", vec!["-selection", "clipboard", "-o"]), ("xsel", vec!["--clipboard", "--output"])];
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        commands.insert(0, ("wl-paste", vec!["--no-newline"]));
+    }
+    commands
+}
+
+fn no_tool_error() -> String {
+    let names: Vec<&str> = copy_commands().iter().map(|(c,_a)| *c).collect();
+    format!("no clipboard tool found (tried {})", names.join(", "))
+}
+
+pub fn copy_text(text: &str) -> std::result::Result<(), String> {
+    for (command, args) in copy_commands() {
+        let child = Command::new(command).args(&args).stdin(Stdio::piped()).stdout(Stdio::null()).stderr(Stdio::null()).spawn();
+        let mut child = match child {
+            Ok(c) => c,
+            Err(_e) => { continue; },
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes()).map_err(|e| e.to_string())?;
+        }
+        let status = child.wait().map_err(|e| e.to_string())?;
+        if status.success() {
+            return Ok(());
+        }
+    }
+    Err(no_tool_error())
+}
+
+pub fn paste_text() -> std::result::Result<String, String> {
+    for (command, args) in paste_commands() {
+        match Command::new(command).args(&args).stderr(Stdio::null()).output() {
+            Ok(out) if out.status.success() => {
+                return Ok(String::from_utf8_lossy(&out.stdout).to_string());
+            }
+            _ => (),
+        }
+    }
+    Err(no_tool_error())
+}
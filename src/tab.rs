@@ -0,0 +1,161 @@
+use iced::{Align, button, Button, Canvas, Column, Container, Element, Length, Row, Text};
+use crate::central_ui;
+use crate::clue_ui;
+use crosser_core::clue_database;
+use crosser_core::puzzle_backend;
+use crate::puzzle_canvas;
+use crate::recovery;
+use crate::solver_ui;
+use crate::suggestions_ui;
+use crosser_core::word_list;
+
+use std::rc::Rc;
+use std::cell::RefCell;
+
+// One open puzzle, the widgets showing it, and how it stands against the file it was saved to.
+pub struct Tab {
+    pub id: usize,
+    pub puzzle: Rc<RefCell<puzzle_backend::Puzzle>>,
+    pub puzzle_ui: puzzle_canvas::PuzzleCanvas,
+    pub clues: clue_ui::CluesBrowser,
+    pub suggestions: suggestions_ui::SuggestionsPanel,
+    pub solver: solver_ui::SolverRow,
+    // Whether validation problems are being shown, and kept current as the grid changes.
+    pub validating: bool,
+    // The file the puzzle was opened from or last saved to. Saving over it doesn't ask first.
+    pub file_path: Option<String>,
+    // The puzzle as it was opened or last saved. Restored work that was never saved has None, so it always counts as unsaved.
+    pub saved: Option<puzzle_backend::Snapshot>,
+    autosaved: Option<puzzle_backend::Snapshot>,
+    select_but: button::State,
+    close_but: button::State,
+}
+
+impl Tab {
+    pub fn new(id: usize, puzzle: puzzle_backend::Puzzle, file_path: Option<String>) -> Self {
+        let p = Rc::new(RefCell::new(puzzle));
+        let saved = Some(p.borrow().snapshot());
+        Tab {
+            id,
+            puzzle_ui: puzzle_canvas::PuzzleCanvas::new(p.clone()),
+            clues: clue_ui::CluesBrowser::new(p.clone()),
+            suggestions: suggestions_ui::SuggestionsPanel::new(p.clone()),
+            solver: solver_ui::SolverRow::new(),
+            puzzle: p,
+            validating: false,
+            file_path,
+            saved,
+            autosaved: None,
+            select_but: Default::default(),
+            close_but: Default::default(),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        self.file_path.as_ref()
+            .and_then(|p| std::path::Path::new(p).file_name())
+            .map(|f| f.to_string_lossy().to_string())
+            .unwrap_or_else(|| "Untitled".to_string())
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.saved.as_ref() != Some(&self.puzzle.borrow().snapshot())
+    }
+
+    // A tab nobody has touched yet, which opening a puzzle can reuse.
+    pub fn is_blank(&self) -> bool {
+        self.file_path.is_none() && !self.is_dirty()
+    }
+
+    pub fn mark_saved(&mut self, path: String) {
+        self.file_path = Some(path);
+        self.saved = Some(self.puzzle.borrow().snapshot());
+        recovery::clear(self.id);
+    }
+
+    // Writes the puzzle to its recovery file if it changed since the last autosave,
+    // and removes the file once there's nothing unsaved left to recover.
+    pub fn autosave(&mut self) {
+        if !self.is_dirty() {
+            if self.autosaved.take().is_some() {
+                recovery::clear(self.id);
+            }
+            return;
+        }
+        let snapshot = self.puzzle.borrow().snapshot();
+        if self.autosaved.as_ref() == Some(&snapshot) {
+            return;
+        }
+        if recovery::save(self.id, &self.puzzle.borrow(), self.file_path.as_deref()).is_ok() {
+            self.autosaved = Some(snapshot);
+        }
+    }
+
+    // The tab's header for the tab bar, and for the active tab the puzzle itself.
+    pub fn view(&mut self, active: bool, words: Option<&word_list::WordList>, clue_db: &clue_database::ClueDatabase) -> (Element<'_, central_ui::Message>, Option<Element<'_, central_ui::Message>>) {
+        let marker = if self.is_dirty() { "*" } else { "" };
+        let label = format!("{}{}", marker, self.name());
+        let Tab { id, puzzle, puzzle_ui, clues, suggestions, solver, select_but, close_but, .. } = self;
+
+        // The active tab is a plain label so it stands out from the buttons.
+        let title: Element<'_, central_ui::Message> = if active {
+            Container::new(Text::new(label)).padding(5).into()
+        } else {
+            Button::new(select_but, Text::new(label)).on_press(central_ui::Message::SelectTab(*id)).into()
+        };
+        let header = Row::new()
+        .spacing(2)
+        .push(
+            title
+        )
+        .push(
+            Button::new(close_but, Text::new("x")).on_press(central_ui::Message::CloseTab(*id))
+        )
+        .into();
+        if !active {
+            return (header, None);
+        }
+
+        // Every change to the grid or selection ends in a redraw, so this keeps the suggestions current.
+        suggestions.refresh(words, puzzle_ui.selected_entry());
+        clues.refresh_past_clues(clue_db, puzzle_ui.selected_entry());
+
+        let solving = puzzle.borrow().fill_only;
+        let revealed = puzzle.borrow().marks.iter().filter(|m| **m == Some(puzzle_backend::SolveMark::Revealed)).count();
+
+        // The grid is hidden while a solve is paused.
+        let grid: Element<'_, central_ui::Message> = if solving && solver.is_paused() {
+            Container::new(Text::new("Paused").size(40))
+            .height(Length::Fill)
+            .width(Length::FillPortion(2))
+            .center_x()
+            .center_y()
+            .into()
+        } else {
+            Canvas::new(puzzle_ui)
+            .height(Length::Fill)
+            .width(Length::FillPortion(2))
+            .into()
+        };
+
+        let mut body = Column::new();
+        if solving {
+            body = body.push(solver.view(revealed));
+        }
+        let body = body.push(
+            Row::new()
+            .padding(20)
+            .align_items(Align::Center)
+            .push(
+                grid
+            )
+            .push(
+                clues.view()
+            )
+            .push(
+                suggestions.view()
+            )
+        );
+        (header, Some(body.into()))
+    }
+}
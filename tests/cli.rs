@@ -0,0 +1,85 @@
+use std::process::{Command, Output};
+
+fn crosser(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_crosser")).args(args).output().unwrap()
+}
+
+fn corpus_path(name: &str) -> String {
+    format!("{}/crosser-core/tests/corpus/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn temp_path(name: &str) -> String {
+    std::env::temp_dir().join(format!("crosser-cli-{}-{}", std::process::id(), name)).to_string_lossy().to_string()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+#[test]
+fn info_describes_a_corpus_file() {
+    let output = crosser(&["info", &corpus_path("filled_mini.cro")]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    let info = stdout(&output);
+    assert!(info.contains("Author: A. Constructor\n"));
+    assert!(info.contains("Size: 5x5\n"));
+    assert!(info.contains("Words: 10\n"));
+    assert!(info.contains("Blockers: 0\n"));
+}
+
+#[test]
+fn validate_exits_with_the_verdict() {
+    let valid = crosser(&["validate", &corpus_path("empty_mini.cro")]);
+    assert_eq!(valid.status.code(), Some(0), "{}", stderr(&valid));
+    assert_eq!(stdout(&valid), "Grid meets publication rules.\n");
+
+    // Every word in a word square is used twice.
+    let invalid = crosser(&["validate", &corpus_path("filled_mini.cro")]);
+    assert_eq!(invalid.status.code(), Some(1));
+    assert!(stdout(&invalid).contains("HEART is used by 1A, 1D\n"));
+    assert_eq!(stderr(&invalid), "5 problem(s) found.\n");
+}
+
+#[test]
+fn missing_files_fail() {
+    let output = crosser(&["info", &corpus_path("no_such_file.cro")]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr(&output).starts_with("Could not open "));
+}
+
+#[test]
+fn bad_arguments_print_usage() {
+    let path = corpus_path("empty_mini.cro");
+    for args in [
+        vec!["info"],
+        vec!["info", path.as_str(), path.as_str()],
+        vec!["info", path.as_str(), "--fill"],
+        vec!["validate", path.as_str(), "--width"],
+        vec!["export", path.as_str(), "out.pdf", "--font", "comic-sans"],
+    ] {
+        let output = crosser(&args);
+        assert_eq!(output.status.code(), Some(2), "{:?}", args);
+        assert!(stderr(&output).contains("Usage: crosser [COMMAND]"), "{:?}", args);
+    }
+}
+
+#[test]
+fn only_custom_grids_take_a_size() {
+    let path = temp_path("new.cro");
+    let sized_mini = crosser(&["new", "mini", &path, "--width", "4"]);
+    assert_eq!(sized_mini.status.code(), Some(2));
+    assert!(stderr(&sized_mini).starts_with("Only custom grids take --width and --height.\n"));
+    let unsized_custom = crosser(&["new", "custom", &path, "--width", "4"]);
+    assert_eq!(unsized_custom.status.code(), Some(2));
+    assert!(std::fs::metadata(&path).is_err());
+
+    let custom = crosser(&["new", "custom", &path, "--width", "4", "--height=3"]);
+    assert_eq!(custom.status.code(), Some(0), "{}", stderr(&custom));
+    let info = crosser(&["info", &path]);
+    std::fs::remove_file(&path).ok();
+    assert!(stdout(&info).contains("Size: 4x3\n"));
+}